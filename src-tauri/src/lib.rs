@@ -2,12 +2,12 @@ use base64::{engine::general_purpose::STANDARD, Engine};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::io::{Cursor, Read};
-use std::path::PathBuf;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
 use tauri::{
     menu::{Menu, MenuItem, Submenu},
     tray::TrayIconBuilder,
-    Emitter, Manager, WindowEvent,
+    AppHandle, Emitter, Manager, WindowEvent,
 };
 
 // ============================================================================
@@ -39,12 +39,68 @@ impl Default for AgentType {
     }
 }
 
+impl AgentType {
+    /// Serialized id, as used by the frontend
+    pub fn id(&self) -> &'static str {
+        match self {
+            AgentType::All => "all",
+            AgentType::Claude => "claude",
+            AgentType::Gemini => "gemini",
+            AgentType::Codex => "codex",
+            AgentType::Opencode => "opencode",
+            AgentType::Kiro => "kiro",
+            AgentType::Antigravity => "antigravity",
+            AgentType::Codebuddy => "codebuddy",
+            AgentType::Cursor => "cursor",
+            AgentType::Kimi => "kimi",
+            AgentType::Moltbot => "moltbot",
+            AgentType::Qoder => "qoder",
+            AgentType::Qwen => "qwen",
+            AgentType::Zencoder => "zencoder",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<AgentType> {
+        get_all_individual_agents()
+            .into_iter()
+            .chain(std::iter::once(AgentType::All))
+            .find(|a| a.id() == id)
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct AgentInfo {
     pub id: String,
-    pub name: String,
     pub skills_path: String,
     pub has_mcp: bool,
+    #[serde(flatten)]
+    pub metadata: AgentMetadata,
+}
+
+/// How an agent is presented in the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentMetadata {
+    pub name: &'static str,
+    /// Letter shown in the agent picker in place of a logo
+    pub monogram: &'static str,
+    pub homepage: Option<&'static str>,
+    pub docs_url: Option<&'static str>,
+    /// Extension of the agent's config file (`json`, `toml`), if it has one
+    pub config_format: Option<String>,
+}
+
+/// Where an installed skill came from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    DirectUrl,
+    GithubDir,
+    GithubBlob,
+    Gist,
+    Zip,
+    Local,
+    Registry,
+    Oci,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -52,6 +108,87 @@ pub struct SkillInfo {
     pub name: String,
     pub path: String,
     pub token_count: Option<u64>,
+    /// Subdirectory of the skills dir the skill lives in, if any
+    pub namespace: Option<String>,
+    /// From the frontmatter, on one line and cut to `LIST_DESCRIPTION_MAX`
+    pub description: Option<String>,
+    /// Shipped with the agent; listed read-only and only deleted when forced
+    pub builtin: bool,
+    /// Latest invocation found in the agent's session logs; `None` for
+    /// agents without such logs and skills never seen there
+    pub last_used_at: Option<String>,
+    /// Marked with `set_skill_favorite`
+    pub favorite: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentError {
+    pub agent: AgentType,
+    pub error: String,
+}
+
+/// A skill's SKILL.md as the viewer shows it
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillContent {
+    pub content: String,
+    /// Encoding the file was decoded from, e.g. `UTF-8` or `windows-1252`
+    pub encoding: String,
+    /// Size of the file on disk
+    pub byte_size: u64,
+    pub token_count: u64,
+    /// `content` was cut to `SKILL_CONTENT_MAX_BYTES`
+    pub truncated: bool,
+    /// Some bytes couldn't be decoded and were replaced
+    pub lossy: bool,
+}
+
+/// Where a skill's context cost goes, from `analyze_skill_content`. Some
+/// agents strip the frontmatter before injecting a skill, so for them
+/// `body_tokens` is the real cost.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillContentAnalysis {
+    pub full_tokens: u64,
+    /// Everything after the frontmatter block
+    pub body_tokens: u64,
+    /// The frontmatter block including its `---` fences; 0 without one
+    pub frontmatter_tokens: u64,
+    pub has_frontmatter: bool,
+}
+
+/// Everything the in-app read-only preview shows, from `get_skill_preview`
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillPreview {
+    pub content: SkillContent,
+    /// SKILL.md after its frontmatter block
+    pub body: String,
+    /// `body` rendered by `render_markdown_html`, safe to insert as is
+    pub html: String,
+    /// The frontmatter parsed from YAML; `null` when there is none or it
+    /// doesn't parse
+    pub frontmatter: serde_json::Value,
+    /// Every content file, sorted by path, as `skill_content_files` lists them
+    pub files: Vec<FileItem>,
+    pub metadata: Option<SkillMetadata>,
+}
+
+/// Skills plus any agents whose skills dir couldn't be read
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillList {
+    /// Empty when `groups` is set
+    pub skills: Vec<SkillInfo>,
+    /// Set by `list_skills` in grouped mode
+    pub groups: Option<SkillGroups>,
+    pub errors: Vec<AgentError>,
+}
+
+/// A listing split for the main screen; each skill is in one group only,
+/// favorites taking precedence over recent
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SkillGroups {
+    pub favorites: Vec<SkillInfo>,
+    /// Installed in the last `RECENT_INSTALL_DAYS`, newest first
+    pub recent: Vec<SkillInfo>,
+    pub others: Vec<SkillInfo>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -69,12 +206,151 @@ pub struct SkillMetadata {
     pub source: Option<String>,
     pub version: Option<String>,
     pub author: Option<String>,
+    /// From the `license:` frontmatter field
+    pub license: Option<String>,
+    /// How `source` should be re-fetched; absent in metadata from older versions
+    pub source_kind: Option<SourceKind>,
     pub installed_at: String,
     pub updated_at: String,
+    /// Pinned skills are left alone by updates, deletes and bulk operations
+    /// unless they're forced
+    #[serde(default)]
+    pub pinned: bool,
+    /// `hash_skill_dir` of the skill as installed, to spot local edits later
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// The user's own annotations, kept across updates
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Installed with `skill_md_only`; reinstalls keep the same mode
+    #[serde(default)]
+    pub skill_md_only: bool,
+    /// Source file installed as SKILL.md, if it wasn't SKILL.md itself
+    #[serde(default)]
+    pub entrypoint: Option<String>,
+    /// Files `externalize_skill_assets` moved under `assets/`, by their
+    /// original path
+    #[serde(default)]
+    pub externalized_assets: Vec<String>,
+    /// Listed first by `list_skills`; kept across updates
+    #[serde(default)]
+    pub favorite: bool,
+}
+
+/// Local references found in a skill's markdown
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SkillDeps {
+    /// Paths relative to the skill dir
+    pub files_found: Vec<String>,
+    pub files_missing: Vec<String>,
+    /// Sibling skills linked as `../<name>/...`
+    pub skills_installed: Vec<String>,
+    pub skills_missing: Vec<String>,
+}
+
+/// MCP servers a skill's frontmatter asks for, checked against the agent's
+/// config
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct McpRequirements {
+    pub present: Vec<String>,
+    pub missing: Vec<String>,
+    /// Configured but turned off
+    pub disabled: Vec<String>,
+}
+
+/// Everything a skill list row shows as badges, from `get_skill_badges`
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillBadges {
+    /// The frontmatter names MCP servers under `mcp:` or `requires_mcp:`
+    pub has_mcp_requirement: bool,
+    /// Has a `scripts/` folder or executable files
+    pub has_scripts: bool,
+    pub is_pinned: bool,
+    /// Whether the source's SKILL.md differs from the installed one; `None`
+    /// unless asked for and the source has a single SKILL.md to compare, or
+    /// when fetching it fails
+    pub update_available: Option<bool>,
+    /// A dev link or otherwise symlinked skill
+    pub is_symlink: bool,
+    pub token_tier: TokenTier,
+}
+
+/// Size class of a skill's SKILL.md, split at `TOKEN_TIER_SMALL_MAX` and
+/// `TOKEN_TIER_MEDIUM_MAX`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenTier {
+    Small,
+    Medium,
+    Large,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenRecountProgress {
+    pub agent: AgentType,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Token footprint of the skills an agent loads
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenCostSummary {
+    pub total_tokens: u64,
+    pub skill_count: usize,
+    /// Heaviest first
+    pub heaviest: Vec<SkillTokenCost>,
+    pub context_window: u64,
+    /// `total_tokens` as a fraction of `context_window`
+    pub window_fraction: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillTokenCost {
+    pub agent: AgentType,
+    pub name: String,
+    pub namespace: Option<String>,
+    pub tokens: u64,
+}
+
+/// Result of checking a skill before it's shared
+#[derive(Debug, Clone, Serialize)]
+pub struct ShareReport {
+    pub name: String,
+    /// What a shared copy contains, after exclusions
+    pub files: Vec<String>,
+    pub token_count: u64,
+    /// Frontmatter problems, which `fix` can't repair
+    pub warnings: Vec<String>,
+    /// OS clutter such as `.DS_Store`
+    pub junk_files: Vec<String>,
+    /// Files that typically hold credentials (`.env`, keys), left out of the
+    /// shared copy
+    pub secret_files: Vec<String>,
+    /// `path:line` of lines that look like they contain a credential
+    pub possible_secrets: Vec<String>,
+    /// Text files with CRLF line endings
+    pub crlf_files: Vec<String>,
+    /// Whether junk and line endings were fixed on disk
+    pub fixed: bool,
+    /// Base64 `.skill` file of the cleaned copy, when requested
+    pub export: Option<String>,
+}
+
+/// What updating an installed skill would run into
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdatePreview {
+    pub name: String,
+    pub source: Option<String>,
+    pub pinned: bool,
+    /// The files differ from what was installed; updating moves this copy
+    /// to trash
+    pub has_local_changes: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct McpServerInfo {
+    /// Whose config the server is in; `All` listings mix agents
+    pub agent: AgentType,
     pub name: String,
     pub transport: String,
     pub disabled: Option<bool>,
@@ -85,6 +361,51 @@ pub struct McpServerInfo {
     pub headers: Option<HashMap<String, String>>,
 }
 
+/// Servers plus any agents whose MCP config couldn't be read
+#[derive(Debug, Clone, Serialize)]
+pub struct McpServerList {
+    pub servers: Vec<McpServerInfo>,
+    pub errors: Vec<AgentError>,
+}
+
+/// One server entry in one agent's config
+#[derive(Debug, Clone, Serialize)]
+pub struct McpServerRef {
+    pub agent: AgentType,
+    pub name: String,
+}
+
+/// Servers with the same definition in more than one agent. Names can
+/// differ; the same name appearing in two groups means it has drifted.
+#[derive(Debug, Clone, Serialize)]
+pub struct McpDupGroup {
+    pub server: McpServerInfo,
+    pub members: Vec<McpServerRef>,
+}
+
+/// `validate_all_mcp_servers`' verdict on one server
+#[derive(Debug, Clone, Serialize)]
+pub struct McpValidation {
+    pub agent: AgentType,
+    pub name: String,
+    pub transport: String,
+    pub disabled: bool,
+    pub ok: bool,
+    /// Where the command was found on `PATH`, or the URL checked
+    pub resolved: Option<String>,
+    pub problem: Option<String>,
+}
+
+/// Which servers `list_mcp_servers` returns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpFilter {
+    #[default]
+    All,
+    Enabled,
+    Disabled,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct AddMcpServerRequest {
     pub name: String,
@@ -102,813 +423,8188 @@ pub struct SearchSkill {
     pub slug: String,
     pub source: String,
     pub installs: u64,
+    pub license: Option<String>,
+    /// From the registry when it reports one
+    pub token_estimate: Option<u64>,
 }
 
-// ============================================================================
-// Paths
-// ============================================================================
-
-/// Returns all individual agent types (excluding All)
-fn get_all_individual_agents() -> Vec<AgentType> {
-    vec![
-        AgentType::Claude,
-        AgentType::Gemini,
-        AgentType::Codex,
-        AgentType::Opencode,
-        AgentType::Kiro,
-        AgentType::Antigravity,
-        AgentType::Codebuddy,
-        AgentType::Cursor,
-        AgentType::Kimi,
-        AgentType::Moltbot,
-        AgentType::Qoder,
-        AgentType::Qwen,
-        AgentType::Zencoder,
-    ]
+/// What to do when the target skill folder already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    /// Move the existing skill to the trash and install over it
+    #[default]
+    Replace,
+    /// Keep the existing skill and install nothing
+    Skip,
+    /// Install nothing if the existing skill differs; report a conflict
+    Ask,
 }
 
-fn get_skills_dir(agent: AgentType) -> Result<PathBuf, String> {
-    let home = dirs::home_dir().ok_or("Cannot find home directory")?;
-    match agent {
-        AgentType::All => Err("Cannot get skills dir for All agent".to_string()),
-        AgentType::Claude => Ok(home.join(".claude").join("skills")),
-        AgentType::Gemini => Ok(home.join(".gemini").join("skills")),
-        AgentType::Codex => Ok(home.join(".codex").join("skills")),
-        AgentType::Opencode => Ok(home.join(".config").join("opencode").join("skills")),
-        AgentType::Kiro => Ok(home.join(".kiro").join("skills")),
-        AgentType::Antigravity => Ok(home.join(".gemini").join("antigravity").join("global_skills")),
-        AgentType::Codebuddy => Ok(home.join(".codebuddy").join("skills")),
-        AgentType::Cursor => Ok(home.join(".cursor").join("skills")),
-        AgentType::Kimi => Ok(home.join(".kimi").join("skills")),
-        AgentType::Moltbot => Ok(home.join(".moltbot").join("skills")),
-        AgentType::Qoder => Ok(home.join(".qoder").join("skills")),
-        AgentType::Qwen => Ok(home.join(".qwen").join("skills")),
-        AgentType::Zencoder => Ok(home.join(".zencoder").join("skills")),
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallStatus {
+    Installed,
+    Replaced,
+    Skipped,
+    Conflict,
+    /// Writing to this agent failed; the error is the only warning
+    Failed,
 }
 
-fn get_mcp_config_path(agent: AgentType) -> Result<PathBuf, String> {
-    let home = dirs::home_dir().ok_or("Cannot find home directory")?;
-    match agent {
-        AgentType::Claude => Ok(home.join(".claude.json")),
-        AgentType::Gemini => Ok(home.join(".gemini").join("settings.json")),
-        AgentType::Codex => Ok(home.join(".codex").join("config.toml")),
-        AgentType::Opencode => Ok(home.join(".config").join("opencode").join("config.json")),
-        AgentType::Kiro => Ok(home.join(".kiro").join("settings.json")),
-        // These agents don't have MCP support
-        AgentType::All | AgentType::Antigravity | AgentType::Codebuddy | AgentType::Cursor |
-        AgentType::Kimi | AgentType::Moltbot | AgentType::Qoder |
-        AgentType::Qwen | AgentType::Zencoder => Err("MCP not supported for this agent".to_string()),
-    }
+/// Optional knobs shared by the install commands
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstallOptions {
+    /// Install under `<skills dir>/<namespace>/` instead of the flat layout
+    pub namespace: Option<String>,
+    pub on_conflict: ConflictPolicy,
+    /// Replace the skill even if it's pinned
+    pub force: bool,
+    /// Keep only SKILL.md and the files it links to directly
+    pub skill_md_only: bool,
+    /// Source file to install as SKILL.md, for repos that name it
+    /// differently (`AGENT.md`, `README.md`)
+    pub entrypoint: Option<String>,
 }
 
-fn agent_has_mcp_support(agent: AgentType) -> bool {
-    matches!(
-        agent,
-        AgentType::Claude | AgentType::Gemini | AgentType::Opencode | AgentType::Kiro
-    )
+/// A skill's state for agents that switch skills on and off in their
+/// config, where being installed doesn't make a skill active
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillEnabledState {
+    pub name: String,
+    /// A folder for it exists in the skills dir
+    pub installed: bool,
+    pub enabled: bool,
 }
 
-// ============================================================================
-// Agent Commands
-// ============================================================================
+/// Where an `All` install would write for one agent
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallTarget {
+    pub agent: AgentType,
+    pub path: String,
+    /// A skill is already at `path`
+    pub exists: bool,
+    pub pinned: bool,
+}
 
-#[tauri::command]
-fn list_agents() -> Result<Vec<AgentInfo>, String> {
-    let home = dirs::home_dir().ok_or("Cannot find home directory")?;
-
-    let agents = vec![
-        AgentInfo {
-            id: "claude".to_string(),
-            name: "Claude Code".to_string(),
-            skills_path: home.join(".claude").join("skills").to_string_lossy().to_string(),
-            has_mcp: true,
-        },
-        AgentInfo {
-            id: "gemini".to_string(),
-            name: "Gemini CLI".to_string(),
-            skills_path: home.join(".gemini").join("skills").to_string_lossy().to_string(),
-            has_mcp: true,
-        },
-        AgentInfo {
-            id: "codex".to_string(),
-            name: "Codex CLI".to_string(),
-            skills_path: home.join(".codex").join("skills").to_string_lossy().to_string(),
-            has_mcp: false,
-        },
-        AgentInfo {
-            id: "opencode".to_string(),
-            name: "OpenCode".to_string(),
-            skills_path: home.join(".config").join("opencode").join("skills").to_string_lossy().to_string(),
-            has_mcp: true,
-        },
-        AgentInfo {
-            id: "kiro".to_string(),
-            name: "Kiro CLI".to_string(),
-            skills_path: home.join(".kiro").join("skills").to_string_lossy().to_string(),
-            has_mcp: true,
-        },
-        AgentInfo {
-            id: "antigravity".to_string(),
-            name: "Antigravity".to_string(),
-            skills_path: home.join(".gemini").join("antigravity").join("global_skills").to_string_lossy().to_string(),
-            has_mcp: false,
-        },
-        AgentInfo {
-            id: "codebuddy".to_string(),
-            name: "CodeBuddy".to_string(),
-            skills_path: home.join(".codebuddy").join("skills").to_string_lossy().to_string(),
-            has_mcp: false,
-        },
-        AgentInfo {
-            id: "cursor".to_string(),
-            name: "Cursor".to_string(),
-            skills_path: home.join(".cursor").join("skills").to_string_lossy().to_string(),
-            has_mcp: false,
-        },
-        AgentInfo {
-            id: "kimi".to_string(),
-            name: "Kimi CLI".to_string(),
-            skills_path: home.join(".kimi").join("skills").to_string_lossy().to_string(),
-            has_mcp: false,
-        },
-        AgentInfo {
-            id: "moltbot".to_string(),
-            name: "Moltbot".to_string(),
-            skills_path: home.join(".moltbot").join("skills").to_string_lossy().to_string(),
-            has_mcp: false,
-        },
-        AgentInfo {
-            id: "qoder".to_string(),
-            name: "Qoder".to_string(),
-            skills_path: home.join(".qoder").join("skills").to_string_lossy().to_string(),
-            has_mcp: false,
-        },
-        AgentInfo {
-            id: "qwen".to_string(),
-            name: "Qwen Code".to_string(),
-            skills_path: home.join(".qwen").join("skills").to_string_lossy().to_string(),
-            has_mcp: false,
-        },
-        AgentInfo {
-            id: "zencoder".to_string(),
-            name: "Zencoder".to_string(),
-            skills_path: home.join(".zencoder").join("skills").to_string_lossy().to_string(),
-            has_mcp: false,
-        },
-    ];
+/// What deleting a skill would remove for one agent
+#[derive(Debug, Clone, Serialize)]
+pub struct DeletePreview {
+    pub agent: AgentType,
+    pub path: String,
+    pub exists: bool,
+    pub size_bytes: u64,
+    pub file_count: usize,
+    /// Only the link is removed, not what it points to
+    pub is_symlink: bool,
+    /// Skipped unless the delete is forced
+    pub pinned: bool,
+    pub builtin: bool,
+}
 
-    Ok(agents)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeleteStatus {
+    Deleted,
+    /// Pinned or built in, and the delete wasn't forced
+    Skipped,
+    Failed,
 }
 
-// ============================================================================
-// Skills Commands
-// ============================================================================
+/// Outcome of deleting a skill from one agent
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteResult {
+    pub agent: AgentType,
+    pub status: DeleteStatus,
+    /// Why it was skipped, or the error
+    pub reason: Option<String>,
+}
 
-#[tauri::command]
-fn list_skills(agent: AgentType) -> Result<Vec<SkillInfo>, String> {
-    // Handle "All" agent - combine skills from all agents
-    if agent == AgentType::All {
-        let mut all_skills = Vec::new();
-        let mut seen_names = std::collections::HashSet::new();
+/// Outcome of installing a skill into one agent
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallResult {
+    pub agent: AgentType,
+    pub name: String,
+    pub path: String,
+    pub status: InstallStatus,
+    /// Paths written, relative to the skill directory
+    pub files: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Output of the post-install hook, if one ran
+    pub hook: Option<HookOutput>,
+}
 
-        for individual_agent in get_all_individual_agents() {
-            if let Ok(skills) = list_skills_for_agent(individual_agent) {
-                for skill in skills {
-                    // Deduplicate by name (same skill might be in multiple agents)
-                    if seen_names.insert(skill.name.clone()) {
-                        all_skills.push(skill);
-                    }
-                }
-            }
-        }
+/// One skill of a multi-skill install: a directory
+/// `install_skills_from_github_glob` matched, or a skill in a ZIP
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobInstallResult {
+    /// The skill directory, relative to the repo root, or the skill's name
+    /// in a ZIP
+    pub path: String,
+    pub results: Vec<InstallResult>,
+    /// Why this skill wasn't installed; the others still go ahead
+    pub error: Option<String>,
+}
 
-        all_skills.sort_by(|a, b| a.name.cmp(&b.name));
-        return Ok(all_skills);
-    }
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobInstallProgress {
+    pub agent: AgentType,
+    /// The skill directory that just finished
+    pub path: String,
+    pub done: usize,
+    pub total: usize,
+}
 
-    list_skills_for_agent(agent)
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentDiagnostics {
+    pub agent: AgentType,
+    pub skills_path: String,
+    pub detected: bool,
+    pub skills_dir_writable: bool,
 }
 
-fn list_skills_for_agent(agent: AgentType) -> Result<Vec<SkillInfo>, String> {
-    let skills_dir = get_skills_dir(agent)?;
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostics {
+    pub app_version: String,
+    pub os: String,
+    pub home_dir: Option<String>,
+    pub agents: Vec<AgentDiagnostics>,
+    pub registry_reachable: bool,
+    pub github_rate_limit_remaining: Option<u64>,
+}
 
-    if !skills_dir.exists() {
-        return Ok(vec![]);
-    }
+/// First-run health check from `preflight`, also sent as
+/// `preflight-complete` when the app starts
+#[derive(Debug, Clone, Serialize)]
+pub struct PreflightReport {
+    pub data_dir: String,
+    /// The data dir exists (created if needed) and accepts files
+    pub data_dir_writable: bool,
+    pub data_dir_error: Option<String>,
+    /// `PREFLIGHT_AGENT`'s skills dir
+    pub skills_dir: String,
+    pub skills_dir_writable: bool,
+    pub network_reachable: bool,
+    /// Both directories are usable; the network isn't required
+    pub ok: bool,
+}
 
-    let mut skills = Vec::new();
-    let entries = fs::read_dir(&skills_dir).map_err(|e| e.to_string())?;
+#[derive(Debug, Clone, Serialize)]
+pub struct ZipEntryInfo {
+    pub path: String,
+    pub size: u64,
+    pub is_directory: bool,
+}
 
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() {
-            let name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("")
-                .to_string();
+/// What installing a ZIP would do, computed without extracting it
+#[derive(Debug, Clone, Serialize)]
+pub struct ZipPreview {
+    pub name: Option<String>,
+    pub warnings: Vec<String>,
+    pub entries: Vec<ZipEntryInfo>,
+    /// Total uncompressed size in bytes
+    pub total_size: u64,
+    pub red_flags: Vec<String>,
+}
 
-            if name.starts_with('.') {
-                continue;
-            }
+/// `manifest.json` at the root of an exported bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: String,
+    pub skills: Vec<BundleSkill>,
+    pub mcp_agents: Vec<AgentType>,
+    pub app_files: Vec<String>,
+}
 
-            let skill_md = find_skill_md(&path);
-            let token_count = skill_md
-                .as_ref()
-                .and_then(|p| fs::metadata(p).ok().map(|m| m.len() / 4));
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleSkill {
+    pub agent: AgentType,
+    pub name: String,
+    /// Bundled under `skills/<agent>/<namespace>/<name>/`; older bundles
+    /// have none
+    #[serde(default)]
+    pub namespace: Option<String>,
+}
 
-            skills.push(SkillInfo {
-                name,
-                path: path.to_string_lossy().to_string(),
-                token_count,
-            });
+impl BundleSkill {
+    /// The skill's folder in the bundle, relative to `skills/`
+    fn bundle_dir(&self) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}/{}/{}", self.agent.id(), namespace, self.name),
+            None => format!("{}/{}", self.agent.id(), self.name),
         }
     }
-
-    skills.sort_by(|a, b| a.name.cmp(&b.name));
-    Ok(skills)
 }
 
-#[tauri::command]
-fn get_skill_content(agent: AgentType, name: String) -> Result<String, String> {
-    let skills_dir = get_skills_dir(agent)?;
-    let skill_dir = skills_dir.join(&name);
-
-    let skill_md =
-        find_skill_md(&skill_dir).ok_or_else(|| format!("SKILL.md not found in {}", name))?;
-
-    fs::read_to_string(skill_md).map_err(|e| e.to_string())
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleExport {
+    /// Base64 ZIP
+    pub bundle: String,
+    pub warnings: Vec<String>,
 }
 
-#[tauri::command]
-fn get_skill_metadata(agent: AgentType, name: String) -> Result<Option<SkillMetadata>, String> {
-    let skills_dir = get_skills_dir(agent)?;
-    let metadata_path = skills_dir.join(&name).join(".metadata.json");
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ImportBundleOptions {
+    pub skills: bool,
+    pub mcp: bool,
+    pub app_files: bool,
+    /// Replace skills, servers and files that already exist
+    pub overwrite: bool,
+}
 
-    if !metadata_path.exists() {
-        return Ok(None);
+impl Default for ImportBundleOptions {
+    fn default() -> Self {
+        ImportBundleOptions {
+            skills: true,
+            mcp: true,
+            app_files: true,
+            overwrite: false,
+        }
     }
+}
 
-    let content = fs::read_to_string(&metadata_path).map_err(|e| e.to_string())?;
-    let metadata: SkillMetadata = serde_json::from_str(&content).map_err(|e| e.to_string())?;
-    Ok(Some(metadata))
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportBundleReport {
+    pub skills_imported: Vec<BundleSkill>,
+    pub skills_skipped: Vec<BundleSkill>,
+    pub mcp_servers_imported: usize,
+    pub app_files_imported: usize,
+    pub warnings: Vec<String>,
 }
 
-#[tauri::command]
-fn list_skill_files(agent: AgentType, name: String, subpath: Option<String>) -> Result<Vec<FileItem>, String> {
-    let skills_dir = get_skills_dir(agent)?;
-    let mut target_dir = skills_dir.join(&name);
+/// A working directory symlinked into an agent's skills dir
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevLink {
+    pub agent: AgentType,
+    pub name: String,
+    pub source_dir: String,
+    pub linked_at: String,
+}
 
-    if let Some(sub) = subpath {
-        target_dir = target_dir.join(sub);
-    }
+/// Payload of the `dev-skill-changed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct DevSkillChangedEvent {
+    pub agent: AgentType,
+    pub name: String,
+    pub paths: Vec<String>,
+}
 
-    if !target_dir.exists() {
-        return Ok(vec![]);
+/// Payload of the `skill-overwritten` event
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillOverwrittenEvent {
+    pub agent: AgentType,
+    pub name: String,
+    pub previous: Option<SkillMetadata>,
+    pub trash_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashItem {
+    pub id: String,
+    pub agent: AgentType,
+    pub name: String,
+    /// The namespace the skill was trashed from, and is restored into
+    pub namespace: Option<String>,
+    pub trashed_at: String,
+    pub path: String,
+}
+
+/// A skill-related error line found in an agent's own log
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillLoadError {
+    /// Installed skill the line mentions, if it could be matched
+    pub skill: Option<String>,
+    pub message: String,
+    pub log_file: String,
+    pub line: usize,
+}
+
+/// A GitHub install that stopped partway; `resume_install(token)` continues
+/// it, reusing the files already downloaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingInstall {
+    pub token: String,
+    pub url: String,
+    pub agent: AgentType,
+    pub options: InstallOptions,
+    pub started_at: String,
+}
+
+/// `skill.json` at the root of a `.skill` file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillFileManifest {
+    pub format_version: u32,
+    pub name: String,
+    pub version: Option<String>,
+    /// `sha256:<hex>` of the other files, as `get_skill_hash` computes it
+    pub checksum: String,
+}
+
+/// Every skill name across agents, with each agent's copy
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillMatrix {
+    pub agents: Vec<AgentType>,
+    pub rows: Vec<SkillMatrixRow>,
+    pub errors: Vec<AgentError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillMatrixRow {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub copies: Vec<SkillCopy>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillCopy {
+    pub agent: AgentType,
+    pub path: String,
+    pub token_count: Option<u64>,
+    pub metadata: Option<SkillMetadata>,
+}
+
+/// Result of checking a local folder before installing from it
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillDirCheck {
+    pub is_skill: bool,
+    pub skill_md: Option<String>,
+    pub name: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// What `clear_all_skills` would remove, and the token that authorizes it
+#[derive(Debug, Clone, Serialize)]
+pub struct ClearPlan {
+    pub agent: AgentType,
+    pub skills: Vec<SkillInfo>,
+    /// Pinned skills that will be kept
+    pub pinned: Vec<SkillInfo>,
+    pub confirm_token: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClearResult {
+    pub moved_to_trash: usize,
+    pub failed: usize,
+    pub skipped_pinned: usize,
+    pub errors: Vec<String>,
+}
+
+/// What `cleanup` removed, as paths
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CleanupReport {
+    /// Skill dirs left holding nothing but `.metadata.json`
+    pub removed_metadata: Vec<String>,
+    pub removed_empty_dirs: Vec<String>,
+    /// Install staging dirs and partial downloads
+    pub removed_temp: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl CleanupReport {
+    fn removed_count(&self) -> usize {
+        self.removed_metadata.len() + self.removed_empty_dirs.len() + self.removed_temp.len()
+    }
+}
+
+/// What `fix_skills_permissions` changed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FixReport {
+    pub changed: Vec<PermissionChange>,
+    pub errors: Vec<String>,
+    /// Set when there was nothing this platform could do
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionChange {
+    pub path: String,
+    /// Unix permission bits before and after
+    pub from: u32,
+    pub to: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RenameResult {
+    pub agent: AgentType,
+    pub namespace: Option<String>,
+    pub from: String,
+    pub to: String,
+    pub renamed: bool,
+    /// Set when the target name was taken or the rename failed
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    Install,
+    DiscardInstall,
+    Delete,
+    Clear,
+    Cleanup,
+    Rename,
+    Restore,
+    AddMcpServer,
+    RemoveMcpServer,
+    ToggleMcpServer,
+    EditMcpConfig,
+    ImportBundle,
+    UpdateSettings,
+    LinkDevSkill,
+    UnlinkDevSkill,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", content = "message", rename_all = "snake_case")]
+pub enum ActivityResult {
+    Ok,
+    Error(String),
+}
+
+/// One entry in the local activity log, emitted as `activity` and kept in
+/// the history file. Never leaves the machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    pub kind: ActivityKind,
+    pub agent: Option<AgentType>,
+    /// Skill name, server name, URL or setting the operation acted on
+    pub target: String,
+    pub result: ActivityResult,
+    /// Extra outcome info, e.g. an install status or counts
+    pub detail: Option<String>,
+    pub timestamp: String,
+}
+
+impl ActivityEvent {
+    fn new<T>(
+        kind: ActivityKind,
+        agent: Option<AgentType>,
+        target: impl Into<String>,
+        result: &Result<T, String>,
+    ) -> Self {
+        ActivityEvent {
+            kind,
+            agent,
+            target: target.into(),
+            result: match result {
+                Ok(_) => ActivityResult::Ok,
+                Err(e) => ActivityResult::Error(e.clone()),
+            },
+            detail: None,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// What `read_skill_usage` has already taken from each transcript, saved
+/// in the data dir so a listing only reads what was appended since
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SkillUsageCache {
+    /// By transcript path
+    transcripts: HashMap<String, TranscriptUsage>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TranscriptUsage {
+    /// Modification time in milliseconds when last read
+    modified: u64,
+    /// Bytes read so far, always at a line break
+    read_to: u64,
+    /// Latest call of each skill in the part read, by the name it was
+    /// called with
+    last_used: HashMap<String, String>,
+}
+
+/// A file about to be written into a skill directory
+#[derive(Clone)]
+struct SkillFile {
+    /// Relative to the skill directory, `/`-separated
+    path: String,
+    content: Vec<u8>,
+    /// Unix permission bits from the source, if known
+    mode: Option<u32>,
+}
+
+impl SkillFile {
+    fn new(path: &str, content: Vec<u8>) -> Self {
+        SkillFile {
+            path: path.to_string(),
+            content,
+            mode: None,
+        }
+    }
+}
+
+/// Why a registry search failed. Serialized with a `kind` tag so the UI can
+/// back off on rate limits instead of showing "no results".
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SearchError {
+    RateLimited { retry_after_secs: u64 },
+    ServiceUnavailable { status: u16, retry_after_secs: u64 },
+    Other { message: String },
+}
+
+impl From<String> for SearchError {
+    fn from(message: String) -> Self {
+        SearchError::Other { message }
+    }
+}
+
+impl std::fmt::Display for SearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchError::RateLimited { retry_after_secs } => {
+                write!(f, "Registry rate limit reached; try again in {}s", retry_after_secs)
+            }
+            SearchError::ServiceUnavailable { status, retry_after_secs } => write!(
+                f,
+                "Registry unavailable (HTTP {}); try again in {}s",
+                status, retry_after_secs
+            ),
+            SearchError::Other { message } => f.write_str(message),
+        }
+    }
+}
+
+/// `app_home` found no home directory: none of the variables it reads are
+/// set and the OS doesn't report one either. Converts into the `String`
+/// errors commands return.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoHomeError;
+
+impl std::fmt::Display for NoHomeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cannot find home directory; set {} to the folder to use", APP_HOME_ENV)
+    }
+}
+
+impl std::error::Error for NoHomeError {}
+
+impl From<NoHomeError> for String {
+    fn from(e: NoHomeError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Skill folders with an install in progress. A second concurrent install
+/// into the same folder is refused instead of racing the first.
+#[derive(Default)]
+pub struct InstallLocks(std::sync::Mutex<std::collections::HashSet<PathBuf>>);
+
+/// Held for the duration of one install; releases its folder on drop
+struct InstallLock<'a> {
+    locks: &'a InstallLocks,
+    skill_dir: PathBuf,
+}
+
+impl<'a> InstallLock<'a> {
+    fn acquire(locks: &'a InstallLocks, skill_dir: &Path) -> Result<Self, String> {
+        let mut held = locks.0.lock().map_err(|e| e.to_string())?;
+        if !held.insert(skill_dir.to_path_buf()) {
+            return Err(format!("Install already in progress for {}", skill_dir.display()));
+        }
+        Ok(InstallLock {
+            locks,
+            skill_dir: skill_dir.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for InstallLock<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut held) = self.locks.0.lock() {
+            held.remove(&self.skill_dir);
+        }
+    }
+}
+
+/// File watchers for linked dev skills, keyed by the link's path. Dropping
+/// a watcher stops it.
+#[derive(Default)]
+pub struct DevWatchers(std::sync::Mutex<HashMap<PathBuf, notify::RecommendedWatcher>>);
+
+/// Serializes appends to the activity history
+#[derive(Default)]
+pub struct ActivityLog(std::sync::Mutex<()>);
+
+/// Token counts from `recount_tokens` by `agent:path`, with a hash
+/// of the manifest they were counted from so unchanged skills aren't
+/// tokenized again
+#[derive(Default)]
+pub struct TokenCounts(std::sync::Mutex<HashMap<String, (u64, u64)>>);
+
+/// Background installs started with `start_install`, by job id in start
+/// order. Finished jobs are kept, up to `JOB_HISTORY_MAX`, so their outcome
+/// can still be looked up.
+#[derive(Default)]
+pub struct InstallJobs {
+    jobs: std::sync::Mutex<std::collections::BTreeMap<u64, InstallJob>>,
+    next_id: std::sync::atomic::AtomicU64,
+}
+
+struct InstallJob {
+    info: JobInfo,
+    /// Set while the install runs; aborting it cancels the job
+    task: Option<tauri::async_runtime::JoinHandle<()>>,
+}
+
+impl InstallJobs {
+    /// Counts a file the job has downloaded and announces it with
+    /// `job-progress`
+    fn file_done(&self, app: &AppHandle, id: u64) {
+        let info = {
+            let Ok(mut jobs) = self.jobs.lock() else {
+                return;
+            };
+            let Some(job) = jobs.get_mut(&id).filter(|job| job.info.status == JobStatus::Running) else {
+                return;
+            };
+            job.info.files_downloaded += 1;
+            job.info.clone()
+        };
+        let _ = app.emit("job-progress", &info);
+    }
+
+    /// Records a job's outcome and announces it, unless it was cancelled
+    /// in the meantime
+    fn finish(&self, app: &AppHandle, id: u64, result: Result<Vec<InstallResult>, String>) {
+        let info = {
+            let Ok(mut jobs) = self.jobs.lock() else {
+                return;
+            };
+            let Some(job) = jobs.get_mut(&id).filter(|job| job.info.status == JobStatus::Running) else {
+                return;
+            };
+            job.task = None;
+            job.info.finished_at = Some(chrono::Utc::now().to_rfc3339());
+            match result {
+                Ok(results) => {
+                    job.info.status = JobStatus::Completed;
+                    job.info.results = results;
+                }
+                Err(e) => {
+                    job.info.status = JobStatus::Failed;
+                    job.info.error = Some(e);
+                }
+            }
+            job.info.clone()
+        };
+
+        let event = if info.status == JobStatus::Completed {
+            "job-complete"
+        } else {
+            "job-failed"
+        };
+        let _ = app.emit(event, &info);
+    }
+}
+
+/// A background install as `get_job` reports it and the `job-progress`,
+/// `job-complete` and `job-failed` events carry it
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: String,
+    pub agent: AgentType,
+    /// Install URL, with credentials redacted
+    pub source: String,
+    pub status: JobStatus,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// Files fetched so far; only GitHub directory installs count them
+    pub files_downloaded: usize,
+    pub results: Vec<InstallResult>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Keeps the background log writer alive and the filter swappable at runtime
+pub struct LogState {
+    filter: tracing_subscriber::reload::Handle<tracing_subscriber::EnvFilter, tracing_subscriber::Registry>,
+    _guard: tracing_appender::non_blocking::WorkerGuard,
+}
+
+/// `search_skills` output, echoing the query so the UI can drop responses
+/// that arrive after a newer search
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResponse {
+    pub query: String,
+    pub elapsed_ms: u64,
+    pub results: Vec<SearchSkill>,
+}
+
+/// Recent search results and any active backoff, shared across searches
+#[derive(Default)]
+pub struct SearchState {
+    cache: std::sync::Mutex<HashMap<String, (Vec<SearchSkill>, std::time::Instant)>>,
+    blocked_until: std::sync::Mutex<Option<std::time::Instant>>,
+    /// Token estimates by slug, from `estimate_search_skill_tokens`
+    token_estimates: std::sync::Mutex<HashMap<String, u64>>,
+    /// One lock per query being fetched, so identical concurrent searches
+    /// wait for the first and read its cached result
+    in_flight: std::sync::Mutex<HashMap<String, std::sync::Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl SearchState {
+    fn query_lock(&self, key: &str) -> std::sync::Arc<tokio::sync::Mutex<()>> {
+        match self.in_flight.lock() {
+            Ok(mut in_flight) => in_flight.entry(key.to_string()).or_default().clone(),
+            Err(_) => Default::default(),
+        }
+    }
+
+    /// Drops the query's lock once no other search is waiting on it
+    fn release_query_lock(&self, key: &str) {
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            if in_flight.get(key).is_some_and(|lock| std::sync::Arc::strong_count(lock) == 1) {
+                in_flight.remove(key);
+            }
+        }
+    }
+
+    fn cached(&self, key: &str) -> Option<(Vec<SearchSkill>, std::time::Instant)> {
+        self.cache.lock().ok()?.get(key).cloned()
+    }
+
+    fn store(&self, key: String, skills: Vec<SearchSkill>) {
+        if let Ok(mut cache) = self.cache.lock() {
+            if cache.len() >= SEARCH_CACHE_MAX_ENTRIES {
+                let oldest = cache
+                    .iter()
+                    .min_by_key(|(_, (_, fetched_at))| *fetched_at)
+                    .map(|(k, _)| k.clone());
+                if let Some(oldest) = oldest {
+                    cache.remove(&oldest);
+                }
+            }
+            cache.insert(key, (skills, std::time::Instant::now()));
+        }
+    }
+
+    /// A result with this slug from any cached search
+    fn find_by_slug(&self, slug: &str) -> Option<SearchSkill> {
+        let cache = self.cache.lock().ok()?;
+        cache
+            .values()
+            .flat_map(|(skills, _)| skills.iter())
+            .find(|s| s.slug == slug)
+            .cloned()
+    }
+
+    fn backoff_remaining(&self) -> Option<std::time::Duration> {
+        let until = (*self.blocked_until.lock().ok()?)?;
+        until.checked_duration_since(std::time::Instant::now())
+    }
+
+    fn back_off(&self, duration: std::time::Duration) {
+        if let Ok(mut blocked_until) = self.blocked_until.lock() {
+            *blocked_until = Some(std::time::Instant::now() + duration);
+        }
+    }
+}
+
+/// Per-host request headers (typically `Authorization`) for private skill
+/// URLs. Debug output lists hosts only so tokens never end up in logs.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct HostCredentials(HashMap<String, HashMap<String, String>>);
+
+impl std::fmt::Debug for HostCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.0.keys().map(|host| (host, "<redacted>")))
+            .finish()
+    }
+}
+
+/// The app's own preferences, stored as `settings.json` in the data dir
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub credentials: HostCredentials,
+    pub post_install_hook: Option<PostInstallHook>,
+    /// Per-agent hooks by agent id; these take precedence over the global one
+    pub agent_post_install_hooks: HashMap<String, PostInstallHook>,
+    /// A `tracing` filter such as `info` or `oh_my_skills=debug`
+    pub log_level: Option<String>,
+    /// Linked dev skills, re-watched on startup
+    pub dev_links: Vec<DevLink>,
+    /// Agents that `All` operations include; every agent when unset
+    pub all_scope: Option<Vec<AgentType>>,
+    /// App version seen on the last launch, to spot an update
+    pub last_seen_version: Option<String>,
+    /// Version updated from, until the frontend takes the what's-new notice
+    pub whats_new_from: Option<String>,
+    /// Frontmatter keys a skill's name is read from, in priority order;
+    /// `DEFAULT_NAME_KEYS` when unset
+    pub name_keys: Option<Vec<String>>,
+    /// Include the start of the server's response in failed fetch errors
+    pub debug_http_errors: bool,
+    pub tray_icon_theme: TrayIconTheme,
+    /// Machines whose agents' skills can be browsed over SSH
+    pub remote_hosts: Vec<RemoteHost>,
+    /// Run `cleanup` across all agents when the app starts
+    pub cleanup_on_startup: bool,
+}
+
+/// A machine reached over `SshTransport`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteHost {
+    /// Label the frontend shows and commands refer to the host by
+    pub name: String,
+    /// `user@host`, or just `host` to log in as the local user
+    pub destination: String,
+    pub port: Option<u16>,
+}
+
+/// Which variant of the tray icon to show
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrayIconTheme {
+    /// Template icon on macOS, elsewhere black or white to match the OS
+    #[default]
+    System,
+    /// Dark glyph, for light menu bars
+    Light,
+    /// White glyph, for dark menu bars
+    Dark,
+    Colored,
+}
+
+/// A shell command run after a successful install, with `{skill_dir}`
+/// replaced by the installed skill's path
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostInstallHook {
+    pub command: String,
+    pub enabled: bool,
+    /// The command text the user last confirmed. The hook only runs while
+    /// this still matches `command`.
+    pub approved_command: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HookOutput {
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// A skill fetched from its source, ready to write into one or more agents
+struct PreparedSkill {
+    name: String,
+    files: Vec<SkillFile>,
+    source: Option<String>,
+}
+
+/// Canonical skill manifest filename
+const SKILL_MANIFEST: &str = "SKILL.md";
+
+/// Metadata files some skill formats keep next to a frontmatter-less
+/// SKILL.md. Their keys take precedence over the frontmatter.
+const SIDECAR_MANIFEST_NAMES: &[&str] = &["skill.yaml", "skill.yml", "skill.toml"];
+
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+const SETTINGS_FILE: &str = "settings.json";
+const DEFAULT_NAME_KEYS: &[&str] = &["name", "title", "id"];
+/// Characters of a description shown in skill lists
+const LIST_DESCRIPTION_MAX: usize = 160;
+/// How far back `list_skills` in grouped mode looks for recent installs
+const RECENT_INSTALL_DAYS: i64 = 7;
+/// Skills read at once by `recount_tokens`
+const RECOUNT_PARALLELISM: usize = 4;
+/// Model context window `total_active_token_cost` measures against by default
+const DEFAULT_CONTEXT_WINDOW: u64 = 200_000;
+/// Skills `total_active_token_cost` lists by default
+const HEAVIEST_SKILLS_SHOWN: usize = 5;
+/// Collections smaller than this finish too fast to need progress events
+const RECOUNT_PROGRESS_MIN: usize = 50;
+/// Largest SKILL.md, in tokens, that `get_skill_badges` calls small
+const TOKEN_TIER_SMALL_MAX: u64 = 2_000;
+/// Largest SKILL.md, in tokens, that `get_skill_badges` calls medium
+const TOKEN_TIER_MEDIUM_MAX: u64 = 8_000;
+/// Finished background installs kept for `get_job` and `list_jobs`
+const JOB_HISTORY_MAX: usize = 50;
+/// Skills `install_skills_from_github_glob` downloads at once
+const GLOB_INSTALL_PARALLELISM: usize = 4;
+/// Agent whose skills dir `preflight` checks, the one the UI opens on
+const PREFLIGHT_AGENT: AgentType = AgentType::Claude;
+/// Servers `validate_all_mcp_servers` checks at once
+const MCP_VALIDATE_PARALLELISM: usize = 8;
+/// How long `validate_all_mcp_servers` waits on an http server
+const MCP_REACH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+/// How long an SSH connection or a remote command may stall
+const SSH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// Tried in order when the SSH agent has no identity the host accepts
+const SSH_DEFAULT_KEYS: &[&str] = &["id_ed25519", "id_ecdsa", "id_rsa"];
+/// Prefixes of well-known API token formats
+const SECRET_PREFIXES: &[&str] = &["ghp_", "gho_", "ghs_", "ghu_", "github_pat_", "sk-", "xoxb-", "xoxp-"];
+/// Files this large or larger are moved by `externalize_skill_assets`
+const EXTERNALIZE_MIN_BYTES: u64 = 64 * 1024;
+/// Where `externalize_skill_assets` moves files, inside the skill dir
+const EXTERNAL_ASSETS_DIR: &str = "assets";
+/// Largest SKILL.md `get_skill_content` returns in full
+const SKILL_CONTENT_MAX_BYTES: usize = 1024 * 1024;
+/// Tint of the `colored` tray icon
+const TRAY_ICON_COLOR: [u8; 3] = [0xD9, 0x77, 0x57];
+/// How much of a failed response's body `debug_http_errors` shows
+const DEBUG_BODY_LIMIT: usize = 2000;
+
+const ACTIVITY_FILE: &str = "activity.jsonl";
+const SKILL_USAGE_CACHE_FILE: &str = "skill-usage.json";
+/// Oldest entries are dropped past this many
+const ACTIVITY_HISTORY_MAX: usize = 1000;
+
+const LOG_FILE_PREFIX: &str = "oh-my-skills.log";
+const DEFAULT_LOG_LEVEL: &str = "info";
+/// Only the newest agent logs are read; older ones describe stale installs
+const AGENT_LOG_FILES_SCANNED: usize = 5;
+
+/// Overrides the home directory every agent path is resolved against
+const APP_HOME_ENV: &str = "OH_MY_SKILLS_HOME";
+
+const SEARCH_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+const SEARCH_CACHE_MAX_ENTRIES: usize = 100;
+/// Used when a 429 or 5xx comes without a usable `Retry-After`
+const SEARCH_RATE_LIMIT_BACKOFF: std::time::Duration = std::time::Duration::from_secs(60);
+const SEARCH_UNAVAILABLE_BACKOFF: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// A `.skill` file is a ZIP of one skill plus a `skill.json` manifest
+const SKILL_FILE_EXTENSION: &str = ".skill";
+const SKILL_FILE_MIME: &str = "application/vnd.oh-my-skills.skill+zip";
+const SKILL_FILE_MANIFEST: &str = "skill.json";
+const SKILL_FILE_FORMAT_VERSION: u32 = 1;
+
+/// Slack for metadata and filesystem overhead on top of a skill's own bytes
+const DISK_SPACE_HEADROOM: u64 = 1024 * 1024;
+
+/// Manifest types accepted from OCI registries, indexes included
+const OCI_MANIFEST_ACCEPT: &str = "application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+/// Most a ZIP may expand to when installed
+const MAX_ZIP_UNCOMPRESSED_SIZE: u64 = 100 * 1024 * 1024;
+/// Largest file a URL install downloads, after decompression
+const MAX_DOWNLOAD_SIZE: u64 = 100 * 1024 * 1024;
+/// Entries compressed better than this to one are treated as ZIP bombs
+const MAX_ZIP_COMPRESSION_RATIO: u64 = 100;
+/// Temp files newer than this may belong to an install still in progress,
+/// so `cleanup` leaves them
+const STALE_TEMP_AGE: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+// ============================================================================
+// Paths
+// ============================================================================
+
+/// Returns all individual agent types (excluding All)
+fn get_all_individual_agents() -> Vec<AgentType> {
+    vec![
+        AgentType::Claude,
+        AgentType::Gemini,
+        AgentType::Codex,
+        AgentType::Opencode,
+        AgentType::Kiro,
+        AgentType::Antigravity,
+        AgentType::Codebuddy,
+        AgentType::Cursor,
+        AgentType::Kimi,
+        AgentType::Moltbot,
+        AgentType::Qoder,
+        AgentType::Qwen,
+        AgentType::Zencoder,
+    ]
+}
+
+fn get_skills_dir(agent: AgentType) -> Result<PathBuf, String> {
+    if let Some(config_dir) = get_agent_config_override(agent) {
+        return Ok(config_dir.join("skills"));
+    }
+    let home = app_home()?;
+    default_skills_dir(agent, &home, &get_xdg_config_home(&home))
+}
+
+/// An agent's skills dir on a machine with the given home and config dirs,
+/// before any override
+fn default_skills_dir(agent: AgentType, home: &Path, config_home: &Path) -> Result<PathBuf, String> {
+    match agent {
+        AgentType::All => Err("Cannot get skills dir for All agent".to_string()),
+        AgentType::Claude => Ok(home.join(".claude").join("skills")),
+        AgentType::Gemini => Ok(home.join(".gemini").join("skills")),
+        AgentType::Codex => Ok(home.join(".codex").join("skills")),
+        AgentType::Opencode => Ok(config_home.join("opencode").join("skills")),
+        AgentType::Kiro => Ok(home.join(".kiro").join("skills")),
+        AgentType::Antigravity => Ok(home.join(".gemini").join("antigravity").join("global_skills")),
+        AgentType::Codebuddy => Ok(home.join(".codebuddy").join("skills")),
+        AgentType::Cursor => Ok(home.join(".cursor").join("skills")),
+        AgentType::Kimi => Ok(home.join(".kimi").join("skills")),
+        AgentType::Moltbot => Ok(home.join(".moltbot").join("skills")),
+        AgentType::Qoder => Ok(home.join(".qoder").join("skills")),
+        AgentType::Qwen => Ok(home.join(".qwen").join("skills")),
+        AgentType::Zencoder => Ok(home.join(".zencoder").join("skills")),
+    }
+}
+
+/// Where an agent keeps the skills it ships with. The app lists these but
+/// leaves them alone unless a delete is forced.
+///
+/// - Codex: `skills/.system`
+fn get_builtin_skills_dirs(agent: AgentType) -> Vec<PathBuf> {
+    match agent {
+        AgentType::Codex => get_skills_dir(agent).map(|dir| vec![dir.join(".system")]).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// The skills dir, or a namespace subdirectory of it
+fn get_skills_root(agent: AgentType, namespace: Option<&str>) -> Result<PathBuf, String> {
+    let skills_dir = get_skills_dir(agent)?;
+    match namespace {
+        Some(ns) => {
+            validate_namespace(ns)?;
+            Ok(skills_dir.join(ns))
+        }
+        None => Ok(skills_dir),
+    }
+}
+
+fn validate_namespace(namespace: &str) -> Result<(), String> {
+    if namespace.is_empty()
+        || namespace.starts_with('.')
+        || namespace.contains(['/', '\\'])
+    {
+        return Err(format!("Invalid namespace: {}", namespace));
+    }
+    Ok(())
+}
+
+/// The directory for skill `name`, after checking that the name is a single
+/// safe component and that the result sits directly in the skills dir.
+/// Every command taking a skill name from the frontend goes through here.
+fn resolve_skill_dir(agent: AgentType, name: &str, namespace: Option<&str>) -> Result<PathBuf, String> {
+    validate_skill_name(name)?;
+    let skills_dir = get_skills_root(agent, namespace)?;
+    let skill_dir = skills_dir.join(name);
+
+    // The entry itself may be a symlink, so compare its parent rather than
+    // following it
+    if fs::symlink_metadata(&skill_dir).is_ok() {
+        let canonical_root = fs::canonicalize(&skills_dir).map_err(|e| e.to_string())?;
+        let canonical_parent = skill_dir
+            .parent()
+            .map(fs::canonicalize)
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        if canonical_parent.as_deref() != Some(canonical_root.as_path()) {
+            return Err(format!("Skill path escapes the skills directory: {}", name));
+        }
+    }
+
+    Ok(skill_dir)
+}
+
+/// Skill names become a single path component under the skills dir, so
+/// anything that could climb out of it or nest is refused
+fn validate_skill_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name == "."
+        || name.contains(['/', '\\'])
+        || name.contains("..")
+        || name.contains('\0')
+    {
+        return Err(format!("Invalid skill name: {}", name));
+    }
+    Ok(())
+}
+
+fn get_mcp_config_path(agent: AgentType) -> Result<PathBuf, String> {
+    if let Some(config_dir) = get_agent_config_override(agent) {
+        let file = match agent {
+            AgentType::Claude => ".claude.json",
+            AgentType::Codex => "config.toml",
+            _ => "config.json",
+        };
+        return Ok(config_dir.join(file));
+    }
+    let home = app_home()?;
+    match agent {
+        AgentType::Claude => Ok(home.join(".claude.json")),
+        AgentType::Gemini => Ok(home.join(".gemini").join("settings.json")),
+        AgentType::Codex => Ok(home.join(".codex").join("config.toml")),
+        AgentType::Opencode => Ok(get_xdg_config_home(&home).join("opencode").join("config.json")),
+        AgentType::Kiro => Ok(home.join(".kiro").join("settings.json")),
+        // These agents don't have MCP support
+        AgentType::All | AgentType::Antigravity | AgentType::Codebuddy | AgentType::Cursor |
+        AgentType::Kimi | AgentType::Moltbot | AgentType::Qoder |
+        AgentType::Qwen | AgentType::Zencoder => Err("MCP not supported for this agent".to_string()),
+    }
+}
+
+/// The config dir an agent was pointed at through its own environment
+/// variable, which replaces the home-relative default:
+///
+/// - Claude: `CLAUDE_CONFIG_DIR` (holds `skills/` and `.claude.json`)
+/// - Codex: `CODEX_HOME` (holds `skills/` and `config.toml`)
+/// - OpenCode: `OPENCODE_CONFIG_DIR` (holds `skills/` and `config.json`)
+///
+/// Other agents don't document an override. Empty or relative values are
+/// ignored.
+fn get_agent_config_override(agent: AgentType) -> Option<PathBuf> {
+    let var = match agent {
+        AgentType::Claude => "CLAUDE_CONFIG_DIR",
+        AgentType::Codex => "CODEX_HOME",
+        AgentType::Opencode => "OPENCODE_CONFIG_DIR",
+        _ => return None,
+    };
+    get_xdg_env_dir(var)
+}
+
+/// `$XDG_CONFIG_HOME`, or `~/.config` when unset. OpenCode resolves its
+/// config this way on every platform, so this isn't Linux-only.
+fn get_xdg_config_home(home: &Path) -> PathBuf {
+    get_xdg_env_dir("XDG_CONFIG_HOME").unwrap_or_else(|| home.join(".config"))
+}
+
+/// The home directory agent and app paths hang off: `OH_MY_SKILLS_HOME`,
+/// then what the OS reports, then `HOME` and `USERPROFILE` directly for
+/// stripped-down environments where the OS lookup fails. Relative values
+/// are ignored.
+fn app_home() -> Result<PathBuf, NoHomeError> {
+    resolve_app_home(dirs::home_dir)
+}
+
+/// `app_home` with the OS lookup passed in
+fn resolve_app_home(os_home: impl FnOnce() -> Option<PathBuf>) -> Result<PathBuf, NoHomeError> {
+    get_xdg_env_dir(APP_HOME_ENV)
+        .or_else(|| os_home().filter(|p| p.is_absolute()))
+        .or_else(|| get_xdg_env_dir("HOME"))
+        .or_else(|| get_xdg_env_dir("USERPROFILE"))
+        .ok_or(NoHomeError)
+}
+
+/// Reads a directory variable such as an XDG base dir; the XDG spec says
+/// relative paths are invalid and must be ignored.
+fn get_xdg_env_dir(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+}
+
+/// Directory for the app's own files (trash, ...). Honors a location set
+/// via `set_data_dir`, otherwise the platform default.
+fn get_app_data_dir() -> Result<PathBuf, String> {
+    let pointer = get_data_dir_pointer_path()?;
+    if let Ok(content) = fs::read_to_string(&pointer) {
+        let custom = PathBuf::from(content.trim());
+        if custom.is_absolute() {
+            return Ok(custom);
+        }
+    }
+
+    get_default_app_data_dir()
+}
+
+/// On Linux this is `$XDG_DATA_HOME/oh-my-skills` when set,
+/// `~/.oh-my-skills` otherwise.
+fn get_default_app_data_dir() -> Result<PathBuf, String> {
+    #[cfg(target_os = "linux")]
+    if let Some(data_home) = get_xdg_env_dir("XDG_DATA_HOME") {
+        return Ok(data_home.join("oh-my-skills"));
+    }
+
+    Ok(app_home()?.join(".oh-my-skills"))
+}
+
+/// Records a relocated data dir. Lives outside the data dir so it survives
+/// the move.
+fn get_data_dir_pointer_path() -> Result<PathBuf, String> {
+    let config_dir = match dirs::config_dir() {
+        Some(config_dir) => config_dir,
+        None => get_xdg_config_home(&app_home()?),
+    };
+    Ok(config_dir.join("oh-my-skills").join("data-dir"))
+}
+
+fn get_trash_dir() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("trash"))
+}
+
+/// Staging area for GitHub downloads, one folder per source URL
+fn get_downloads_dir() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("downloads"))
+}
+
+fn get_log_dir() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("logs"))
+}
+
+/// Release notes fetched from GitHub, one markdown file per version
+fn get_release_notes_dir() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("release-notes"))
+}
+
+fn pending_install_token(url: &str) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(url.as_bytes()))[..16].to_string()
+}
+
+/// Where an agent writes logs that mention skills it failed to load, for
+/// the agents known to keep one
+fn get_agent_log_dir(agent: AgentType) -> Option<PathBuf> {
+    let home = app_home().ok()?;
+    match agent {
+        AgentType::Claude => Some(home.join(".claude").join("debug")),
+        _ => None,
+    }
+}
+
+/// Where an agent keeps session transcripts that record skill invocations,
+/// for the agents known to keep them
+fn get_agent_usage_log_dir(agent: AgentType) -> Option<PathBuf> {
+    let home = app_home().ok()?;
+    match agent {
+        AgentType::Claude => Some(home.join(".claude").join("projects")),
+        _ => None,
+    }
+}
+
+/// Individual agents, keeping only the first of any that resolve to the
+/// same skills directory, so `All` operations touch each folder once
+fn get_distinct_skill_agents() -> Vec<AgentType> {
+    distinct_by_skills_dir(get_all_individual_agents())
+}
+
+/// The agents `All` operations act on: those in the `all_scope` setting,
+/// or every agent when it's unset, deduplicated by skills dir
+fn get_all_scope_agents() -> Vec<AgentType> {
+    let scope = load_settings().ok().and_then(|s| s.all_scope);
+    let agents = get_all_individual_agents()
+        .into_iter()
+        .filter(|agent| scope.as_ref().is_none_or(|scope| scope.contains(agent)))
+        .collect();
+    distinct_by_skills_dir(agents)
+}
+
+fn distinct_by_skills_dir(agents: Vec<AgentType>) -> Vec<AgentType> {
+    let mut seen = std::collections::HashSet::new();
+    agents
+        .into_iter()
+        .filter(|agent| match get_skills_dir(*agent) {
+            Ok(dir) => seen.insert(fs::canonicalize(&dir).unwrap_or(dir)),
+            Err(_) => true,
+        })
+        .collect()
+}
+
+/// Whether `file_name` is a skill manifest; every supported agent reads
+/// SKILL.md, in any case
+fn is_skill_manifest_name(file_name: &str) -> bool {
+    file_name.eq_ignore_ascii_case(SKILL_MANIFEST)
+}
+
+fn agent_has_mcp_support(agent: AgentType) -> bool {
+    matches!(
+        agent,
+        AgentType::Claude | AgentType::Gemini | AgentType::Opencode | AgentType::Kiro
+    )
+}
+
+/// Why an agent is left out of MCP commands although it has an MCP config:
+/// only JSON configs are read and written, so Codex's `config.toml` isn't
+fn unsupported_mcp_config(agent: AgentType) -> Option<String> {
+    if agent_has_mcp_support(agent) {
+        return None;
+    }
+    let config_path = get_mcp_config_path(agent).ok()?;
+    Some(format!("MCP servers in {} aren't supported yet", config_path.display()))
+}
+
+/// The agents `All` covers for MCP: those in the `All` scope with MCP support
+fn get_mcp_scope_agents() -> Vec<AgentType> {
+    get_all_scope_agents()
+        .into_iter()
+        .filter(|agent| agent_has_mcp_support(*agent))
+        .collect()
+}
+
+/// Whether the agent has a capability a skill can declare under
+/// `requires:`; `None` for names that aren't checked
+fn agent_has_capability(agent: AgentType, capability: &str) -> Option<bool> {
+    match capability {
+        "mcp" => Some(agent_has_mcp_support(agent)),
+        _ => None,
+    }
+}
+
+/// Capabilities the skill's frontmatter needs that the agent lacks. Listing
+/// MCP servers under `mcp:` or `requires_mcp:` implies `requires: mcp`.
+fn missing_capabilities(agent: AgentType, files: &[SkillFile]) -> Vec<String> {
+    let Some(content) = skill_files_manifest(files) else {
+        return vec![];
+    };
+    let frontmatter = frontmatter_block(&content).unwrap_or_default();
+    let mut required: Vec<String> = frontmatter_list(frontmatter, "requires")
+        .into_iter()
+        .map(|c| c.to_lowercase())
+        .collect();
+    if !frontmatter_list(frontmatter, "mcp").is_empty() || !frontmatter_list(frontmatter, "requires_mcp").is_empty() {
+        required.push("mcp".to_string());
+    }
+
+    required.sort();
+    required.dedup();
+    required.retain(|capability| agent_has_capability(agent, capability) == Some(false));
+    required
+}
+
+fn missing_capabilities_message(agent: AgentType, missing: &[String]) -> String {
+    format!("needs {}, which {} doesn't support", missing.join(", "), agent_metadata(agent).name)
+}
+
+// ============================================================================
+// Agent Commands
+// ============================================================================
+
+#[tauri::command]
+fn list_agents() -> Result<Vec<AgentInfo>, String> {
+    get_all_individual_agents()
+        .into_iter()
+        .map(|agent| {
+            Ok(AgentInfo {
+                id: agent.id().to_string(),
+                skills_path: get_skills_dir(agent)?.to_string_lossy().to_string(),
+                has_mcp: agent_has_mcp_support(agent),
+                metadata: agent_metadata(agent),
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+fn get_agent_metadata(agent: AgentType) -> AgentMetadata {
+    agent_metadata(agent)
+}
+
+/// Display data for each agent. Adding an agent means adding it here and to
+/// the path functions; the frontend reads everything else from `list_agents`.
+fn agent_metadata(agent: AgentType) -> AgentMetadata {
+    let (name, monogram, homepage, docs_url) = match agent {
+        AgentType::All => ("All Agents", "*", None, None),
+        AgentType::Claude => (
+            "Claude Code",
+            "C",
+            Some("https://github.com/anthropics/claude-code"),
+            Some("https://docs.claude.com/en/docs/claude-code"),
+        ),
+        AgentType::Gemini => (
+            "Gemini CLI",
+            "G",
+            Some("https://github.com/google-gemini/gemini-cli"),
+            Some("https://github.com/google-gemini/gemini-cli/tree/main/docs"),
+        ),
+        AgentType::Codex => (
+            "Codex CLI",
+            "X",
+            Some("https://github.com/openai/codex"),
+            Some("https://github.com/openai/codex/tree/main/docs"),
+        ),
+        AgentType::Opencode => ("OpenCode", "O", Some("https://opencode.ai"), Some("https://opencode.ai/docs")),
+        AgentType::Kiro => ("Kiro CLI", "K", Some("https://kiro.dev"), Some("https://kiro.dev/docs")),
+        AgentType::Antigravity => ("Antigravity", "A", Some("https://antigravity.google"), None),
+        AgentType::Codebuddy => ("CodeBuddy", "B", Some("https://www.codebuddy.ai"), None),
+        AgentType::Cursor => ("Cursor", "U", Some("https://cursor.com"), Some("https://docs.cursor.com")),
+        AgentType::Kimi => ("Kimi CLI", "I", Some("https://github.com/MoonshotAI/kimi-cli"), None),
+        AgentType::Moltbot => ("Moltbot", "M", None, None),
+        AgentType::Qoder => ("Qoder", "D", Some("https://qoder.com"), None),
+        AgentType::Qwen => ("Qwen Code", "Q", Some("https://github.com/QwenLM/qwen-code"), None),
+        AgentType::Zencoder => ("Zencoder", "Z", Some("https://zencoder.ai"), None),
+    };
+
+    let config_format = get_mcp_config_path(agent)
+        .ok()
+        .and_then(|p| p.extension().map(|ext| ext.to_string_lossy().to_string()));
+
+    AgentMetadata {
+        name,
+        monogram,
+        homepage,
+        docs_url,
+        config_format,
+    }
+}
+
+/// Whether skills can be installed for the agent. For `All`, every agent
+/// in scope has to be writable.
+#[tauri::command]
+fn can_write_skills_dir(agent: AgentType) -> Result<bool, String> {
+    let agents = if agent == AgentType::All {
+        get_all_scope_agents()
+    } else {
+        vec![agent]
+    };
+    for agent in agents {
+        if !is_dir_writable(&get_skills_dir(agent)?) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+// ============================================================================
+// Skills Commands
+// ============================================================================
+
+/// Lists an agent's skills sorted by namespace and name. `favorites_first`
+/// floats favorites to the top of that list; `grouped` returns them in
+/// `groups` instead, split into favorites, recent installs and the rest.
+#[tauri::command]
+fn list_skills(
+    agent: AgentType,
+    namespace: Option<String>,
+    include_namespaced: Option<bool>,
+    favorites_first: Option<bool>,
+    grouped: Option<bool>,
+) -> Result<SkillList, String> {
+    let mut list = collect_skill_list(agent, namespace, include_namespaced.unwrap_or(false))?;
+    if grouped.unwrap_or(false) {
+        list.groups = Some(group_skills(std::mem::take(&mut list.skills)));
+    } else if favorites_first.unwrap_or(false) {
+        // Stable, so each half keeps its name order
+        list.skills.sort_by_key(|skill| !skill.favorite);
+    }
+    Ok(list)
+}
+
+fn collect_skill_list(
+    agent: AgentType,
+    namespace: Option<String>,
+    include_namespaced: bool,
+) -> Result<SkillList, String> {
+
+    // Handle "All" agent - combine skills from all agents
+    if agent == AgentType::All {
+        let mut all_skills = Vec::new();
+        let mut errors = Vec::new();
+        let mut seen_names = std::collections::HashSet::new();
+
+        for individual_agent in get_all_scope_agents() {
+            match list_skills_with_builtins(individual_agent, namespace.as_deref(), include_namespaced) {
+                Ok(mut skills) => {
+                    fill_last_used(individual_agent, &mut skills);
+                    for skill in skills {
+                        // Deduplicate by name (same skill might be in multiple agents)
+                        if seen_names.insert((skill.namespace.clone(), skill.name.clone())) {
+                            all_skills.push(skill);
+                        }
+                    }
+                }
+                Err(error) => errors.push(AgentError {
+                    agent: individual_agent,
+                    error,
+                }),
+            }
+        }
+
+        all_skills.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+        return Ok(SkillList {
+            skills: all_skills,
+            groups: None,
+            errors,
+        });
+    }
+
+    let mut skills = list_skills_with_builtins(agent, namespace.as_deref(), include_namespaced)?;
+    fill_last_used(agent, &mut skills);
+    Ok(SkillList {
+        skills,
+        groups: None,
+        errors: vec![],
+    })
+}
+
+/// `list_skills_for_agent` plus the agent's built-in skills for the top
+/// level. Kept apart so bulk operations never see built-ins.
+fn list_skills_with_builtins(
+    agent: AgentType,
+    namespace: Option<&str>,
+    include_namespaced: bool,
+) -> Result<Vec<SkillInfo>, String> {
+    let mut skills = list_skills_for_agent(agent, namespace, include_namespaced)?;
+    if namespace.is_none() {
+        for dir in get_builtin_skills_dirs(agent) {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if entry.path().is_dir() && !name.starts_with('.') && !is_junk_file(&name) {
+                    let mut skill = skill_info(&entry.path(), name, None);
+                    skill.builtin = true;
+                    skills.push(skill);
+                }
+            }
+        }
+    }
+    Ok(skills)
+}
+
+/// Lists the skills directly under the skills root. With
+/// `include_namespaced`, folders that only group other skills are descended
+/// one level and their skills reported with that namespace.
+fn list_skills_for_agent(
+    agent: AgentType,
+    namespace: Option<&str>,
+    include_namespaced: bool,
+) -> Result<Vec<SkillInfo>, String> {
+    let skills_dir = get_skills_root(agent, namespace)?;
+
+    if !skills_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut skills = Vec::new();
+    let entries = fs::read_dir(&skills_dir)
+        .map_err(|e| format!("Cannot read {}: {}", skills_dir.display(), e))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("")
+                .to_string();
+
+            if name.starts_with('.') || is_junk_file(&name) {
+                continue;
+            }
+
+            if include_namespaced && namespace.is_none() && is_namespace_dir(&path) {
+                skills.extend(list_skills_for_agent(agent, Some(&name), false)?);
+                continue;
+            }
+
+            skills.push(skill_info(&path, name, namespace));
+        }
+    }
+
+    skills.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+    Ok(skills)
+}
+
+fn skill_info(path: &PathBuf, name: String, namespace: Option<&str>) -> SkillInfo {
+    let manifest = read_content_manifest(path);
+    let token_count = manifest.as_deref().map(manifest_tokens);
+    let description = manifest
+        .and_then(|content| extract_skill_description(&content))
+        .map(|d| list_description(&d));
+
+    SkillInfo {
+        name,
+        path: path.to_string_lossy().to_string(),
+        token_count,
+        namespace: namespace.map(String::from),
+        description,
+        builtin: false,
+        last_used_at: None,
+        favorite: read_metadata(path).is_some_and(|m| m.favorite),
+    }
+}
+
+fn group_skills(skills: Vec<SkillInfo>) -> SkillGroups {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(RECENT_INSTALL_DAYS);
+    let mut groups = SkillGroups::default();
+    let mut recent = Vec::new();
+
+    for skill in skills {
+        if skill.favorite {
+            groups.favorites.push(skill);
+            continue;
+        }
+        let installed_at = read_metadata(Path::new(&skill.path))
+            .and_then(|m| chrono::DateTime::parse_from_rfc3339(&m.installed_at).ok())
+            .filter(|installed_at| *installed_at >= cutoff);
+        match installed_at {
+            Some(installed_at) => recent.push((installed_at, skill)),
+            None => groups.others.push(skill),
+        }
+    }
+
+    recent.sort_by_key(|(installed_at, _)| std::cmp::Reverse(*installed_at));
+    groups.recent = recent.into_iter().map(|(_, skill)| skill).collect();
+    groups
+}
+
+/// Re-reads every skill's SKILL.md and returns fresh token counts, keyed
+/// `namespace/name` for namespaced skills. `All` counts each agent in scope
+/// separately, keys prefixed `agent:`. Skills whose manifest hasn't changed
+/// since the last recount keep their cached count. Emits
+/// `token-recount-progress` for large collections.
+#[tauri::command]
+async fn recount_tokens(app: AppHandle, agent: AgentType) -> Result<Vec<(String, u64)>, String> {
+    let agents = if agent == AgentType::All {
+        get_all_scope_agents()
+    } else {
+        vec![agent]
+    };
+    let mut skills = Vec::new();
+    for individual_agent in agents {
+        match list_skills_with_builtins(individual_agent, None, true) {
+            Ok(agent_skills) => skills.extend(agent_skills.into_iter().map(|skill| (individual_agent, skill))),
+            // `list_skills` reports these; the other agents still get counted
+            Err(e) if agent == AgentType::All => {
+                tracing::warn!(agent = individual_agent.id(), error = %e, "skipping unreadable skills dir")
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let total = skills.len();
+    let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(RECOUNT_PARALLELISM));
+    let mut tasks = tokio::task::JoinSet::new();
+
+    for (skill_agent, skill) in skills {
+        let permit = semaphore.clone().acquire_owned().await.map_err(|e| e.to_string())?;
+        let app = app.clone();
+        tasks.spawn_blocking(move || {
+            let _permit = permit;
+            let tokens = read_content_manifest(&PathBuf::from(&skill.path))
+                .map(|content| cached_manifest_tokens(&app, skill_agent, &skill.path, &content))
+                .unwrap_or(0);
+            let mut key = match &skill.namespace {
+                Some(ns) => format!("{}/{}", ns, skill.name),
+                None => skill.name,
+            };
+            if agent == AgentType::All {
+                key = format!("{}:{}", skill_agent.id(), key);
+            }
+            (key, tokens)
+        });
+    }
+
+    let mut counts = Vec::with_capacity(total);
+    while let Some(joined) = tasks.join_next().await {
+        counts.push(joined.map_err(|e| e.to_string())?);
+        if total >= RECOUNT_PROGRESS_MIN {
+            let _ = app.emit("token-recount-progress", TokenRecountProgress { agent, done: counts.len(), total });
+        }
+    }
+
+    counts.sort();
+    Ok(counts)
+}
+
+/// `manifest_tokens`, reusing the count from `TokenCounts` when the manifest
+/// is the one last counted for this skill
+fn cached_manifest_tokens(app: &AppHandle, agent: AgentType, path: &str, manifest: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    manifest.hash(&mut hasher);
+    let hash = hasher.finish();
+    let key = format!("{}:{}", agent.id(), path);
+
+    let counts = app.state::<TokenCounts>();
+    if let Some((_, tokens)) = counts.0.lock().ok().and_then(|c| c.get(&key).copied()).filter(|(h, _)| *h == hash) {
+        return tokens;
+    }
+    let tokens = manifest_tokens(manifest);
+    if let Ok(mut counts) = counts.0.lock() {
+        counts.insert(key, (hash, tokens));
+    }
+    tokens
+}
+
+/// Sums the token counts of every skill the agent loads (built-ins
+/// included, skills switched off in its config left out), with the `top`
+/// heaviest and the share of `context_window` they take up. `All` adds up
+/// each agent in scope.
+#[tauri::command]
+fn total_active_token_cost(
+    agent: AgentType,
+    context_window: Option<u64>,
+    top: Option<usize>,
+) -> Result<TokenCostSummary, String> {
+    let agents = if agent == AgentType::All {
+        get_all_scope_agents()
+    } else {
+        vec![agent]
+    };
+
+    let mut costs = Vec::new();
+    for agent in agents {
+        let disabled = match get_skill_gate(agent) {
+            Some(gate) => gate.read_names()?,
+            None => vec![],
+        };
+        costs.extend(
+            list_skills_with_builtins(agent, None, true)?
+                .into_iter()
+                .filter(|s| s.namespace.is_some() || !disabled.contains(&s.name))
+                .map(|s| SkillTokenCost {
+                    agent,
+                    name: s.name,
+                    namespace: s.namespace,
+                    tokens: s.token_count.unwrap_or(0),
+                }),
+        );
+    }
+
+    let total_tokens = costs.iter().map(|c| c.tokens).sum();
+    let skill_count = costs.len();
+    costs.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.name.cmp(&b.name)));
+    costs.truncate(top.unwrap_or(HEAVIEST_SKILLS_SHOWN));
+
+    let context_window = context_window.filter(|w| *w > 0).unwrap_or(DEFAULT_CONTEXT_WINDOW);
+    Ok(TokenCostSummary {
+        total_tokens,
+        skill_count,
+        heaviest: costs,
+        context_window,
+        window_fraction: total_tokens as f64 / context_window as f64,
+    })
+}
+
+/// Skills not used in the last `older_than_days` days, oldest use first.
+/// Skills never seen in the logs count once they were installed before the
+/// cutoff. Agents without usage logs are an error rather than reporting
+/// every skill as unused.
+#[tauri::command]
+fn find_unused_skills(agent: AgentType, older_than_days: u32) -> Result<Vec<SkillInfo>, String> {
+    if get_agent_usage_log_dir(agent).is_none() {
+        return Err(format!("{} doesn't keep usage logs", agent_metadata(agent).name));
+    }
+
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(older_than_days as i64);
+    let before_cutoff =
+        |time: &str| chrono::DateTime::parse_from_rfc3339(time).is_ok_and(|t| t.with_timezone(&chrono::Utc) < cutoff);
+
+    let mut skills = list_skills_for_agent(agent, None, true)?;
+    fill_last_used(agent, &mut skills);
+    skills.retain(|skill| match &skill.last_used_at {
+        Some(used) => before_cutoff(used),
+        None => read_metadata(Path::new(&skill.path)).is_none_or(|m| before_cutoff(&m.installed_at)),
+    });
+    skills.sort_by(|a, b| a.last_used_at.cmp(&b.last_used_at).then_with(|| a.name.cmp(&b.name)));
+    Ok(skills)
+}
+
+fn fill_last_used(agent: AgentType, skills: &mut [SkillInfo]) {
+    let Some(log_dir) = get_agent_usage_log_dir(agent) else {
+        return;
+    };
+    let last_used = read_skill_usage(&log_dir);
+    for skill in skills {
+        // Namespaced skills are called as `namespace:name`
+        let called_as = match &skill.namespace {
+            Some(namespace) => format!("{}:{}", namespace, skill.name),
+            None => skill.name.clone(),
+        };
+        skill.last_used_at = last_used.get(&called_as).cloned();
+    }
+}
+
+/// Latest invocation time of each skill in Claude Code transcripts:
+/// `projects/<project>/<session>.jsonl`, where a `Skill` tool call names
+/// the skill in its input. Keyed by the name as called, `plugin:skill` for
+/// plugin and namespaced skills. Only what was appended to a transcript
+/// since the last call is read; the rest comes from `SkillUsageCache`.
+fn read_skill_usage(log_dir: &Path) -> HashMap<String, String> {
+    let cache_path = get_app_data_dir().ok().map(|dir| dir.join(SKILL_USAGE_CACHE_FILE));
+    let mut cache: SkillUsageCache = cache_path
+        .as_ref()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .unwrap_or_default();
+
+    let transcripts: Vec<PathBuf> = fs::read_dir(log_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|project| fs::read_dir(project.path()).ok())
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect();
+
+    let mut changed = false;
+    let mut seen = std::collections::HashSet::new();
+    for transcript in transcripts {
+        let Ok(meta) = fs::metadata(&transcript) else {
+            continue;
+        };
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_millis() as u64);
+        let key = transcript.to_string_lossy().to_string();
+        seen.insert(key.clone());
+
+        let usage = cache.transcripts.entry(key).or_default();
+        if usage.modified == modified && usage.read_to == meta.len() {
+            continue;
+        }
+        // Transcripts only grow; anything else means it was rewritten
+        if meta.len() < usage.read_to || modified < usage.modified {
+            *usage = TranscriptUsage::default();
+        }
+        if read_transcript_usage(&transcript, usage).is_ok() {
+            usage.modified = modified;
+        }
+        changed = true;
+    }
+
+    let known = cache.transcripts.len();
+    cache.transcripts.retain(|path, _| seen.contains(path));
+    changed |= cache.transcripts.len() != known;
+    if let (true, Some(path), Ok(json)) = (changed, &cache_path, serde_json::to_vec(&cache)) {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = fs::write(path, json);
+    }
+
+    let mut last_used: HashMap<String, String> = HashMap::new();
+    for (skill, timestamp) in cache.transcripts.into_values().flat_map(|usage| usage.last_used) {
+        let latest = last_used.entry(skill).or_default();
+        if timestamp > *latest {
+            *latest = timestamp;
+        }
+    }
+    last_used
+}
+
+/// Reads the complete lines appended to a transcript since `usage.read_to`
+/// into `usage`
+fn read_transcript_usage(transcript: &Path, usage: &mut TranscriptUsage) -> std::io::Result<()> {
+    use std::io::Seek;
+
+    let mut file = fs::File::open(transcript)?;
+    file.seek(std::io::SeekFrom::Start(usage.read_to))?;
+    let mut appended = Vec::new();
+    file.read_to_end(&mut appended)?;
+    // A line still being written is picked up next time
+    let Some(end) = appended.iter().rposition(|b| *b == b'\n') else {
+        return Ok(());
+    };
+    usage.read_to += end as u64 + 1;
+
+    let last_used = &mut usage.last_used;
+    for line in String::from_utf8_lossy(&appended[..end]).lines() {
+        if !line.contains("\"Skill\"") {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let Some(timestamp) = entry.get("timestamp").and_then(|t| t.as_str()) else {
+            continue;
+        };
+        let calls = entry
+            .pointer("/message/content")
+            .and_then(|c| c.as_array())
+            .into_iter()
+            .flatten()
+            .filter(|c| c.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+            .filter(|c| c.get("name").and_then(|n| n.as_str()) == Some("Skill"));
+        for call in calls {
+            let input = call.get("input");
+            let Some(skill) = ["skill", "command"]
+                .iter()
+                .find_map(|key| input.and_then(|i| i.get(key)).and_then(|v| v.as_str()))
+            else {
+                continue;
+            };
+            let skill = skill.trim_start_matches('/');
+            let latest = last_used.entry(skill.to_string()).or_default();
+            if timestamp > latest.as_str() {
+                *latest = timestamp.to_string();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Skill x agent pivot: unlike the deduplicated `All` list, shows which
+/// agents have each skill
+#[tauri::command]
+fn list_skills_matrix() -> Result<SkillMatrix, String> {
+    let agents = get_all_individual_agents();
+    let mut rows: std::collections::BTreeMap<(Option<String>, String), Vec<SkillCopy>> =
+        Default::default();
+    let mut errors = Vec::new();
+
+    for agent in &agents {
+        match list_skills_for_agent(*agent, None, true) {
+            Ok(skills) => {
+                for skill in skills {
+                    rows.entry((skill.namespace, skill.name)).or_default().push(SkillCopy {
+                        agent: *agent,
+                        metadata: read_metadata(Path::new(&skill.path)),
+                        path: skill.path,
+                        token_count: skill.token_count,
+                    });
+                }
+            }
+            Err(error) => errors.push(AgentError {
+                agent: *agent,
+                error,
+            }),
+        }
+    }
+
+    Ok(SkillMatrix {
+        agents,
+        rows: rows
+            .into_iter()
+            .map(|((namespace, name), copies)| SkillMatrixRow {
+                name,
+                namespace,
+                copies,
+            })
+            .collect(),
+        errors,
+    })
+}
+
+#[tauri::command]
+fn get_skill_content(agent: AgentType, name: String, namespace: Option<String>) -> Result<SkillContent, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+
+    let skill_md =
+        find_skill_md(&skill_dir).ok_or_else(|| format!("SKILL.md not found in {}", name))?;
+
+    let bytes = fs::read(&skill_md).map_err(|e| e.to_string())?;
+    let byte_size = bytes.len() as u64;
+    let (mut content, encoding, lossy) = decode_text_detailed(&bytes, &skill_md.display().to_string());
+
+    let truncated = content.len() > SKILL_CONTENT_MAX_BYTES;
+    if truncated {
+        let mut end = SKILL_CONTENT_MAX_BYTES;
+        while !content.is_char_boundary(end) {
+            end -= 1;
+        }
+        content.truncate(end);
+    }
+
+    Ok(SkillContent {
+        content,
+        encoding: encoding.name().to_string(),
+        byte_size,
+        token_count: estimate_tokens(byte_size),
+        truncated,
+        lossy,
+    })
+}
+
+/// A skill's SKILL.md, frontmatter, file list and metadata in one call,
+/// for a quick look without opening the file manager
+#[tauri::command]
+fn get_skill_preview(agent: AgentType, name: String, namespace: Option<String>) -> Result<SkillPreview, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    let content = get_skill_content(agent, name, namespace)?;
+
+    // The body is the file as written; the frontmatter includes the sidecar
+    let (_, body) = split_frontmatter(&content.content);
+    let body = body.to_string();
+    let html = render_markdown_html(&body);
+    let manifest = read_content_manifest(&skill_dir).unwrap_or_default();
+    let frontmatter = split_frontmatter(&manifest)
+        .0
+        .and_then(|block| serde_yaml::from_str::<serde_yaml::Value>(block).ok())
+        .and_then(|yaml| serde_json::to_value(yaml).ok())
+        .unwrap_or(serde_json::Value::Null);
+
+    let mut files: Vec<FileItem> = skill_content_files(&skill_dir)
+        .map(|(path, meta)| FileItem {
+            name: path.rsplit('/').next().unwrap_or(&path).to_string(),
+            path,
+            is_directory: false,
+            size: Some(meta.len()),
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(SkillPreview {
+        content,
+        body,
+        html,
+        frontmatter,
+        files,
+        metadata: read_metadata(&skill_dir),
+    })
+}
+
+/// Token counts for the whole SKILL.md, its body and its frontmatter,
+/// from the untruncated file
+#[tauri::command]
+fn analyze_skill_content(
+    agent: AgentType,
+    name: String,
+    namespace: Option<String>,
+) -> Result<SkillContentAnalysis, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    let content =
+        read_content_manifest(&skill_dir).ok_or_else(|| format!("SKILL.md not found in {}", name))?;
+
+    let (frontmatter, body) = split_frontmatter(&content);
+    let full_len = content.len() as u64;
+    let body_len = body.len() as u64;
+
+    Ok(SkillContentAnalysis {
+        full_tokens: manifest_tokens(&content),
+        body_tokens: estimate_tokens(body_len),
+        frontmatter_tokens: estimate_tokens(full_len - body_len),
+        has_frontmatter: frontmatter.is_some(),
+    })
+}
+
+/// `get_skill_content` as a bare string, for callers that predate it
+#[tauri::command]
+fn get_skill_content_text(agent: AgentType, name: String, namespace: Option<String>) -> Result<String, String> {
+    get_skill_content(agent, name, namespace).map(|c| c.content)
+}
+
+/// The skill's `CHANGELOG.md` (any case, `.md` optional) from its top
+/// folder; `None` when it doesn't ship one
+#[tauri::command]
+fn get_skill_changelog(agent: AgentType, name: String, namespace: Option<String>) -> Result<Option<String>, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    if !skill_dir.is_dir() {
+        return Err(format!("Skill not found: {}", name));
+    }
+
+    let changelog = fs::read_dir(&skill_dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|e| e.path())
+        .find(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.eq_ignore_ascii_case("changelog.md") || n.eq_ignore_ascii_case("changelog"))
+        });
+    match changelog {
+        Some(path) => read_text(path).map(Some).map_err(|e| e.to_string()),
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+fn get_skill_metadata(agent: AgentType, name: String, namespace: Option<String>) -> Result<Option<SkillMetadata>, String> {
+    let metadata_path = resolve_skill_dir(agent, &name, namespace.as_deref())?.join(".metadata.json");
+
+    if !metadata_path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&metadata_path).map_err(|e| e.to_string())?;
+    let metadata: SkillMetadata = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    Ok(Some(metadata))
+}
+
+/// Checks the servers listed under `mcp:` or `requires_mcp:` in a skill's
+/// frontmatter against the agent's MCP config
+#[tauri::command]
+fn skill_mcp_requirements(agent: AgentType, name: String, namespace: Option<String>) -> Result<McpRequirements, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    let skill_md = find_skill_md(&skill_dir).ok_or_else(|| format!("SKILL.md not found in {}", name))?;
+    let content = read_text(skill_md).map_err(|e| e.to_string())?;
+
+    let servers = read_mcp_servers(agent, None)?;
+    let mut requirements = McpRequirements::default();
+    for server_name in required_mcp_servers(&content) {
+        match servers.iter().find(|s| s.name == server_name) {
+            Some(server) if server.disabled == Some(true) => requirements.disabled.push(server_name),
+            Some(_) => requirements.present.push(server_name),
+            None => requirements.missing.push(server_name),
+        }
+    }
+    Ok(requirements)
+}
+
+fn required_mcp_servers(content: &str) -> Vec<String> {
+    let frontmatter = frontmatter_block(content).unwrap_or_default();
+    let mut required = frontmatter_list(frontmatter, "mcp");
+    required.extend(frontmatter_list(frontmatter, "requires_mcp"));
+    required.sort();
+    required.dedup();
+    required
+}
+
+/// The badges a skill list row shows, computed in one call. Checking for
+/// an update fetches the source's SKILL.md, so it only happens when
+/// `check_updates` is set.
+#[tauri::command]
+async fn get_skill_badges(
+    agent: AgentType,
+    name: String,
+    namespace: Option<String>,
+    check_updates: Option<bool>,
+) -> Result<SkillBadges, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    let is_symlink = fs::symlink_metadata(&skill_dir)
+        .map_err(|_| format!("Skill not found: {}", name))?
+        .file_type()
+        .is_symlink();
+    let skill_md = find_skill_md(&skill_dir).ok_or_else(|| format!("SKILL.md not found in {}", name))?;
+    let content = read_text(&skill_md).map_err(|e| e.to_string())?;
+    let metadata = read_metadata(&skill_dir);
+
+    let tokens = estimate_tokens(content.len() as u64);
+    let token_tier = if tokens <= TOKEN_TIER_SMALL_MAX {
+        TokenTier::Small
+    } else if tokens <= TOKEN_TIER_MEDIUM_MAX {
+        TokenTier::Medium
+    } else {
+        TokenTier::Large
+    };
+    // Metadata only, so no file is read just for a badge
+    let has_scripts = skill_content_files(&skill_dir).any(|(path, meta)| {
+        path.starts_with("scripts/") || file_mode(&meta).is_some_and(|mode| mode & 0o111 != 0)
+    });
+
+    // An unreachable source shouldn't keep the other badges from showing
+    let update_available = match &metadata {
+        Some(metadata) if check_updates.unwrap_or(false) => skill_has_update(metadata, &content)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(skill = %name, error = %e, "update check failed");
+                None
+            }),
+        _ => None,
+    };
+
+    Ok(SkillBadges {
+        has_mcp_requirement: !required_mcp_servers(&content).is_empty(),
+        has_scripts,
+        is_pinned: metadata.as_ref().is_some_and(|m| m.pinned),
+        update_available,
+        is_symlink,
+        token_tier,
+    })
+}
+
+/// Compares the installed SKILL.md with the source's, for sources that
+/// resolve to a single SKILL.md URL: GitHub folders and direct links to the
+/// file. `None` for anything else.
+async fn skill_has_update(metadata: &SkillMetadata, installed: &str) -> Result<Option<bool>, String> {
+    let Some(source) = metadata.source.as_deref() else {
+        return Ok(None);
+    };
+    if metadata.entrypoint.is_some() {
+        return Ok(None);
+    }
+    let url = match metadata.source_kind.unwrap_or_else(|| classify_source(Some(source))) {
+        SourceKind::GithubDir => github_skill_md_url(source.trim_start_matches("https://github.com/"))?,
+        SourceKind::DirectUrl if !source.to_lowercase().ends_with(SKILL_FILE_EXTENSION) => source.to_string(),
+        _ => return Ok(None),
+    };
+
+    let client = http_client()?;
+    let bytes = authorized_get(&client, &url, None)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| format!("Cannot fetch SKILL.md: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| e.to_string())?;
+    let latest = decode_text(&bytes, &url);
+    Ok(Some(latest.replace("\r\n", "\n").trim() != installed.replace("\r\n", "\n").trim()))
+}
+
+/// Files and sibling skills referenced by relative links in SKILL.md and
+/// the markdown files it links to
+#[tauri::command]
+fn skill_dependencies(agent: AgentType, name: String, namespace: Option<String>) -> Result<SkillDeps, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    let skill_md = find_skill_md(&skill_dir).ok_or_else(|| format!("SKILL.md not found in {}", name))?;
+    let skills_dir = skill_dir.parent().ok_or("Invalid skill path")?.to_path_buf();
+
+    let mut files = std::collections::BTreeMap::new();
+    let mut skills = std::collections::BTreeMap::new();
+    let mut queue = vec![skill_md];
+    let mut seen = std::collections::HashSet::new();
+
+    while let Some(doc) = queue.pop() {
+        if !seen.insert(doc.clone()) {
+            continue;
+        }
+        let Ok(content) = read_text(&doc) else {
+            continue;
+        };
+        let base = doc.parent().unwrap_or(&skill_dir).to_path_buf();
+
+        for target in extract_markdown_links(&content) {
+            let Some(path) = resolve_relative_link(&base, &target) else {
+                continue;
+            };
+
+            if let Ok(relative) = path.strip_prefix(&skill_dir) {
+                let exists = path.exists();
+                if exists && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("md")) {
+                    queue.push(path.clone());
+                }
+                files.insert(relative.to_string_lossy().replace('\\', "/"), exists);
+            } else if let Ok(relative) = path.strip_prefix(&skills_dir) {
+                if let Some(sibling) = relative.components().next() {
+                    let sibling = sibling.as_os_str().to_string_lossy().to_string();
+                    let installed = skills_dir.join(&sibling).is_dir();
+                    skills.insert(sibling, installed);
+                }
+            }
+        }
+    }
+
+    let mut deps = SkillDeps::default();
+    for (path, exists) in files {
+        if exists {
+            deps.files_found.push(path);
+        } else {
+            deps.files_missing.push(path);
+        }
+    }
+    for (sibling, installed) in skills {
+        if installed {
+            deps.skills_installed.push(sibling);
+        } else {
+            deps.skills_missing.push(sibling);
+        }
+    }
+    Ok(deps)
+}
+
+/// Checks an installed skill before it's updated from its source
+#[tauri::command]
+fn preview_update(agent: AgentType, name: String, namespace: Option<String>) -> Result<UpdatePreview, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    if !skill_dir.is_dir() {
+        return Err(format!("Skill not found: {}", name));
+    }
+
+    let metadata = read_metadata(&skill_dir);
+    Ok(UpdatePreview {
+        has_local_changes: has_local_changes(&skill_dir),
+        pinned: metadata.as_ref().is_some_and(|m| m.pinned),
+        source: metadata.and_then(|m| m.source),
+        name,
+    })
+}
+
+/// Content hash of a skill, stable across machines and reinstalls
+#[tauri::command]
+fn get_skill_hash(agent: AgentType, name: String, namespace: Option<String>) -> Result<String, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    if !skill_dir.is_dir() {
+        return Err(format!("Skill not found: {}", name));
+    }
+    hash_skill_dir(&skill_dir)
+}
+
+/// Unified diff of two skills' SKILL.md, followed by a summary of the other
+/// files that differ or exist on only one side. Empty when they match.
+#[tauri::command]
+fn compare_skills(
+    a_agent: AgentType,
+    a_name: String,
+    a_namespace: Option<String>,
+    b_agent: AgentType,
+    b_name: String,
+    b_namespace: Option<String>,
+) -> Result<String, String> {
+    let a_dir = resolve_skill_dir(a_agent, &a_name, a_namespace.as_deref())?;
+    let b_dir = resolve_skill_dir(b_agent, &b_name, b_namespace.as_deref())?;
+    // `agent/namespace/name`, as the diff headers and summary name each side
+    let label = |agent: AgentType, namespace: &Option<String>, name: &str| match namespace {
+        Some(namespace) => format!("{}/{}/{}", agent.id(), namespace, name),
+        None => format!("{}/{}", agent.id(), name),
+    };
+    let a_label = label(a_agent, &a_namespace, &a_name);
+    let b_label = label(b_agent, &b_namespace, &b_name);
+    for (dir, name) in [(&a_dir, &a_name), (&b_dir, &b_name)] {
+        if !dir.is_dir() {
+            return Err(format!("Skill not found: {}", name));
+        }
+    }
+
+    let read_manifest = |dir: &PathBuf| {
+        find_skill_md(dir)
+            .map(|path| read_text(path).map_err(|e| e.to_string()))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    };
+    let a_content = read_manifest(&a_dir)?;
+    let b_content = read_manifest(&b_dir)?;
+
+    let mut out = similar::TextDiff::from_lines(&a_content, &b_content)
+        .unified_diff()
+        .header(
+            &format!("{}/{}", a_label, SKILL_MANIFEST),
+            &format!("{}/{}", b_label, SKILL_MANIFEST),
+        )
+        .to_string();
+
+    // Manifests were compared above, whatever each agent calls them
+    let other_files = |dir: &Path| -> Result<HashMap<String, Vec<u8>>, String> {
+        Ok(collect_skill_files(dir)?
+            .into_iter()
+            .filter(|f| f.path != ".metadata.json" && !is_junk_path(&f.path) && !is_skill_manifest_name(&f.path))
+            .map(|f| (f.path, f.content))
+            .collect())
+    };
+    let a_files = other_files(&a_dir)?;
+    let b_files = other_files(&b_dir)?;
+
+    let mut paths: Vec<&String> = a_files.keys().chain(b_files.keys()).collect();
+    paths.sort();
+    paths.dedup();
+    for path in paths {
+        let line = match (a_files.get(path), b_files.get(path)) {
+            (Some(a), Some(b)) if a == b => continue,
+            (Some(_), Some(_)) => format!("Files differ: {}", path),
+            (Some(_), None) => format!("Only in {}: {}", a_label, path),
+            _ => format!("Only in {}: {}", b_label, path),
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+#[tauri::command]
+fn list_skill_files(
+    agent: AgentType,
+    name: String,
+    subpath: Option<String>,
+    namespace: Option<String>,
+) -> Result<Vec<FileItem>, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    let mut target_dir = skill_dir.clone();
+
+    if let Some(sub) = subpath {
+        target_dir = target_dir.join(sub);
+    }
+
+    if !target_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let canonical_target = target_dir.canonicalize().map_err(|e| e.to_string())?;
+    if !canonical_target.starts_with(skill_dir.canonicalize().map_err(|e| e.to_string())?) {
+        return Err("Access denied: path outside skill directory".to_string());
+    }
+
+    let mut items = Vec::new();
+    let entries = fs::read_dir(&target_dir).map_err(|e| e.to_string())?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string();
+
+        // Skip hidden files except .metadata.json
+        if (file_name.starts_with('.') && file_name != ".metadata.json") || is_junk_file(&file_name) {
+            continue;
+        }
+
+        let is_directory = path.is_dir();
+        let size = if is_directory {
+            None
+        } else {
+            fs::metadata(&path).ok().map(|m| m.len())
+        };
+
+        // Get relative path from skill root
+        let relative_path = path
+            .strip_prefix(&skill_dir)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| file_name.clone());
+
+        items.push(FileItem {
+            name: file_name,
+            path: relative_path,
+            is_directory,
+            size,
+        });
+    }
+
+    // Sort: directories first, then by name
+    items.sort_by(|a, b| {
+        match (a.is_directory, b.is_directory) {
+            (true, false) => std::cmp::Ordering::Less,
+            (false, true) => std::cmp::Ordering::Greater,
+            _ => a.name.cmp(&b.name),
+        }
+    });
+
+    Ok(items)
+}
+
+#[tauri::command]
+fn read_skill_file(
+    agent: AgentType,
+    name: String,
+    file_path: String,
+    namespace: Option<String>,
+) -> Result<String, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    let full_path = skill_dir.join(&file_path);
+
+    // Security: ensure path doesn't escape skill directory
+    let canonical = full_path.canonicalize().map_err(|e| e.to_string())?;
+    let skill_dir = skill_dir.canonicalize().map_err(|e| e.to_string())?;
+
+    if !canonical.starts_with(&skill_dir) {
+        return Err("Access denied: path outside skill directory".to_string());
+    }
+
+    read_text(&canonical).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(agent = agent.id(), url = %redact_url(&url)), err)]
+async fn install_skill_from_url(
+    app: AppHandle,
+    agent: AgentType,
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    options: Option<InstallOptions>,
+) -> Result<Vec<InstallResult>, String> {
+    let target = redact_url(&url);
+    let result = fetch_and_install_from_url(&app, agent, url, headers, options).await;
+    record_install_activity(&app, agent, target, &result);
+    result
+}
+
+/// Installs every skill in a GitHub repo (`owner/repo[@ref]` or its URL)
+/// whose SKILL.md path or directory matches `pattern`, e.g.
+/// `skills/*/SKILL.md`. `*` and `?` stay within a path segment, `**` spans
+/// any number. A path after the repo is the root `pattern` is relative to.
+/// Emits `glob-install-progress` as each skill finishes.
+#[tauri::command]
+#[tracing::instrument(skip(app, options), fields(agent = agent.id()), err)]
+async fn install_skills_from_github_glob(
+    app: AppHandle,
+    agent: AgentType,
+    repo_url: String,
+    pattern: String,
+    options: Option<InstallOptions>,
+) -> Result<Vec<GlobInstallResult>, String> {
+    let repo = repo_url.trim().trim_start_matches("https://github.com/").trim_end_matches('/');
+    let shorthand = parse_github_shorthand(repo).ok_or_else(|| format!("Not a GitHub repo: {}", repo_url))?;
+    let (owner, repo) = (shorthand.owner, shorthand.repo);
+    let reference = match shorthand.reference {
+        Some(reference) => reference,
+        None => fetch_github_default_branch(&owner, &repo).await?,
+    };
+    let pattern = pattern.trim().trim_matches('/');
+    let pattern = if shorthand.path.is_empty() {
+        pattern.to_string()
+    } else {
+        format!("{}/{}", shorthand.path, pattern)
+    };
+
+    let client = http_client()?;
+    let dirs = find_github_skill_dirs(&client, &owner, &repo, &reference, &pattern).await?;
+    if dirs.is_empty() {
+        return Err(format!("No skills in {}/{} match {}", owner, repo, pattern));
+    }
+
+    let total = dirs.len();
+    let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(GLOB_INSTALL_PARALLELISM));
+    let mut tasks = tokio::task::JoinSet::new();
+    for dir in dirs {
+        let permit = semaphore.clone().acquire_owned().await.map_err(|e| e.to_string())?;
+        let mut url = format!("https://github.com/{}/{}/tree/{}", owner, repo, reference);
+        if !dir.is_empty() {
+            url.push('/');
+            url.push_str(&dir);
+        }
+        let (app, options) = (app.clone(), options.clone());
+        tasks.spawn(async move {
+            let _permit = permit;
+            let result = fetch_and_install_from_url(&app, agent, url.clone(), None, options).await;
+            record_install_activity(&app, agent, url, &result);
+            match result {
+                Ok(results) => GlobInstallResult { path: dir, results, error: None },
+                Err(e) => GlobInstallResult { path: dir, results: Vec::new(), error: Some(e) },
+            }
+        });
+    }
+
+    let mut installed = Vec::with_capacity(total);
+    while let Some(joined) = tasks.join_next().await {
+        let result = joined.map_err(|e| e.to_string())?;
+        let progress = GlobInstallProgress { agent, path: result.path.clone(), done: installed.len() + 1, total };
+        let _ = app.emit("glob-install-progress", progress);
+        installed.push(result);
+    }
+
+    installed.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(installed)
+}
+
+/// Installs a fresh copy from the recorded source, replacing every file of
+/// the current one (which goes to trash). Pin and notes carry over.
+#[tauri::command]
+#[tracing::instrument(skip(app, agent, namespace), fields(agent = agent.id()), err)]
+async fn reinstall_skill(
+    app: AppHandle,
+    agent: AgentType,
+    name: String,
+    namespace: Option<String>,
+) -> Result<Vec<InstallResult>, String> {
+    refresh_skill_from_source(&app, agent, &name, namespace, true, false).await
+}
+
+/// Refreshes a skill from the source in its metadata, a direct file URL or
+/// a GitHub `/tree/` URL going through the matching install path. Keeps
+/// `installed_at` (only `updated_at` moves) along with the pin, notes and
+/// favorite; a pinned skill comes back skipped unless `force` is set. The
+/// old copy stays in place until the new one is fully downloaded and staged.
+#[tauri::command]
+#[tracing::instrument(skip(app, agent, namespace), fields(agent = agent.id()), err)]
+async fn update_skill(
+    app: AppHandle,
+    agent: AgentType,
+    name: String,
+    namespace: Option<String>,
+    force: Option<bool>,
+) -> Result<Vec<InstallResult>, String> {
+    refresh_skill_from_source(&app, agent, &name, namespace, force.unwrap_or(false), true).await
+}
+
+/// Installs a skill again from the source in its metadata, for
+/// `update_skill` and `reinstall_skill`. Pin, notes and favorite carry over
+/// to the new copy, and `installed_at` too with `keep_installed_at`. A
+/// source that has renamed the skill since leaves the old copy displaced.
+async fn refresh_skill_from_source(
+    app: &AppHandle,
+    agent: AgentType,
+    name: &str,
+    namespace: Option<String>,
+    force: bool,
+    keep_installed_at: bool,
+) -> Result<Vec<InstallResult>, String> {
+    let skill_dir = resolve_skill_dir(agent, name, namespace.as_deref())?;
+    if !skill_dir.is_dir() {
+        return Err(format!("Skill not found: {}", name));
+    }
+    let metadata = read_metadata(&skill_dir);
+    let Some(source) = metadata.as_ref().and_then(|m| m.source.clone()) else {
+        return Err(format!("{} has no remembered source", name));
+    };
+    if matches!(classify_source(Some(&source)), SourceKind::Local | SourceKind::Zip) {
+        return Err(format!("{} was installed from a local file; install it again from there", name));
+    }
+
+    let options = InstallOptions {
+        namespace,
+        on_conflict: ConflictPolicy::Replace,
+        force,
+        skill_md_only: metadata.as_ref().is_some_and(|m| m.skill_md_only),
+        entrypoint: metadata.as_ref().and_then(|m| m.entrypoint.clone()),
+    };
+    let target = redact_url(&source);
+    let result = async {
+        let results = fetch_and_install_from_url(app, agent, source, None, Some(options)).await?;
+
+        for installed in &results {
+            if !matches!(installed.status, InstallStatus::Installed | InstallStatus::Replaced) {
+                continue;
+            }
+            let installed_dir = PathBuf::from(&installed.path);
+            if let Some(previous) = &metadata {
+                update_metadata(&installed_dir, &installed.name, |m| {
+                    m.pinned = previous.pinned;
+                    m.notes = previous.notes.clone();
+                    m.favorite = previous.favorite;
+                    if keep_installed_at {
+                        m.installed_at = previous.installed_at.clone();
+                    }
+                })?;
+            }
+            // The source may have renamed the skill since it was installed
+            if installed_dir != skill_dir {
+                displace_existing_skill(app, agent, &skill_dir)?;
+            }
+        }
+        Ok(results)
+    }
+    .await;
+    record_install_activity(app, agent, target, &result);
+    result
+}
+
+async fn fetch_and_install_from_url(
+    app: &AppHandle,
+    agent: AgentType,
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    options: Option<InstallOptions>,
+) -> Result<Vec<InstallResult>, String> {
+    fetch_and_install_with_progress(app, agent, url, headers, options, &|_| {}).await
+}
+
+/// `fetch_and_install_from_url`, calling `on_file` with each file's path
+/// as a GitHub directory install downloads it
+async fn fetch_and_install_with_progress(
+    app: &AppHandle,
+    agent: AgentType,
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    options: Option<InstallOptions>,
+    on_file: &(dyn Fn(&str) + Send + Sync),
+) -> Result<Vec<InstallResult>, String> {
+    let options = options.unwrap_or_default();
+    if url.trim_start().starts_with("data:") {
+        let skill = prepare_skill_from_data_url(url.trim())?;
+        let mut results = install_prepared(app, agent, skill, &options)?;
+        run_post_install_hooks(app, &mut results).await;
+        return Ok(results);
+    }
+    let url = normalize_source(&url);
+    let url = match parse_github_shorthand(&url).or_else(|| parse_github_repo_url(&url)) {
+        Some(shorthand) => expand_github_shorthand(shorthand).await?,
+        None => url,
+    };
+    let url = url.as_str();
+
+    // Check if it's a GitHub directory URL
+    let mut staging = None;
+    let skill = if url.starts_with("oci://") {
+        let zip_data = fetch_oci_artifact(url).await?;
+        prepare_skill_from_archive(&zip_data, url.to_string(), options.entrypoint.as_deref(), false)?
+    } else if url.contains("github.com") && url.contains("/tree/") {
+        let token = pending_install_token(url);
+        let dir = get_downloads_dir()?.join(&token);
+        fs::create_dir_all(dir.join("files")).map_err(|e| e.to_string())?;
+        let pending = PendingInstall {
+            token,
+            url: url.to_string(),
+            agent,
+            options: options.clone(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+        };
+        let json = serde_json::to_string_pretty(&pending).map_err(|e| e.to_string())?;
+        fs::write(dir.join("pending.json"), json).map_err(|e| e.to_string())?;
+
+        let skill = fetch_github_dir(url, &dir.join("files"), options.entrypoint.as_deref(), on_file)
+            .await
+            .map_err(|e| format!("{} (resume with token {})", e, pending.token))?;
+        staging = Some(dir);
+        skill
+    } else {
+        // Direct file URL
+        let client = reqwest::Client::new();
+        let response = authorized_get(&client, url, headers.as_ref())
+            .header(reqwest::header::ACCEPT_ENCODING, "gzip")
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let response = check_response(response).await?;
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        let lower = url.to_lowercase();
+        let is_skill_file = lower.ends_with(SKILL_FILE_EXTENSION) || content_type.starts_with(SKILL_FILE_MIME);
+        let is_archive = is_skill_file || lower.ends_with(".zip") || content_type.starts_with("application/zip");
+
+        // Stream to disk so a large download never sits in memory whole
+        let downloads_dir = get_downloads_dir()?;
+        fs::create_dir_all(&downloads_dir).map_err(|e| e.to_string())?;
+        // Concurrent installs of the same URL each get their own file
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        let download = downloads_dir.join(format!(
+            "{}-{}-{}.download",
+            pending_install_token(url),
+            std::process::id(),
+            nanos
+        ));
+        let prepared = async {
+            download_to_file(response, &download).await?;
+            if is_archive {
+                let file = fs::File::open(&download).map_err(|e| e.to_string())?;
+                prepare_skill_from_reader(file, url.to_string(), options.entrypoint.as_deref(), is_skill_file)
+            } else {
+                let content = read_text(&download).map_err(|e| e.to_string())?;
+                Ok(PreparedSkill {
+                    name: extract_skill_name(&content, url),
+                    files: vec![SkillFile::new(SKILL_MANIFEST, content.into_bytes())],
+                    source: Some(url.to_string()),
+                })
+            }
+        }
+        .await;
+        let _ = fs::remove_file(&download);
+        prepared?
+    };
+
+    let mut results = install_prepared(app, agent, skill, &options)?;
+    if let Some(dir) = staging {
+        let _ = fs::remove_dir_all(dir);
+    }
+    run_post_install_hooks(app, &mut results).await;
+    Ok(results)
+}
+
+/// GitHub installs that failed partway and can be resumed
+#[tauri::command]
+fn list_pending_installs() -> Result<Vec<PendingInstall>, String> {
+    let downloads_dir = get_downloads_dir()?;
+    if !downloads_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut pending: Vec<PendingInstall> = fs::read_dir(&downloads_dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .filter_map(|e| fs::read_to_string(e.path().join("pending.json")).ok())
+        .filter_map(|json| serde_json::from_str(&json).ok())
+        .collect();
+    pending.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    Ok(pending)
+}
+
+/// Retries a pending install with its original agent and options. Files
+/// already downloaded are read from the staging folder, not fetched again.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+async fn resume_install(app: AppHandle, token: String) -> Result<Vec<InstallResult>, String> {
+    let pending = read_pending_install(&token)?;
+    install_skill_from_url(app, pending.agent, pending.url, None, Some(pending.options)).await
+}
+
+/// Runs `install_skill_from_url` as a background job and returns its id
+/// at once. The job reports through `job-progress` when it starts and as
+/// each file downloads, then `job-complete` or `job-failed`, each carrying
+/// its `JobInfo`.
+#[tauri::command]
+fn start_install(
+    app: AppHandle,
+    jobs: tauri::State<'_, InstallJobs>,
+    agent: AgentType,
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    options: Option<InstallOptions>,
+) -> Result<String, String> {
+    let id = jobs.next_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+    let info = JobInfo {
+        id: id.to_string(),
+        agent,
+        source: redact_url(&url),
+        status: JobStatus::Running,
+        started_at: chrono::Utc::now().to_rfc3339(),
+        finished_at: None,
+        files_downloaded: 0,
+        results: vec![],
+        error: None,
+    };
+    {
+        let mut running = jobs.jobs.lock().map_err(|e| e.to_string())?;
+        // Spawned under the lock, so a cancel can't come before the handle
+        // is stored; the task can't finish before then either, as `finish`
+        // waits for the lock
+        let task_app = app.clone();
+        let task = tauri::async_runtime::spawn(async move {
+            let target = redact_url(&url);
+            let jobs = task_app.state::<InstallJobs>();
+            let on_file = |_: &str| jobs.file_done(&task_app, id);
+            let result = fetch_and_install_with_progress(&task_app, agent, url, headers, options, &on_file).await;
+            record_install_activity(&task_app, agent, target, &result);
+            jobs.finish(&task_app, id, result);
+        });
+        running.insert(id, InstallJob { info: info.clone(), task: Some(task) });
+        // Forget the oldest finished jobs past the cap
+        let finished: Vec<u64> = running
+            .iter()
+            .filter(|(_, job)| job.info.status != JobStatus::Running)
+            .map(|(id, _)| *id)
+            .collect();
+        for old in finished.iter().take(finished.len().saturating_sub(JOB_HISTORY_MAX)) {
+            running.remove(old);
+        }
+    }
+    let _ = app.emit("job-progress", &info);
+    Ok(id.to_string())
+}
+
+#[tauri::command]
+fn get_job(jobs: tauri::State<'_, InstallJobs>, job_id: String) -> Result<JobInfo, String> {
+    let jobs = jobs.jobs.lock().map_err(|e| e.to_string())?;
+    job_id
+        .parse::<u64>()
+        .ok()
+        .and_then(|id| jobs.get(&id))
+        .map(|job| job.info.clone())
+        .ok_or_else(|| format!("Unknown job: {}", job_id))
+}
+
+/// Running and recently finished background installs, oldest first
+#[tauri::command]
+fn list_jobs(jobs: tauri::State<'_, InstallJobs>) -> Result<Vec<JobInfo>, String> {
+    let jobs = jobs.jobs.lock().map_err(|e| e.to_string())?;
+    Ok(jobs.values().map(|job| job.info.clone()).collect())
+}
+
+/// Stops a running background install. Files are only written once
+/// everything is downloaded, so a cancelled job leaves no partial skill;
+/// any staged download is removed by `cleanup`.
+#[tauri::command]
+fn cancel_install(app: AppHandle, jobs: tauri::State<'_, InstallJobs>, job_id: String) -> Result<JobInfo, String> {
+    let info = {
+        let mut jobs = jobs.jobs.lock().map_err(|e| e.to_string())?;
+        let job = job_id
+            .parse::<u64>()
+            .ok()
+            .and_then(|id| jobs.get_mut(&id))
+            .ok_or_else(|| format!("Unknown job: {}", job_id))?;
+        if job.info.status != JobStatus::Running {
+            return Err("This install has already finished".to_string());
+        }
+        if let Some(task) = job.task.take() {
+            task.abort();
+        }
+        job.info.status = JobStatus::Cancelled;
+        job.info.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        job.info.error = Some("Cancelled".to_string());
+        job.info.clone()
+    };
+    let _ = app.emit("job-failed", &info);
+    Ok(info)
+}
+
+/// Drops a pending install and its partial downloads
+#[tauri::command]
+fn discard_pending_install(app: AppHandle, token: String) -> Result<(), String> {
+    let result = read_pending_install(&token).and_then(|_| {
+        fs::remove_dir_all(get_downloads_dir()?.join(&token)).map_err(|e| e.to_string())
+    });
+    record_activity(&app, ActivityEvent::new(ActivityKind::DiscardInstall, None, &token, &result));
+    result
+}
+
+fn read_pending_install(token: &str) -> Result<PendingInstall, String> {
+    if token.is_empty() || !token.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("Invalid resume token".to_string());
+    }
+
+    let path = get_downloads_dir()?.join(token).join("pending.json");
+    let json = fs::read_to_string(path).map_err(|_| "No pending install for this token".to_string())?;
+    serde_json::from_str(&json).map_err(|e| e.to_string())
+}
+
+/// Installs pasted or dropped text as SKILL.md. Text without a frontmatter
+/// block is refused unless `force` is set, since it's usually the wrong
+/// thing pasted.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(agent = agent.id(), filename = %filename), err)]
+async fn install_skill_from_content(
+    app: AppHandle,
+    agent: AgentType,
+    content: String,
+    filename: String,
+    options: Option<InstallOptions>,
+    force: Option<bool>,
+) -> Result<Vec<InstallResult>, String> {
+    let options = options.unwrap_or_default();
+
+    // write_skill reports a missing name itself, with the name it fell back to
+    let content_warnings: Vec<String> = frontmatter_warnings(&content)
+        .into_iter()
+        .filter(|w| !w.contains("`name`"))
+        .collect();
+    let has_frontmatter = frontmatter_block(&content).is_some();
+
+    let result = if !has_frontmatter && !force.unwrap_or(false) {
+        Err("Not a skill: the content has no frontmatter block (install with force to use it anyway)".to_string())
+    } else {
+        let skill = PreparedSkill {
+            name: extract_skill_name(&content, &filename),
+            files: vec![SkillFile::new(SKILL_MANIFEST, content.into_bytes())],
+            source: None,
+        };
+        install_prepared(&app, agent, skill, &options)
+    };
+    let result = match result {
+        Ok(mut results) => {
+            for installed in &mut results {
+                installed.warnings.extend(content_warnings.iter().cloned());
+            }
+            run_post_install_hooks(&app, &mut results).await;
+            Ok(results)
+        }
+        Err(e) => Err(e),
+    };
+    record_install_activity(&app, agent, filename, &result);
+    result
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(agent = agent.id(), source = %redact_url(&source)), err)]
+async fn install_skill_from_zip(
+    app: AppHandle,
+    agent: AgentType,
+    zip_base64: String,
+    source: String,
+    options: Option<InstallOptions>,
+) -> Result<Vec<GlobInstallResult>, String> {
+    let options = options.unwrap_or_default();
+    let target = redact_url(&source);
+
+    let skills = match prepare_skills_from_zip(&zip_base64, source, options.entrypoint.as_deref()) {
+        Ok(skills) => skills,
+        Err(e) => {
+            record_install_activity(&app, agent, target, &Err(e.clone()));
+            return Err(e);
+        }
+    };
+    // Each skill gets its own conflict check, and one that fails doesn't
+    // stop the others
+    let mut installed = Vec::with_capacity(skills.len());
+    for skill in skills {
+        let path = skill.name.clone();
+        let result = match install_prepared(&app, agent, skill, &options) {
+            Ok(mut results) => {
+                run_post_install_hooks(&app, &mut results).await;
+                Ok(results)
+            }
+            Err(e) => Err(e),
+        };
+        record_install_activity(&app, agent, format!("{} ({})", target, path), &result);
+        installed.push(match result {
+            Ok(results) => GlobInstallResult { path, results, error: None },
+            Err(e) => GlobInstallResult { path, results: Vec::new(), error: Some(e) },
+        });
+    }
+    Ok(installed)
+}
+
+/// A skill carried inside a `data:` URL: an archive when the media type is
+/// ZIP or `.skill`, otherwise the SKILL.md text. Nothing is fetched and no
+/// source is recorded.
+fn prepare_skill_from_data_url(url: &str) -> Result<PreparedSkill, String> {
+    let (header, data) = url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(','))
+        .ok_or("Invalid data URL")?;
+    let mut params = header.split(';');
+    let media_type = params.next().unwrap_or("").to_lowercase();
+    let bytes = if params.any(|p| p.eq_ignore_ascii_case("base64")) {
+        STANDARD
+            .decode(data.trim())
+            .map_err(|e| format!("Invalid base64 in data URL: {}", e))?
+    } else {
+        urlencoding::decode_binary(data.as_bytes()).into_owned()
+    };
+
+    if media_type == "application/zip" || media_type == SKILL_FILE_MIME {
+        let mut skill = prepare_skill_from_archive(&bytes, "skill.zip".to_string(), None, media_type == SKILL_FILE_MIME)?;
+        skill.source = None;
+        return Ok(skill);
+    }
+
+    let content = String::from_utf8(bytes).map_err(|_| "Data URL content is not UTF-8 text")?;
+    if frontmatter_block(&content).is_none() {
+        return Err("Not a skill: the data URL content has no frontmatter block".to_string());
+    }
+    Ok(PreparedSkill {
+        name: extract_skill_name(&content, "skill"),
+        files: vec![SkillFile::new(SKILL_MANIFEST, content.into_bytes())],
+        source: None,
+    })
+}
+
+/// Reads a QR code from a PNG or JPEG screenshot and returns the URL or
+/// `owner/repo/path` shorthand it contains, so it can be shown before
+/// `install_skill_from_url` installs it
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+fn resolve_qr_url(image_base64: String) -> Result<String, String> {
+    let image_base64 = image_base64.as_str();
+    // Accept a pasted `data:image/png;base64,...` as well as bare base64
+    let encoded = image_base64.rsplit_once(',').map_or(image_base64, |(_, data)| data);
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("Invalid base64: {}", e))?;
+    let image = image::load_from_memory(&bytes)
+        .map_err(|e| format!("Cannot read image: {}", e))?
+        .to_luma8();
+
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        image.width() as usize,
+        image.height() as usize,
+        |x, y| image.get_pixel(x as u32, y as u32).0[0],
+    );
+    let grid = prepared.detect_grids().into_iter().next().ok_or("No QR code found in the image")?;
+    let (_, content) = grid.decode().map_err(|e| format!("Cannot decode QR code: {}", e))?;
+
+    let content = content.trim().to_string();
+    if content.is_empty() {
+        return Err("The QR code is empty".to_string());
+    }
+    Ok(content)
+}
+
+/// One skill per top-level SKILL.md: a ZIP bundling skills in sibling
+/// folders yields each of them, while SKILL.md files nested inside another
+/// skill's folder stay part of that skill. A `source` named `*.skill` is
+/// read as a `.skill` file.
+fn prepare_skills_from_zip(
+    zip_base64: &str,
+    source: String,
+    entrypoint: Option<&str>,
+) -> Result<Vec<PreparedSkill>, String> {
+    let zip_data = STANDARD
+        .decode(zip_base64)
+        .map_err(|e| format!("Invalid base64: {}", e))?;
+
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(&zip_data)).map_err(|e| format!("Invalid ZIP: {}", e))?;
+    let skill_file = source.to_lowercase().ends_with(SKILL_FILE_EXTENSION);
+    let manifests = if skill_file {
+        vec![]
+    } else {
+        top_level_zip_skill_mds(&mut archive, entrypoint)?
+    };
+    if manifests.len() < 2 {
+        return prepare_skill_from_archive(&zip_data, source, entrypoint, skill_file).map(|skill| vec![skill]);
+    }
+
+    let mut skills = Vec::new();
+    // One running total, so the size cap covers the whole archive
+    let mut total_size = 0;
+    for manifest in manifests {
+        let (prefix, content) = read_zip_skill_md(&mut archive, &manifest)?;
+        let folder = prefix.trim_end_matches('/').rsplit('/').next().unwrap_or(&source);
+        let name = extract_skill_name(&content, folder);
+        let mut files = collect_zip_files(&mut archive, &prefix, &mut total_size)?;
+        if let Some(entrypoint) = entrypoint {
+            use_entrypoint(&mut files, entrypoint)?;
+        }
+        skills.push(PreparedSkill {
+            name,
+            files,
+            source: Some(source.clone()),
+        });
+    }
+    Ok(skills)
+}
+
+/// A plain ZIP, or with `skill_file` a `.skill` file, which must carry a
+/// valid `skill.json` manifest. A plain ZIP's `skill.json` is just another
+/// file. An `entrypoint` locates the skill by that file name instead of
+/// SKILL.md.
+fn prepare_skill_from_archive(
+    zip_data: &[u8],
+    source: String,
+    entrypoint: Option<&str>,
+    skill_file: bool,
+) -> Result<PreparedSkill, String> {
+    prepare_skill_from_reader(Cursor::new(zip_data), source, entrypoint, skill_file)
+}
+
+/// `prepare_skill_from_archive` for an archive read straight from a file
+fn prepare_skill_from_reader<R: Read + std::io::Seek>(
+    reader: R,
+    source: String,
+    entrypoint: Option<&str>,
+    skill_file: bool,
+) -> Result<PreparedSkill, String> {
+    let mut archive =
+        zip::ZipArchive::new(reader).map_err(|e| format!("Invalid ZIP: {}", e))?;
+
+    if skill_file {
+        let manifest = read_skill_file_manifest(&mut archive)
+            .ok_or_else(|| format!("Not a valid .skill file: missing or invalid {}", SKILL_FILE_MANIFEST))?;
+        return prepare_skill_from_skill_file(&mut archive, manifest, source);
+    }
+
+    // First pass: find SKILL.md and get prefix
+    let (skill_path_prefix, content) = find_zip_skill_md(&mut archive, entrypoint)?
+        .ok_or_else(|| format!("No {} found in ZIP", entrypoint.unwrap_or(SKILL_MANIFEST)))?;
+    let name = extract_skill_name(&content, &source);
+
+    // Second pass: collect files
+    let mut files = collect_zip_files(&mut archive, &skill_path_prefix, &mut 0)?;
+    if let Some(entrypoint) = entrypoint {
+        use_entrypoint(&mut files, entrypoint)?;
+    }
+
+    Ok(PreparedSkill {
+        name,
+        files,
+        source: Some(source),
+    })
+}
+
+/// The archive's files under `prefix`, with paths relative to it.
+/// `total_size` carries the bytes read across calls on the same archive.
+fn collect_zip_files<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    prefix: &str,
+    total_size: &mut u64,
+) -> Result<Vec<SkillFile>, String> {
+    let mut files = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+        let file_name = file.name().to_string();
+
+        if is_junk_path(&file_name) || file.is_dir() {
+            continue;
+        }
+
+        let Some(relative_path) = file_name.strip_prefix(prefix) else {
+            continue;
+        };
+        if relative_path.is_empty() {
+            continue;
+        }
+        if file.enclosed_name().is_none() {
+            return Err(format!("ZIP entry escapes the archive root: {}", file_name));
+        }
+
+        let file_content = read_zip_entry(&mut file, &file_name, total_size)?;
+        files.push(SkillFile {
+            path: relative_path.to_string(),
+            content: file_content,
+            mode: file.unix_mode(),
+        });
+    }
+
+    Ok(files)
+}
+
+/// Reads one archive entry, refusing what `preview_zip` flags: a
+/// compression ratio past `MAX_ZIP_COMPRESSION_RATIO`, or the archive
+/// expanding past `MAX_ZIP_UNCOMPRESSED_SIZE`. `total_size` adds up the
+/// entries read so far; an entry is never read past its declared size.
+fn read_zip_entry(file: &mut zip::read::ZipFile<'_>, name: &str, total_size: &mut u64) -> Result<Vec<u8>, String> {
+    let size = file.size();
+    if file.compressed_size() > 0 && size / file.compressed_size() > MAX_ZIP_COMPRESSION_RATIO {
+        return Err(format!("Refusing ZIP: suspicious compression ratio for {}", name));
+    }
+    *total_size += size;
+    if *total_size > MAX_ZIP_UNCOMPRESSED_SIZE {
+        return Err(format!(
+            "Refusing ZIP: it expands past {} MB",
+            MAX_ZIP_UNCOMPRESSED_SIZE / (1024 * 1024)
+        ));
+    }
+
+    let mut content = Vec::new();
+    file.take(size.saturating_add(1))
+        .read_to_end(&mut content)
+        .map_err(|e| e.to_string())?;
+    if content.len() as u64 > size {
+        return Err(format!("Refusing ZIP: {} is larger than it claims", name));
+    }
+    Ok(content)
+}
+
+/// Installs `entrypoint` as the skill's SKILL.md, keeping the original file
+/// alongside. A SKILL.md the source also has is replaced.
+fn use_entrypoint(files: &mut Vec<SkillFile>, entrypoint: &str) -> Result<(), String> {
+    let entrypoint = entrypoint.trim_start_matches("./");
+    let file = files
+        .iter()
+        .find(|f| f.path.eq_ignore_ascii_case(entrypoint))
+        .ok_or_else(|| format!("Entrypoint {} not found in the source", entrypoint))?;
+    let manifest = SkillFile::new(SKILL_MANIFEST, file.content.clone());
+
+    files.retain(|f| !is_skill_manifest_name(&f.path));
+    files.push(manifest);
+    Ok(())
+}
+
+/// Runs the configured hook for each fresh install. Hooks that are enabled
+/// but not yet approved are skipped with a warning.
+async fn run_post_install_hooks(app: &AppHandle, results: &mut [InstallResult]) {
+    let Ok(settings) = load_settings() else {
+        return;
+    };
+
+    for result in results.iter_mut() {
+        if !matches!(result.status, InstallStatus::Installed | InstallStatus::Replaced) {
+            continue;
+        }
+
+        let hook = settings
+            .agent_post_install_hooks
+            .get(result.agent.id())
+            .or(settings.post_install_hook.as_ref());
+        let Some(hook) = hook.filter(|h| h.enabled && !h.command.trim().is_empty()) else {
+            continue;
+        };
+        if hook.approved_command.as_deref() != Some(hook.command.as_str()) {
+            result
+                .warnings
+                .push("Post-install hook not run: review and approve it in settings first".to_string());
+            continue;
+        }
+
+        let command = hook.command.replace("{skill_dir}", &shell_quote(&result.path));
+        result.hook = Some(run_hook(app, command, &result.path).await);
+    }
+}
+
+async fn run_hook(app: &AppHandle, command: String, skill_dir: &str) -> HookOutput {
+    use tauri_plugin_shell::ShellExt;
+
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/C");
+    #[cfg(not(windows))]
+    let (shell, flag) = ("sh", "-c");
+
+    let output = app
+        .shell()
+        .command(shell)
+        .args([flag, command.as_str()])
+        .current_dir(skill_dir)
+        .output()
+        .await;
+
+    match output {
+        Ok(output) => HookOutput {
+            command,
+            exit_code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        },
+        Err(e) => HookOutput {
+            command,
+            exit_code: None,
+            stdout: String::new(),
+            stderr: e.to_string(),
+        },
+    }
+}
+
+#[cfg(windows)]
+fn shell_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+#[cfg(not(windows))]
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn read_skill_file_manifest<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Option<SkillFileManifest> {
+    let mut file = archive.by_name(SKILL_FILE_MANIFEST).ok()?;
+    let mut content = String::new();
+    file.read_to_string(&mut content).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Unpacks a `.skill` file, enforcing its manifest: a supported format
+/// version, a name, a SKILL.md and a matching checksum
+fn prepare_skill_from_skill_file<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    manifest: SkillFileManifest,
+    source: String,
+) -> Result<PreparedSkill, String> {
+    if manifest.format_version > SKILL_FILE_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported .skill format version {} (this app reads up to {})",
+            manifest.format_version, SKILL_FILE_FORMAT_VERSION
+        ));
+    }
+    if manifest.name.trim().is_empty() {
+        return Err(".skill manifest has no name".to_string());
+    }
+
+    let mut files = Vec::new();
+    let mut total_size = 0;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+        if file.is_dir() {
+            continue;
+        }
+        let Some(path) = file.enclosed_name() else {
+            continue;
+        };
+        let path = path.to_string_lossy().replace('\\', "/");
+        if path == SKILL_FILE_MANIFEST || is_junk_path(&path) {
+            continue;
+        }
+
+        let content = read_zip_entry(&mut file, &path, &mut total_size)?;
+        files.push(SkillFile {
+            path,
+            content,
+            mode: file.unix_mode(),
+        });
+    }
+
+    if !files.iter().any(|f| is_skill_manifest_name(&f.path)) {
+        return Err("No SKILL.md found in .skill file".to_string());
+    }
+    let checksum = format!("sha256:{}", hash_skill_files(&files));
+    if !manifest.checksum.eq_ignore_ascii_case(&checksum) {
+        return Err(".skill checksum mismatch; the file may be corrupted".to_string());
+    }
+
+    Ok(PreparedSkill {
+        name: manifest.name,
+        files,
+        source: Some(source),
+    })
+}
+
+/// Installs into one agent, or every agent for `All`. For `All` with
+/// `ConflictPolicy::Ask`, nothing is written if any agent already has a
+/// different skill by this name; those conflicts are returned instead.
+fn install_prepared(
+    app: &AppHandle,
+    agent: AgentType,
+    skill: PreparedSkill,
+    options: &InstallOptions,
+) -> Result<Vec<InstallResult>, String> {
+    if agent != AgentType::All {
+        // Installing to one agent was an explicit choice, so only warn
+        let missing = missing_capabilities(agent, &skill.files);
+        let mut result = write_skill(app, agent, &skill.name, skill.files, skill.source, options)?;
+        if !missing.is_empty() {
+            result.warnings.push(format!("This skill {}", missing_capabilities_message(agent, &missing)));
+        }
+        return Ok(vec![result]);
+    }
+
+    let agents = get_all_scope_agents();
+
+    if options.on_conflict == ConflictPolicy::Ask {
+        let mut conflicts = Vec::new();
+        for individual_agent in &agents {
+            let Ok(skills_dir) = get_skills_root(*individual_agent, options.namespace.as_deref()) else {
+                continue;
+            };
+            let skill_dir = skills_dir.join(sanitize_name(&skill.name));
+            if is_conflicting_skill(&skill_dir, &skill.files) {
+                conflicts.push(conflict_result(*individual_agent, &skill.name, &skill_dir));
+            }
+        }
+        if !conflicts.is_empty() {
+            return Ok(conflicts);
+        }
+    }
+
+    let mut results = Vec::new();
+    for individual_agent in agents {
+        let missing = missing_capabilities(individual_agent, &skill.files);
+        if !missing.is_empty() {
+            let skill_dir = get_skills_root(individual_agent, options.namespace.as_deref())?.join(sanitize_name(&skill.name));
+            let mut result = conflict_result(individual_agent, &skill.name, &skill_dir);
+            result.status = InstallStatus::Skipped;
+            result.warnings = vec![format!("skipped: {}", missing_capabilities_message(individual_agent, &missing))];
+            results.push(result);
+            continue;
+        }
+
+        let files = skill.files.clone();
+        match write_skill(app, individual_agent, &skill.name, files, skill.source.clone(), options) {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                let skill_dir = get_skills_root(individual_agent, options.namespace.as_deref())
+                    .map(|root| root.join(sanitize_name(&skill.name)))
+                    .unwrap_or_default();
+                let mut result = conflict_result(individual_agent, &skill.name, &skill_dir);
+                result.status = InstallStatus::Failed;
+                result.warnings = vec![e];
+                results.push(result);
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Symlinks a skill author's working directory into the agent's skills dir,
+/// so edits show up without reinstalling. Changes under it are reported as
+/// `dev-skill-changed` events.
+#[tauri::command]
+fn link_dev_skill(app: AppHandle, agent: AgentType, source_dir: String) -> Result<DevLink, String> {
+    let target = source_dir.clone();
+    let result = create_dev_link(&app, agent, &source_dir);
+    record_activity(&app, ActivityEvent::new(ActivityKind::LinkDevSkill, Some(agent), target, &result));
+    result
+}
+
+fn create_dev_link(app: &AppHandle, agent: AgentType, source_dir: &str) -> Result<DevLink, String> {
+    if agent == AgentType::All {
+        return Err("Link a dev skill to one agent at a time".to_string());
+    }
+
+    let source = fs::canonicalize(source_dir.trim()).map_err(|e| format!("{}: {}", source_dir, e))?;
+    if !source.is_dir() {
+        return Err(format!("{} is not a folder", source.display()));
+    }
+    let skill_md = find_skill_md(&source)
+        .filter(|p| p.parent() == Some(source.as_path()))
+        .ok_or("No SKILL.md at the top of this folder")?;
+
+    let content = read_text(&skill_md).map_err(|e| e.to_string())?;
+    let fallback = source.file_name().and_then(|n| n.to_str()).unwrap_or("skill");
+    let name = sanitize_name(&extract_skill_name(&content, fallback));
+
+    let link_path = resolve_skill_dir(agent, &name, None)?;
+    if fs::symlink_metadata(&link_path).is_ok() {
+        return Err(format!("A skill named {} already exists", name));
+    }
+    if let Some(parent) = link_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&source, &link_path).map_err(|e| e.to_string())?;
+    #[cfg(windows)]
+    std::os::windows::fs::symlink_dir(&source, &link_path)
+        .map_err(|e| format!("Cannot create symlink (Developer Mode may be required): {}", e))?;
+
+    let link = DevLink {
+        agent,
+        name,
+        source_dir: source.to_string_lossy().to_string(),
+        linked_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let mut settings = load_settings()?;
+    settings.dev_links.retain(|l| !(l.agent == agent && l.name == link.name));
+    settings.dev_links.push(link.clone());
+    save_settings(&settings)?;
+
+    if let Err(e) = watch_dev_link(app, &link) {
+        tracing::warn!("Not watching {}: {}", link.source_dir, e);
+    }
+    Ok(link)
+}
+
+/// Removes a dev link. Only the symlink goes; the working directory is
+/// never touched.
+#[tauri::command]
+fn unlink_dev_skill(app: AppHandle, agent: AgentType, name: String) -> Result<(), String> {
+    let result = remove_dev_link(&app, agent, &name);
+    record_activity(&app, ActivityEvent::new(ActivityKind::UnlinkDevSkill, Some(agent), name, &result));
+    result
+}
+
+fn remove_dev_link(app: &AppHandle, agent: AgentType, name: &str) -> Result<(), String> {
+    let link_path = resolve_skill_dir(agent, name, None)?;
+
+    if let Ok(meta) = fs::symlink_metadata(&link_path) {
+        if !meta.file_type().is_symlink() {
+            return Err(format!("{} is an installed skill, not a dev link", name));
+        }
+        // Windows directory symlinks are removed as directories
+        fs::remove_file(&link_path)
+            .or_else(|_| fs::remove_dir(&link_path))
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Ok(mut watchers) = app.state::<DevWatchers>().0.lock() {
+        watchers.remove(&link_path);
+    }
+
+    let mut settings = load_settings()?;
+    settings.dev_links.retain(|l| !(l.agent == agent && l.name == name));
+    save_settings(&settings)
+}
+
+#[tauri::command]
+fn list_dev_skills() -> Result<Vec<DevLink>, String> {
+    Ok(load_settings()?.dev_links)
+}
+
+/// Checks whether a local folder holds a skill, without copying anything
+#[tauri::command]
+fn is_skill_dir(path: String) -> Result<SkillDirCheck, String> {
+    let dir = PathBuf::from(&path);
+    if !dir.is_dir() {
+        return Err(format!("{} is not a folder", path));
+    }
+
+    let Some(skill_md) = find_skill_md(&dir) else {
+        return Ok(SkillDirCheck {
+            is_skill: false,
+            skill_md: None,
+            name: None,
+            warnings: vec!["No SKILL.md found in this folder".to_string()],
+        });
+    };
+
+    let content = read_skill_manifest(&dir).ok_or_else(|| format!("Cannot read {}", skill_md.display()))?;
+    let fallback = dir.file_name().and_then(|n| n.to_str()).unwrap_or("skill");
+
+    Ok(SkillDirCheck {
+        is_skill: true,
+        skill_md: Some(skill_md.to_string_lossy().to_string()),
+        name: Some(extract_skill_name(&content, fallback)),
+        warnings: frontmatter_warnings(&content),
+    })
+}
+
+/// Lists a ZIP's entries and flags what an install would refuse, without
+/// extracting anything
+#[tauri::command]
+fn preview_zip(zip_base64: String) -> Result<ZipPreview, String> {
+    let zip_data = STANDARD
+        .decode(&zip_base64)
+        .map_err(|e| format!("Invalid base64: {}", e))?;
+
+    let cursor = Cursor::new(&zip_data);
+    let mut archive =
+        zip::ZipArchive::new(cursor).map_err(|e| format!("Invalid ZIP: {}", e))?;
+
+    let mut entries = Vec::new();
+    let mut red_flags = Vec::new();
+    let mut total_size: u64 = 0;
+
+    for i in 0..archive.len() {
+        let file = archive.by_index(i).map_err(|e| e.to_string())?;
+        let path = file.name().to_string();
+        let size = file.size();
+
+        if file.enclosed_name().is_none() {
+            red_flags.push(format!("Entry escapes the archive root: {}", path));
+        }
+        if file.compressed_size() > 0 && size / file.compressed_size() > MAX_ZIP_COMPRESSION_RATIO {
+            red_flags.push(format!("Suspicious compression ratio: {}", path));
+        }
+
+        total_size += size;
+        entries.push(ZipEntryInfo {
+            path,
+            size,
+            is_directory: file.is_dir(),
+        });
+    }
+
+    if total_size > MAX_ZIP_UNCOMPRESSED_SIZE {
+        red_flags.push(format!(
+            "Archive expands to {} MB (limit {} MB)",
+            total_size / (1024 * 1024),
+            MAX_ZIP_UNCOMPRESSED_SIZE / (1024 * 1024)
+        ));
+    }
+
+    let (name, warnings) = match find_zip_skill_md(&mut archive, None)? {
+        Some((_, content)) => (
+            Some(extract_skill_name(&content, "skill")),
+            frontmatter_warnings(&content),
+        ),
+        None => (None, vec!["No SKILL.md found in ZIP".to_string()]),
+    };
+
+    Ok(ZipPreview {
+        name,
+        warnings,
+        entries,
+        total_size,
+        red_flags,
+    })
+}
+
+/// Packs one installed skill as a base64 `.skill` file
+#[tauri::command]
+fn export_skill(agent: AgentType, name: String, namespace: Option<String>) -> Result<String, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    if !skill_dir.is_dir() {
+        return Err(format!("Skill not found: {}", name));
+    }
+
+    let mut files = collect_skill_files(&skill_dir)?;
+    files.retain(|f| f.path != ".metadata.json");
+    pack_skill_file(&skill_dir, name, &files)
+}
+
+/// Something to paste to a teammate: the recorded source URL, which any
+/// install-from-URL box accepts, or for skills installed from a local
+/// folder or ZIP the base64 `.skill` bundle `export_skill` makes
+#[tauri::command]
+fn copy_skill_install_command(agent: AgentType, name: String, namespace: Option<String>) -> Result<String, String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    if !skill_dir.is_dir() {
+        return Err(format!("Skill not found: {}", name));
+    }
+
+    let source = read_metadata(&skill_dir).and_then(|m| m.source);
+    match source {
+        Some(source) if !matches!(classify_source(Some(&source)), SourceKind::Local | SourceKind::Zip) => {
+            // Credentials in userinfo or query strings stay private
+            Ok(redact_url(&source))
+        }
+        _ => export_skill(agent, name, namespace),
+    }
+}
+
+/// Lints a skill for distribution: frontmatter completeness, OS junk,
+/// credential files and lines, CRLF endings. Only reports unless `fix` is
+/// set, which deletes junk and converts line endings. Credential files are
+/// never touched in the skill itself, only left out of the list and the
+/// export. With `export`, the cleaned copy is packed as a `.skill` file
+/// either way.
+#[tauri::command]
+fn prepare_skill_for_share(
+    agent: AgentType,
+    name: String,
+    namespace: Option<String>,
+    fix: Option<bool>,
+    export: Option<bool>,
+) -> Result<ShareReport, String> {
+    let fix = fix.unwrap_or(false);
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+    if !skill_dir.is_dir() {
+        return Err(format!("Skill not found: {}", name));
+    }
+
+    let mut junk_files = Vec::new();
+    find_junk_paths(&skill_dir, "", &mut junk_files);
+
+    let mut files = collect_skill_files(&skill_dir)?;
+    files.retain(|f| f.path != ".metadata.json");
+
+    let (secret, mut files): (Vec<SkillFile>, Vec<SkillFile>) = files.into_iter().partition(|f| is_secret_file(&f.path));
+    let secret_files: Vec<String> = secret.into_iter().map(|f| f.path).collect();
+
+    let mut possible_secrets = Vec::new();
+    let mut crlf_files = Vec::new();
+    for file in &mut files {
+        let Ok(text) = std::str::from_utf8(&file.content) else {
+            continue;
+        };
+        possible_secrets.extend(secret_lines(text).into_iter().map(|line| format!("{}:{}", file.path, line)));
+        if text.contains("\r\n") {
+            crlf_files.push(file.path.clone());
+            file.content = text.replace("\r\n", "\n").into_bytes();
+        }
+    }
+
+    let warnings = match read_skill_manifest(&skill_dir) {
+        Some(content) => frontmatter_warnings(&content),
+        None => vec!["No SKILL.md found".to_string()],
+    };
+    let token_count = find_skill_md(&skill_dir)
+        .and_then(|p| files.iter().find(|f| skill_dir.join(&f.path) == p))
+        .map(|f| estimate_tokens(f.content.len() as u64))
+        .unwrap_or(0);
+
+    if fix {
+        for path in &junk_files {
+            let target = skill_dir.join(path);
+            let removed = if target.is_dir() {
+                fs::remove_dir_all(&target)
+            } else {
+                fs::remove_file(&target)
+            };
+            removed.map_err(|e| format!("Cannot remove {}: {}", path, e))?;
+        }
+        for file in files.iter().filter(|f| crlf_files.contains(&f.path)) {
+            fs::write(skill_dir.join(&file.path), &file.content).map_err(|e| e.to_string())?;
+        }
+    }
+
+    let export = if export.unwrap_or(false) {
+        Some(pack_skill_file(&skill_dir, name.clone(), &files)?)
+    } else {
+        None
+    };
+
+    Ok(ShareReport {
+        name,
+        files: files.into_iter().map(|f| f.path).collect(),
+        token_count,
+        warnings,
+        junk_files,
+        secret_files,
+        possible_secrets,
+        crlf_files,
+        fixed: fix,
+        export,
+    })
+}
+
+/// OS junk anywhere under `dir`, as paths relative to it
+fn find_junk_paths(dir: &Path, prefix: &str, found: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let relative = if prefix.is_empty() { name.clone() } else { format!("{}/{}", prefix, name) };
+        if is_junk_file(&name) {
+            found.push(relative);
+        } else if entry.path().is_dir() {
+            find_junk_paths(&entry.path(), &relative, found);
+        }
+    }
+}
+
+/// Files that hold credentials by convention and shouldn't leave the machine
+fn is_secret_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path).to_lowercase();
+    name == ".env"
+        || name.starts_with(".env.")
+        || matches!(name.as_str(), ".npmrc" | ".netrc" | ".pypirc" | "credentials.json" | "id_rsa" | "id_ed25519" | "id_ecdsa")
+        || name.ends_with(".pem")
+        || name.ends_with(".key")
+        || name.ends_with(".p12")
+}
+
+/// 1-based numbers of lines holding a known token format, a private key,
+/// or a credential-named key assigned a literal value
+fn secret_lines(text: &str) -> Vec<usize> {
+    const KEYS: &[&str] = &["api_key", "apikey", "secret", "token", "password", "passwd"];
+    let is_placeholder = |v: &str| {
+        v.len() < 8 || v.starts_with('<') || v.starts_with("${") || v.starts_with('$') || v.chars().all(|c| c == 'x' || c == 'X' || c == '*')
+    };
+
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            if line.contains("PRIVATE KEY-----") {
+                return true;
+            }
+            let has_token = line
+                .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                .any(|word| SECRET_PREFIXES.iter().any(|p| word.starts_with(p) && word.len() > p.len() + 15));
+            if has_token {
+                return true;
+            }
+            let Some((key, value)) = line.split_once('=').or_else(|| line.split_once(':')) else {
+                return false;
+            };
+            let key = key.trim().trim_matches(['"', '\'']).to_lowercase();
+            let value = value.trim().trim_end_matches([',', ';']).trim_matches(['"', '\'']);
+            KEYS.iter().any(|k| key.ends_with(k)) && !value.contains(' ') && !is_placeholder(value)
+        })
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+/// A base64 `.skill` file of `files`, named and versioned from the skill's
+/// metadata when it has some
+fn pack_skill_file(skill_dir: &Path, name: String, files: &[SkillFile]) -> Result<String, String> {
+    let metadata = read_metadata(skill_dir);
+    let manifest = SkillFileManifest {
+        format_version: SKILL_FILE_FORMAT_VERSION,
+        version: metadata.as_ref().and_then(|m| m.version.clone()),
+        name: metadata.map(|m| m.name).unwrap_or(name),
+        checksum: format!("sha256:{}", hash_skill_files(files)),
+    };
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    add_zip_file(&mut zip, SKILL_FILE_MANIFEST, &manifest_json, None)?;
+    for file in files {
+        add_zip_file(&mut zip, &file.path, &file.content, file.mode)?;
+    }
+
+    let cursor = zip.finish().map_err(|e| e.to_string())?;
+    Ok(STANDARD.encode(cursor.into_inner()))
+}
+
+#[tauri::command]
+#[tracing::instrument(fields(agent = agent.id()), skip(app, agent), err)]
+fn delete_skill(
+    app: AppHandle,
+    agent: AgentType,
+    name: String,
+    namespace: Option<String>,
+    force: Option<bool>,
+) -> Result<Vec<DeleteResult>, String> {
+    let force = force.unwrap_or(false);
+    // Handle "All" agent - delete from all agents
+    let agents = if agent == AgentType::All {
+        get_all_scope_agents()
+    } else {
+        vec![agent]
+    };
+
+    // A failure for one agent doesn't stop the others; each gets a result
+    let mut results = Vec::new();
+    for individual_agent in agents {
+        let result = delete_skill_for_agent(individual_agent, name.clone(), namespace.as_deref(), force);
+        let mut event = ActivityEvent::new(ActivityKind::Delete, Some(individual_agent), &name, &result);
+        let (status, reason) = match result {
+            Ok(None) => (DeleteStatus::Deleted, None),
+            Ok(Some(reason)) => {
+                event = event.with_detail(format!("skipped: {}", reason));
+                (DeleteStatus::Skipped, Some(reason.to_string()))
+            }
+            Err(e) => (DeleteStatus::Failed, Some(e)),
+        };
+        record_activity(&app, event);
+        results.push(DeleteResult {
+            agent: individual_agent,
+            status,
+            reason,
+        });
+    }
+    Ok(results)
+}
+
+/// Deletes one agent's copy. `Some` names why it was left in place.
+fn delete_skill_for_agent(
+    agent: AgentType,
+    name: String,
+    namespace: Option<&str>,
+    force: bool,
+) -> Result<Option<&'static str>, String> {
+    let (skill_dir, builtin) = resolve_delete_target(agent, &name, namespace)?;
+    if !force && builtin {
+        return Ok(Some("built in"));
+    }
+    if !force && read_metadata(&skill_dir).is_some_and(|m| m.pinned) {
+        return Ok(Some("pinned"));
+    }
+
+    // symlink_metadata so a dangling or linked skill still counts as present
+    let Ok(meta) = fs::symlink_metadata(&skill_dir) else {
+        return Ok(None);
+    };
+
+    // Links and stray files go straight away; skill folders go to trash
+    if meta.file_type().is_symlink() || meta.is_file() {
+        fs::remove_file(&skill_dir).map_err(|e| e.to_string())?;
+    } else {
+        move_to_trash(agent, &skill_dir)?;
+    }
+
+    Ok(None)
+}
+
+/// The folder a delete of `name` acts on, and whether it is one of the
+/// agent's built-in skills (only matched when nothing is installed over it)
+fn resolve_delete_target(agent: AgentType, name: &str, namespace: Option<&str>) -> Result<(PathBuf, bool), String> {
+    let skill_dir = resolve_skill_dir(agent, name, namespace)?;
+    if fs::symlink_metadata(&skill_dir).is_err() && namespace.is_none() {
+        if let Some(builtin) = get_builtin_skills_dirs(agent)
+            .into_iter()
+            .map(|dir| dir.join(name))
+            .find(|dir| dir.is_dir())
+        {
+            return Ok((builtin, true));
+        }
+    }
+    Ok((skill_dir, false))
+}
+
+/// What `delete_skill` would remove, per agent, without touching anything
+#[tauri::command]
+fn preview_delete(agent: AgentType, name: String, namespace: Option<String>) -> Result<Vec<DeletePreview>, String> {
+    let agents = if agent == AgentType::All {
+        get_all_scope_agents()
+    } else {
+        vec![agent]
+    };
+
+    agents
+        .into_iter()
+        .map(|agent| {
+            let (skill_dir, builtin) = resolve_delete_target(agent, &name, namespace.as_deref())?;
+            let meta = fs::symlink_metadata(&skill_dir).ok();
+            let is_symlink = meta.as_ref().is_some_and(|m| m.file_type().is_symlink());
+            let (size_bytes, file_count) = match &meta {
+                Some(m) if m.is_dir() => dir_usage(&skill_dir),
+                Some(m) => (m.len(), 1),
+                None => (0, 0),
+            };
+            Ok(DeletePreview {
+                agent,
+                exists: meta.is_some(),
+                size_bytes,
+                file_count,
+                is_symlink,
+                pinned: read_metadata(&skill_dir).is_some_and(|m| m.pinned),
+                builtin,
+                path: skill_dir.to_string_lossy().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Total size and number of files under `dir`, not following symlinks.
+/// Everything counts, metadata and dotfiles too, since a delete removes them.
+fn dir_usage(dir: &Path) -> (u64, usize) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return (0, 0);
+    };
+
+    entries.flatten().fold((0, 0), |(size, count), entry| {
+        let Ok(meta) = fs::symlink_metadata(entry.path()) else {
+            return (size, count);
+        };
+        if meta.is_dir() {
+            let (dir_size, dir_count) = dir_usage(&entry.path());
+            (size + dir_size, count + dir_count)
+        } else {
+            (size + meta.len(), count + 1)
+        }
+    })
+}
+
+/// Skill-related errors from the agent's most recent logs, matched back to
+/// installed skill names where a line mentions one. Empty for agents
+/// without a known log location.
+#[tauri::command]
+fn get_agent_skill_errors(agent: AgentType) -> Result<Vec<SkillLoadError>, String> {
+    let Some(log_dir) = get_agent_log_dir(agent) else {
+        return Ok(vec![]);
+    };
+    if !log_dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let skill_names: Vec<String> = list_skills_for_agent(agent, None, true)?
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+
+    let mut logs: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(&log_dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .collect();
+    logs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+
+    let mut errors = Vec::new();
+    for (_, log_file) in logs.into_iter().take(AGENT_LOG_FILES_SCANNED) {
+        let Ok(content) = fs::read(&log_file) else {
+            continue;
+        };
+        for (index, line) in String::from_utf8_lossy(&content).lines().enumerate() {
+            let lower = line.to_lowercase();
+            if !lower.contains("skill")
+                || !["error", "failed", "invalid", "unsupported"].iter().any(|k| lower.contains(k))
+            {
+                continue;
+            }
+
+            let skill = skill_names
+                .iter()
+                .filter(|name| lower.contains(&name.to_lowercase()))
+                .max_by_key(|name| name.len())
+                .cloned();
+            errors.push(SkillLoadError {
+                skill,
+                message: line.trim().to_string(),
+                log_file: log_file.to_string_lossy().to_string(),
+                line: index + 1,
+            });
+        }
+    }
+
+    Ok(errors)
+}
+
+/// Lists every skill `clear_all_skills` would move to trash. The token is
+/// derived from that list, so it stops matching if the skills change.
+#[tauri::command]
+fn prepare_clear(agent: AgentType, force: Option<bool>) -> Result<ClearPlan, String> {
+    let (skills, pinned) = split_pinned(clear_targets(agent)?, force.unwrap_or(false));
+    let confirm_token = clear_token(agent, &skills);
+
+    Ok(ClearPlan {
+        agent,
+        skills: skills.into_iter().map(|(_, skill)| skill).collect(),
+        pinned: pinned.into_iter().map(|(_, skill)| skill).collect(),
+        confirm_token,
+    })
+}
+
+/// Moves all of an agent's skills to trash. Requires the token from a
+/// `prepare_clear` call made against the same set of skills.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(agent = agent.id()), err)]
+fn clear_all_skills(
+    app: AppHandle,
+    agent: AgentType,
+    confirm_token: String,
+    force: Option<bool>,
+) -> Result<ClearResult, String> {
+    let result = clear_skills(agent, confirm_token, force.unwrap_or(false));
+    let mut event = ActivityEvent::new(ActivityKind::Clear, Some(agent), agent.id(), &result);
+    if let Ok(cleared) = &result {
+        event = event.with_detail(format!("{} moved to trash, {} failed", cleared.moved_to_trash, cleared.failed));
+    }
+    record_activity(&app, event);
+    result
+}
+
+fn clear_skills(agent: AgentType, confirm_token: String, force: bool) -> Result<ClearResult, String> {
+    let (skills, pinned) = split_pinned(clear_targets(agent)?, force);
+    if confirm_token != clear_token(agent, &skills) {
+        return Err("Skills changed since prepare_clear; review the list again".to_string());
+    }
+
+    let mut result = ClearResult {
+        moved_to_trash: 0,
+        failed: 0,
+        skipped_pinned: pinned.len(),
+        errors: vec![],
+    };
+    for (skill_agent, skill) in skills {
+        match move_to_trash(skill_agent, Path::new(&skill.path)) {
+            Ok(_) => result.moved_to_trash += 1,
+            Err(e) => {
+                result.failed += 1;
+                result.errors.push(format!("{}/{}: {}", skill_agent.id(), skill.name, e));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// A skill as found under one agent's skills dir
+type SkillTarget = (AgentType, SkillInfo);
+
+/// Separates pinned skills out of a bulk operation's targets; `force`
+/// keeps them in
+fn split_pinned(targets: Vec<SkillTarget>, force: bool) -> (Vec<SkillTarget>, Vec<SkillTarget>) {
+    if force {
+        return (targets, vec![]);
+    }
+    targets
+        .into_iter()
+        .partition(|(_, skill)| !read_metadata(Path::new(&skill.path)).is_some_and(|m| m.pinned))
+}
+
+fn clear_targets(agent: AgentType) -> Result<Vec<SkillTarget>, String> {
+    let agents = if agent == AgentType::All {
+        get_all_scope_agents()
+    } else {
+        vec![agent]
+    };
+
+    let mut targets = Vec::new();
+    for individual_agent in agents {
+        for skill in list_skills_for_agent(individual_agent, None, true)? {
+            targets.push((individual_agent, skill));
+        }
+    }
+    Ok(targets)
+}
+
+/// Removes what interrupted installs and deletes leave behind: skill dirs
+/// holding only `.metadata.json`, empty dirs, stale `.installing` staging
+/// dirs, and partial downloads. Pending installs that can still be resumed
+/// are kept.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn cleanup(app: AppHandle, agent: AgentType) -> Result<CleanupReport, String> {
+    let result = cleanup_leftovers(agent);
+    let mut event = ActivityEvent::new(ActivityKind::Cleanup, Some(agent), "cleanup", &result);
+    if let Ok(report) = &result {
+        event = event.with_detail(format!("{} removed, {} failed", report.removed_count(), report.errors.len()));
+    }
+    record_activity(&app, event);
+    result
+}
+
+fn cleanup_leftovers(agent: AgentType) -> Result<CleanupReport, String> {
+    let agents = if agent == AgentType::All {
+        get_all_scope_agents()
+    } else {
+        vec![agent]
+    };
+
+    let mut report = CleanupReport::default();
+    for individual_agent in agents {
+        let skills_dir = get_skills_dir(individual_agent)?;
+        cleanup_skills_dir(&skills_dir, true, &mut report);
+    }
+    cleanup_downloads(&mut report)?;
+    Ok(report)
+}
+
+/// Cleans one level of a skills dir, descending once into namespace groups
+fn cleanup_skills_dir(dir: &Path, top_level: bool, report: &mut CleanupReport) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Symlinks are dev links or user-managed; never look through them
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if !meta.is_dir() {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            if name.ends_with(".installing") && is_stale(&meta) {
+                match fs::remove_dir_all(&path) {
+                    Ok(()) => report.removed_temp.push(path.to_string_lossy().to_string()),
+                    Err(e) => report.errors.push(format!("{}: {}", path.display(), e)),
+                }
+            }
+            continue;
+        }
+        if find_skill_md(&path).is_some() {
+            continue;
+        }
+
+        if top_level {
+            cleanup_skills_dir(&path, false, report);
+        }
+        let contents: Vec<String> = match fs::read_dir(&path) {
+            Ok(entries) => entries.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect(),
+            Err(e) => {
+                report.errors.push(format!("{}: {}", path.display(), e));
+                continue;
+            }
+        };
+
+        let display = path.to_string_lossy().to_string();
+        if contents.is_empty() {
+            match fs::remove_dir(&path) {
+                Ok(()) => report.removed_empty_dirs.push(display),
+                Err(e) => report.errors.push(format!("{}: {}", display, e)),
+            }
+        } else if contents == [".metadata.json"] {
+            let removed = fs::remove_file(path.join(".metadata.json")).and_then(|_| fs::remove_dir(&path));
+            match removed {
+                Ok(()) => report.removed_metadata.push(display),
+                Err(e) => report.errors.push(format!("{}: {}", display, e)),
+            }
+        }
+    }
+}
+
+/// Removes old `.download`/`.part` files and staging folders whose
+/// `pending.json` is gone, so they can no longer be resumed
+fn cleanup_downloads(report: &mut CleanupReport) -> Result<(), String> {
+    let Ok(entries) = fs::read_dir(get_downloads_dir()?) else {
+        return Ok(());
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if !is_stale(&meta) {
+            continue;
+        }
+
+        let removed = if meta.is_dir() {
+            if path.join("pending.json").exists() {
+                continue;
+            }
+            fs::remove_dir_all(&path)
+        } else {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.ends_with(".download") && !name.ends_with(".part") {
+                continue;
+            }
+            fs::remove_file(&path)
+        };
+        match removed {
+            Ok(()) => report.removed_temp.push(path.to_string_lossy().to_string()),
+            Err(e) => report.errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    Ok(())
+}
+
+fn is_stale(meta: &fs::Metadata) -> bool {
+    meta.modified()
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age >= STALE_TEMP_AGE)
+}
+
+/// A list in an agent's config naming skills that are installed but
+/// switched off
+struct SkillGate {
+    config_path: PathBuf,
+    /// Keys leading to the list inside the config
+    pointer: &'static [&'static str],
+}
+
+/// The config list that gates skills for agents that have one:
+///
+/// - Gemini CLI: names under `skills.disabled` in `settings.json`
+///
+/// Other agents load whatever is in the skills dir.
+fn get_skill_gate(agent: AgentType) -> Option<SkillGate> {
+    match agent {
+        AgentType::Gemini => Some(SkillGate {
+            config_path: get_mcp_config_path(agent).ok()?,
+            pointer: &["skills", "disabled"],
+        }),
+        _ => None,
+    }
+}
+
+impl SkillGate {
+    fn read_names(&self) -> Result<Vec<String>, String> {
+        let root = read_mcp_config(&self.config_path)?;
+        let list = self.pointer.iter().try_fold(&root, |value, key| value.get(key));
+        Ok(list
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter().filter_map(|i| i.as_str().map(String::from)).collect())
+            .unwrap_or_default())
+    }
+}
+
+/// Every skill that's installed or named in the agent's gating list, with
+/// whether it's active. A listed name that isn't installed shows up as
+/// neither. Agents without a list report each installed skill
+/// as enabled.
+#[tauri::command]
+fn list_enabled_skills(agent: AgentType) -> Result<Vec<SkillEnabledState>, String> {
+    let installed: Vec<String> = list_skills_for_agent(agent, None, false)?
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    let Some(gate) = get_skill_gate(agent) else {
+        return Ok(installed
+            .into_iter()
+            .map(|name| SkillEnabledState { name, installed: true, enabled: true })
+            .collect());
+    };
+
+    let listed = gate.read_names()?;
+    let mut names: Vec<&String> = installed.iter().chain(&listed).collect();
+    names.sort();
+    names.dedup();
+
+    Ok(names
+        .into_iter()
+        .map(|name| SkillEnabledState {
+            name: name.clone(),
+            installed: installed.contains(name),
+            enabled: !listed.contains(name),
+        })
+        .collect())
+}
+
+#[tauri::command]
+#[tracing::instrument(fields(agent = agent.id()), skip(app, agent), err)]
+fn set_skill_enabled(app: AppHandle, agent: AgentType, name: String, enabled: bool) -> Result<(), String> {
+    let result = write_skill_enabled(agent, &name, enabled);
+    record_activity(
+        &app,
+        ActivityEvent::new(ActivityKind::UpdateSettings, Some(agent), &name, &result)
+            .with_detail(if enabled { "enabled" } else { "disabled" }),
+    );
+    result
+}
+
+fn write_skill_enabled(agent: AgentType, name: &str, enabled: bool) -> Result<(), String> {
+    validate_skill_name(name)?;
+    let gate = get_skill_gate(agent)
+        .ok_or_else(|| format!("{} loads every installed skill; delete or install it instead", agent.id()))?;
+    let config_path = &gate.config_path;
+
+    let mut root = read_mcp_config(config_path)?;
+    let mut node = &mut root;
+    for key in gate.pointer {
+        node = node
+            .as_object_mut()
+            .ok_or_else(|| format!("Cannot edit {}: {} is not an object", config_path.display(), key))?
+            .entry(*key)
+            .or_insert(serde_json::json!({}));
+    }
+    if !node.is_array() {
+        *node = serde_json::json!([]);
+    }
+    let list = node.as_array_mut().ok_or("Invalid skill list")?;
+
+    list.retain(|item| item.as_str() != Some(name));
+    if !enabled {
+        list.push(serde_json::json!(name));
+    }
+
+    write_mcp_config(config_path, &root)
+}
+
+#[tauri::command]
+fn pin_skill(app: AppHandle, agent: AgentType, name: String, namespace: Option<String>) -> Result<(), String> {
+    set_skill_pinned(&app, agent, name, namespace, true)
+}
+
+#[tauri::command]
+fn unpin_skill(app: AppHandle, agent: AgentType, name: String, namespace: Option<String>) -> Result<(), String> {
+    set_skill_pinned(&app, agent, name, namespace, false)
+}
+
+fn set_skill_pinned(
+    app: &AppHandle,
+    agent: AgentType,
+    name: String,
+    namespace: Option<String>,
+    pinned: bool,
+) -> Result<(), String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref());
+    let result = skill_dir.and_then(|dir| {
+        if !dir.is_dir() {
+            return Err(format!("Skill not found: {}", name));
+        }
+        set_metadata_pinned(&dir, &name, pinned)
+    });
+    record_activity(
+        app,
+        ActivityEvent::new(ActivityKind::UpdateSettings, Some(agent), &name, &result)
+            .with_detail(if pinned { "pinned" } else { "unpinned" }),
+    );
+    result
+}
+
+#[tauri::command]
+fn set_skill_favorite(
+    app: AppHandle,
+    agent: AgentType,
+    name: String,
+    namespace: Option<String>,
+    favorite: bool,
+) -> Result<(), String> {
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref());
+    let result = skill_dir.and_then(|dir| {
+        if !dir.is_dir() {
+            return Err(format!("Skill not found: {}", name));
+        }
+        update_metadata(&dir, &name, |m| m.favorite = favorite)
+    });
+    record_activity(
+        &app,
+        ActivityEvent::new(ActivityKind::UpdateSettings, Some(agent), &name, &result)
+            .with_detail(if favorite { "favorited" } else { "unfavorited" }),
+    );
+    result
+}
+
+#[tauri::command]
+fn set_skill_notes(
+    app: AppHandle,
+    agent: AgentType,
+    name: String,
+    namespace: Option<String>,
+    notes: Option<String>,
+) -> Result<(), String> {
+    let notes = notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref());
+    let result = skill_dir.and_then(|dir| {
+        if !dir.is_dir() {
+            return Err(format!("Skill not found: {}", name));
+        }
+        update_metadata(&dir, &name, |m| m.notes = notes)
+    });
+    record_activity(
+        &app,
+        ActivityEvent::new(ActivityKind::UpdateSettings, Some(agent), &name, &result).with_detail("notes"),
+    );
+    result
+}
+
+/// Moves files of at least `min_bytes` (`EXTERNALIZE_MIN_BYTES` by default)
+/// into `assets/` and points the links in every markdown file at the new
+/// paths. Markdown files stay put, so their own relative links hold.
+/// Returns the moved files' original paths; `restore_skill_assets` puts
+/// them back.
+#[tauri::command]
+fn externalize_skill_assets(
+    app: AppHandle,
+    agent: AgentType,
+    name: String,
+    namespace: Option<String>,
+    min_bytes: Option<u64>,
+) -> Result<Vec<String>, String> {
+    let min_bytes = min_bytes.unwrap_or(EXTERNALIZE_MIN_BYTES);
+    let result = resolve_skill_dir(agent, &name, namespace.as_deref()).and_then(|skill_dir| {
+        let moved: Vec<String> = collect_skill_files(&skill_dir)?
+            .into_iter()
+            .filter(|f| f.content.len() as u64 >= min_bytes)
+            .map(|f| f.path)
+            .filter(|path| {
+                path != ".metadata.json"
+                    && !is_markdown_path(path)
+                    && !path.starts_with(&format!("{}/", EXTERNAL_ASSETS_DIR))
+            })
+            .collect();
+        if moved.is_empty() {
+            return Ok(moved);
+        }
+
+        relocate_skill_assets(&skill_dir, &moved, true)?;
+        update_metadata(&skill_dir, &name, |m| m.externalized_assets.extend(moved.iter().cloned()))?;
+        Ok(moved)
+    });
+    record_activity(
+        &app,
+        ActivityEvent::new(ActivityKind::UpdateSettings, Some(agent), &name, &result).with_detail("externalized assets"),
+    );
+    result
+}
+
+/// Reverses `externalize_skill_assets`, returning the files moved back
+#[tauri::command]
+fn restore_skill_assets(
+    app: AppHandle,
+    agent: AgentType,
+    name: String,
+    namespace: Option<String>,
+) -> Result<Vec<String>, String> {
+    let result = resolve_skill_dir(agent, &name, namespace.as_deref()).and_then(|skill_dir| {
+        let moved = read_metadata(&skill_dir)
+            .map(|m| m.externalized_assets)
+            .unwrap_or_default();
+        if moved.is_empty() {
+            return Ok(moved);
+        }
+
+        relocate_skill_assets(&skill_dir, &moved, false)?;
+        update_metadata(&skill_dir, &name, |m| m.externalized_assets.clear())?;
+        Ok(moved)
+    });
+    record_activity(
+        &app,
+        ActivityEvent::new(ActivityKind::UpdateSettings, Some(agent), &name, &result).with_detail("restored assets"),
+    );
+    result
+}
+
+/// Moves each of `paths` into `assets/` (or back out of it) and rewrites
+/// the links to them in each of the skill's markdown files to match
+fn relocate_skill_assets(skill_dir: &Path, paths: &[String], outward: bool) -> Result<(), String> {
+    for path in paths {
+        let (from, to) = if outward {
+            (skill_dir.join(path), skill_dir.join(EXTERNAL_ASSETS_DIR).join(path))
+        } else {
+            (skill_dir.join(EXTERNAL_ASSETS_DIR).join(path), skill_dir.join(path))
+        };
+        if !from.is_file() {
+            continue;
+        }
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::rename(&from, &to).map_err(|e| format!("{}: {}", path, e))?;
+    }
+
+    let assets_prefix = format!("{}/", EXTERNAL_ASSETS_DIR);
+    let markdown: Vec<String> = skill_content_files(skill_dir)
+        .map(|(path, _)| path)
+        .filter(|path| is_markdown_path(path))
+        .collect();
+    for md_path in markdown {
+        let md_file = skill_dir.join(&md_path);
+        let content = read_text(&md_file).map_err(|e| e.to_string())?;
+        // Links are relative to the file's folder; `up` climbs from there
+        // back to the skill root
+        let base = Path::new(&md_path).parent().unwrap_or(Path::new(""));
+        let up = "../".repeat(base.components().count());
+        let rewritten = rewrite_markdown_links(&content, |target| {
+            let linked = resolve_relative_link(base, target)?;
+            let linked = linked.to_string_lossy().replace('\\', "/");
+            if outward {
+                paths.contains(&linked).then(|| format!("{}{}{}", up, assets_prefix, linked))
+            } else {
+                let original = linked.strip_prefix(&assets_prefix)?;
+                paths.iter().any(|p| p == original).then(|| format!("{}{}", up, original))
+            }
+        });
+        if rewritten != content {
+            fs::write(&md_file, rewritten).map_err(|e| format!("{}: {}", md_path, e))?;
+        }
+    }
+    Ok(())
+}
+
+fn is_markdown_path(path: &str) -> bool {
+    path.to_lowercase().ends_with(".md")
+}
+
+/// Sets the pin in a skill's metadata, creating the metadata for skills
+/// that were placed there by hand
+fn set_metadata_pinned(skill_dir: &Path, name: &str, pinned: bool) -> Result<(), String> {
+    update_metadata(skill_dir, name, |m| m.pinned = pinned)
+}
+
+/// Edits a skill's metadata in place, creating the metadata for skills
+/// that were placed there by hand
+fn update_metadata(skill_dir: &Path, name: &str, edit: impl FnOnce(&mut SkillMetadata)) -> Result<(), String> {
+    if read_metadata(skill_dir).is_none() {
+        save_metadata(skill_dir, name, None)?;
+    }
+    let mut metadata = read_metadata(skill_dir).ok_or("Cannot read skill metadata")?;
+    edit(&mut metadata);
+
+    let json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+    fs::write(skill_dir.join(".metadata.json"), json).map_err(|e| e.to_string())
+}
+
+/// Renames skill directories whose names predate (or bypassed)
+/// `sanitize_name`. A rename that would collide with an existing skill is
+/// skipped and reported rather than merged.
+#[tauri::command]
+#[tracing::instrument(fields(agent = agent.id()), skip(app, agent), err)]
+fn normalize_skill_names(app: AppHandle, agent: AgentType) -> Result<Vec<RenameResult>, String> {
+    let result = rename_unsanitized_skills(agent);
+    match &result {
+        Ok(renames) => {
+            for rename in renames {
+                let outcome = match &rename.error {
+                    Some(e) if !rename.renamed => Err(e.clone()),
+                    _ => Ok(()),
+                };
+                record_activity(
+                    &app,
+                    ActivityEvent::new(ActivityKind::Rename, Some(rename.agent), &rename.from, &outcome)
+                        .with_detail(format!("to {}", rename.to)),
+                );
+            }
+        }
+        Err(_) => record_activity(
+            &app,
+            ActivityEvent::new(ActivityKind::Rename, Some(agent), agent.id(), &result),
+        ),
+    }
+    result
+}
+
+fn rename_unsanitized_skills(agent: AgentType) -> Result<Vec<RenameResult>, String> {
+    let mut results = Vec::new();
+
+    let (targets, _) = split_pinned(clear_targets(agent)?, false);
+    for (individual_agent, skill) in targets {
+        let to = sanitize_name(&skill.name);
+        if to == skill.name || to.is_empty() {
+            continue;
+        }
+
+        let from_dir = PathBuf::from(&skill.path);
+        let Some(parent) = from_dir.parent() else {
+            continue;
+        };
+
+        // Compare exact entry names so a case-only rename isn't mistaken for
+        // a collision on case-insensitive filesystems
+        let taken = fs::read_dir(parent)
+            .map_err(|e| e.to_string())?
+            .flatten()
+            .any(|e| e.file_name().to_str() == Some(to.as_str()));
+
+        let to_dir = parent.join(&to);
+        let (renamed, error) = if taken {
+            (false, Some(format!("A skill named {} already exists", to)))
+        } else if let Err(e) = fs::rename(&from_dir, &to_dir) {
+            (false, Some(e.to_string()))
+        } else {
+            (true, rename_skill_metadata(&to_dir, &skill.name, &to).err())
+        };
+
+        results.push(RenameResult {
+            agent: individual_agent,
+            namespace: skill.namespace,
+            renamed,
+            from: skill.name,
+            to,
+            error,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Resets modes under the skills dir to what agents expect: `0755` for
+/// directories and scripts (files that were executable or start with `#!`),
+/// `0644` for everything else. Linked dev skills are left alone.
+#[tauri::command]
+#[tracing::instrument(fields(agent = agent.id()), skip(agent), err)]
+fn fix_skills_permissions(agent: AgentType) -> Result<FixReport, String> {
+    if !cfg!(unix) {
+        return Ok(FixReport {
+            message: Some("Windows doesn't use Unix permissions; nothing to fix".to_string()),
+            ..Default::default()
+        });
+    }
+
+    let agents = if agent == AgentType::All {
+        get_all_scope_agents()
+    } else {
+        vec![agent]
+    };
+
+    let mut report = FixReport::default();
+    for agent in agents {
+        let skills_dir = get_skills_dir(agent)?;
+        if skills_dir.is_dir() {
+            fix_path_permissions(&skills_dir, &mut report);
+        }
+    }
+    tracing::info!(changed = report.changed.len(), errors = report.errors.len(), "permissions fixed");
+    Ok(report)
+}
+
+#[cfg(unix)]
+fn fix_path_permissions(path: &Path, report: &mut FixReport) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return;
+    };
+    if meta.file_type().is_symlink() {
+        return;
+    }
+
+    let mode = meta.permissions().mode() & 0o777;
+    let wanted = if meta.is_dir() || mode & 0o111 != 0 || has_shebang(path) {
+        0o755
+    } else {
+        0o644
+    };
+    if mode != wanted {
+        match fs::set_permissions(path, fs::Permissions::from_mode(wanted)) {
+            Ok(()) => report.changed.push(PermissionChange {
+                path: path.to_string_lossy().to_string(),
+                from: mode,
+                to: wanted,
+            }),
+            Err(e) => report.errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    if meta.is_dir() {
+        match fs::read_dir(path) {
+            Ok(entries) => {
+                for entry in entries.flatten() {
+                    fix_path_permissions(&entry.path(), report);
+                }
+            }
+            Err(e) => report.errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn fix_path_permissions(_path: &Path, _report: &mut FixReport) {}
+
+#[cfg(unix)]
+fn has_shebang(path: &Path) -> bool {
+    let mut start = [0u8; 2];
+    fs::File::open(path).and_then(|mut f| f.read_exact(&mut start)).is_ok() && &start == b"#!"
+}
+
+/// Points the metadata's name at a renamed directory, if it tracked the old one
+fn rename_skill_metadata(skill_dir: &Path, from: &str, to: &str) -> Result<(), String> {
+    let Some(mut metadata) = read_metadata(skill_dir) else {
+        return Ok(());
+    };
+    if metadata.name != from {
+        return Ok(());
+    }
+    metadata.name = to.to_string();
+
+    let json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+    fs::write(skill_dir.join(".metadata.json"), json)
+        .map_err(|e| format!("Renamed, but metadata not updated: {}", e))
+}
+
+fn clear_token(agent: AgentType, targets: &[(AgentType, SkillInfo)]) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(agent.id().as_bytes());
+    for (_, skill) in targets {
+        hasher.update([0]);
+        hasher.update(skill.path.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[tauri::command]
+fn open_skill_folder(agent: AgentType, name: String, namespace: Option<String>) -> Result<(), String> {
+    if agent == AgentType::All {
+        return Err("Cannot open folder for All agents".to_string());
+    }
+
+    let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg(&skill_dir)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("explorer")
+            .arg(&skill_dir)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        std::process::Command::new("xdg-open")
+            .arg(&skill_dir)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn search_skills(
+    state: tauri::State<'_, SearchState>,
+    query: String,
+) -> Result<SearchResponse, SearchError> {
+    let started = std::time::Instant::now();
+
+    let key = query.trim().to_lowercase();
+    let lock = state.query_lock(&key);
+    let results = {
+        let _in_flight = lock.lock().await;
+        fetch_search_results(&state, &query, &key).await
+    };
+    drop(lock);
+    state.release_query_lock(&key);
+
+    Ok(SearchResponse {
+        query,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        results: results?,
+    })
+}
+
+async fn fetch_search_results(state: &SearchState, query: &str, key: &str) -> Result<Vec<SearchSkill>, SearchError> {
+    if key.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let cached = state.cached(key);
+    if let Some((skills, fetched_at)) = &cached {
+        if fetched_at.elapsed() < SEARCH_CACHE_TTL {
+            return Ok(skills.clone());
+        }
+    }
+
+    // While backing off, stale results beat no results
+    if let Some(remaining) = state.backoff_remaining() {
+        return match cached {
+            Some((skills, _)) => Ok(skills),
+            None => Err(SearchError::RateLimited {
+                retry_after_secs: remaining.as_secs().max(1),
+            }),
+        };
+    }
+
+    let client = http_client()?;
+
+    let url = format!(
+        "https://skills.sh/api/search?q={}&limit=20",
+        urlencoding::encode(query)
+    );
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to search: {}", e))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+        let is_rate_limited = status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+        let retry_after = parse_retry_after(response.headers()).unwrap_or(if is_rate_limited {
+            SEARCH_RATE_LIMIT_BACKOFF
+        } else {
+            SEARCH_UNAVAILABLE_BACKOFF
+        });
+        state.back_off(retry_after);
+
+        if let Some((skills, _)) = cached {
+            return Ok(skills);
+        }
+        let retry_after_secs = retry_after.as_secs().max(1);
+        return Err(if is_rate_limited {
+            SearchError::RateLimited { retry_after_secs }
+        } else {
+            SearchError::ServiceUnavailable {
+                status: status.as_u16(),
+                retry_after_secs,
+            }
+        });
+    }
+
+    if !status.is_success() {
+        return Ok(vec![]);
+    }
+
+    let data: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid response: {}", e))?;
+
+    let skills: Vec<SearchSkill> = data
+        .get("skills")
+        .and_then(|s| s.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|item| {
+                    let name = item.get("name")?.as_str()?.to_string();
+                    let slug = item.get("id")?.as_str()?.to_string();
+                    let source = item
+                        .get("topSource")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .to_string();
+                    let installs = item
+                        .get("installs")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0);
+                    let license = item
+                        .get("license")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+                    let token_estimate = ["tokens", "tokenCount", "tokenEstimate"]
+                        .iter()
+                        .find_map(|key| item.get(*key).and_then(|v| v.as_u64()))
+                        .or_else(|| item.get("size").and_then(|v| v.as_u64()).map(estimate_tokens));
+
+                    Some(SearchSkill {
+                        name,
+                        slug,
+                        source,
+                        installs,
+                        license,
+                        token_estimate,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    state.store(key.to_string(), skills.clone());
+    Ok(skills)
+}
+
+/// The URL a registry skill installs from, so it can be shown before
+/// installing. Recent search results are used when they include the slug,
+/// otherwise the registry is searched for it.
+#[tauri::command]
+async fn resolve_slug_url(state: tauri::State<'_, SearchState>, slug: String) -> Result<String, String> {
+    let slug = slug.trim();
+    let skill = match state.find_by_slug(slug) {
+        Some(skill) => skill,
+        None => fetch_search_results(&state, slug, &slug.to_lowercase())
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|s| s.slug == slug)
+            .ok_or_else(|| format!("{} is not in the registry", slug))?,
+    };
+
+    let source = if skill.source.is_empty() { &skill.slug } else { &skill.source };
+    Ok(registry_source_url(source))
+}
+
+/// Registry sources are `owner/repo` or `owner/repo/tree/<branch>/<path>`
+/// on GitHub; full URLs are kept as they are
+fn registry_source_url(source: &str) -> String {
+    let source = source.trim();
+    if source.starts_with("http://") || source.starts_with("https://") {
+        source.to_string()
+    } else {
+        format!("https://github.com/{}", source.trim_matches('/'))
+    }
+}
+
+/// Installs a registry skill from the URL `resolve_slug_url` gives for it
+#[tauri::command]
+#[tracing::instrument(skip(app, state, options), fields(agent = agent.id()), err)]
+async fn install_skill_by_slug(
+    app: AppHandle,
+    state: tauri::State<'_, SearchState>,
+    agent: AgentType,
+    slug: String,
+    options: Option<InstallOptions>,
+) -> Result<Vec<InstallResult>, String> {
+    let url = match resolve_slug_url(state, slug.clone()).await {
+        Ok(url) => url,
+        Err(e) => {
+            let result = Err(e);
+            record_install_activity(&app, agent, slug, &result);
+            return result;
+        }
+    };
+
+    let target = redact_url(&url);
+    let result = fetch_and_install_from_url(&app, agent, url, None, options).await;
+    record_install_activity(&app, agent, target, &result);
+    result
+}
+
+/// Token estimate for a search result without installing it. Uses the
+/// registry's figure when present, otherwise fetches just its SKILL.md.
+#[tauri::command]
+async fn estimate_search_skill_tokens(
+    state: tauri::State<'_, SearchState>,
+    slug: String,
+) -> Result<u64, String> {
+    if let Some(tokens) = state.token_estimates.lock().ok().and_then(|m| m.get(&slug).copied()) {
+        return Ok(tokens);
+    }
+
+    let skill = state
+        .find_by_slug(&slug)
+        .ok_or("Skill not found in recent search results")?;
+    let tokens = match skill.token_estimate {
+        Some(tokens) => tokens,
+        None => {
+            let url = github_skill_md_url(&skill.source)?;
+            let client = http_client()?;
+            let content = authorized_get(&client, &url, None)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?
+                .error_for_status()
+                .map_err(|e| format!("Cannot fetch SKILL.md: {}", e))?
+                .bytes()
+                .await
+                .map_err(|e| e.to_string())?;
+            estimate_tokens(content.len() as u64)
+        }
+    };
+
+    if let Ok(mut estimates) = state.token_estimates.lock() {
+        estimates.insert(slug, tokens);
+    }
+    Ok(tokens)
+}
+
+/// Raw SKILL.md URL for a registry source: `owner/repo` or
+/// `owner/repo/tree/<branch>/<path>`, as installs resolve it on GitHub
+fn github_skill_md_url(source: &str) -> Result<String, String> {
+    let parts: Vec<&str> = source.trim_matches('/').split('/').collect();
+    match parts.as_slice() {
+        [owner, repo] => Ok(format!(
+            "https://raw.githubusercontent.com/{}/{}/HEAD/{}",
+            owner, repo, SKILL_MANIFEST
+        )),
+        [owner, repo, "tree", branch, path @ ..] => {
+            let dir = if path.is_empty() {
+                String::new()
+            } else {
+                format!("{}/", path.join("/"))
+            };
+            Ok(format!(
+                "https://raw.githubusercontent.com/{}/{}/{}/{}{}",
+                owner, repo, branch, dir, SKILL_MANIFEST
+            ))
+        }
+        _ => Err(format!("Cannot locate SKILL.md for source: {}", source)),
+    }
+}
+
+// ============================================================================
+// MCP Server Commands
+// ============================================================================
+
+/// Servers in the agent's config, in config order. `All` lists every
+/// MCP-capable agent in the `All` scope; configs that can't be read, and
+/// agents whose config format isn't supported, are reported in `errors`.
+#[tauri::command]
+fn list_mcp_servers(agent: AgentType, filter: Option<McpFilter>) -> Result<McpServerList, String> {
+    if agent != AgentType::All {
+        let servers = read_mcp_servers(agent, filter)?;
+        return Ok(McpServerList { servers, errors: Vec::new() });
+    }
+
+    let mut list = McpServerList { servers: Vec::new(), errors: Vec::new() };
+    for individual_agent in get_all_scope_agents() {
+        if let Some(error) = unsupported_mcp_config(individual_agent) {
+            list.errors.push(AgentError { agent: individual_agent, error });
+            continue;
+        }
+        if !agent_has_mcp_support(individual_agent) {
+            continue;
+        }
+        match read_mcp_servers(individual_agent, filter) {
+            Ok(servers) => list.servers.extend(servers),
+            Err(error) => list.errors.push(AgentError { agent: individual_agent, error }),
+        }
+    }
+    Ok(list)
+}
+
+/// Servers in one agent's config, in config order; `All` leaves out the
+/// configs `list_mcp_servers` reports as errors
+fn read_mcp_servers(agent: AgentType, filter: Option<McpFilter>) -> Result<Vec<McpServerInfo>, String> {
+    if agent == AgentType::All {
+        return list_mcp_servers(agent, filter).map(|list| list.servers);
+    }
+    if !agent_has_mcp_support(agent) {
+        return Ok(vec![]);
+    }
+    let config_path = get_mcp_config_path(agent)?;
+
+    if !config_path.exists() {
+        return Ok(vec![]);
+    }
+
+    let config = read_mcp_config(&config_path)?;
+    check_mcp_servers(&config, &config_path)?;
+
+    let filter = filter.unwrap_or_default();
+    let servers = config
+        .get("mcpServers")
+        .and_then(|s| s.as_object())
+        .map(|obj| {
+            obj.iter()
+                .map(|(name, value)| parse_mcp_server(agent, name, value))
+                .filter(|server| match filter {
+                    McpFilter::All => true,
+                    McpFilter::Enabled => server.disabled != Some(true),
+                    McpFilter::Disabled => server.disabled == Some(true),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(servers)
+}
+
+#[tauri::command]
+fn find_duplicate_mcp_servers() -> Result<Vec<McpDupGroup>, String> {
+    let mut groups: Vec<(String, McpDupGroup)> = Vec::new();
+
+    for agent in get_all_individual_agents() {
+        if !agent_has_mcp_support(agent) {
+            continue;
+        }
+        // One unreadable config shouldn't hide the rest
+        let Ok(servers) = read_mcp_servers(agent, None) else {
+            continue;
+        };
+
+        for server in servers {
+            let key = mcp_definition_key(&server);
+            let member = McpServerRef { agent, name: server.name.clone() };
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, group)) => group.members.push(member),
+                None => groups.push((key, McpDupGroup { server, members: vec![member] })),
+            }
+        }
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|(_, group)| group)
+        .filter(|group| {
+            let first = group.members[0].agent;
+            group.members.iter().any(|m| m.agent != first)
+        })
+        .collect())
+}
+
+/// Checks every server in an agent's config: that a stdio server's command
+/// is on `PATH`, and that an http server's URL parses. With `check_reachable`
+/// http servers are also contacted; any response counts, since most answer
+/// a bare GET with an error.
+#[tauri::command]
+#[tracing::instrument(err)]
+async fn validate_all_mcp_servers(
+    agent: AgentType,
+    check_reachable: Option<bool>,
+) -> Result<Vec<McpValidation>, String> {
+    let servers = read_mcp_servers(agent, None)?;
+    let check_reachable = check_reachable.unwrap_or(false);
+    let client = reqwest::Client::builder()
+        .user_agent("Oh-My-Skills/0.1")
+        .timeout(MCP_REACH_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(MCP_VALIDATE_PARALLELISM));
+    let mut tasks = tokio::task::JoinSet::new();
+
+    for (index, server) in servers.into_iter().enumerate() {
+        let permit = semaphore.clone().acquire_owned().await.map_err(|e| e.to_string())?;
+        let client = client.clone();
+        tasks.spawn(async move {
+            let _permit = permit;
+            (index, validate_mcp_server(&client, server, check_reachable).await)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        results.push(joined.map_err(|e| e.to_string())?);
+    }
+    // Back in config order
+    results.sort_by_key(|(index, _)| *index);
+    Ok(results.into_iter().map(|(_, validation)| validation).collect())
+}
+
+async fn validate_mcp_server(client: &reqwest::Client, server: McpServerInfo, check_reachable: bool) -> McpValidation {
+    let checked: Result<String, String> = if server.transport == "http" {
+        match server.url.as_deref().map(reqwest::Url::parse) {
+            None => Err("No url set".to_string()),
+            Some(Err(e)) => Err(format!("Invalid url: {}", e)),
+            Some(Ok(url)) if !matches!(url.scheme(), "http" | "https") => {
+                Err(format!("Unsupported url scheme: {}", url.scheme()))
+            }
+            Some(Ok(url)) if check_reachable => match client.get(url.clone()).send().await {
+                Ok(_) => Ok(url.to_string()),
+                Err(e) if e.is_timeout() => Err(format!("No response within {}s", MCP_REACH_TIMEOUT.as_secs())),
+                Err(e) => Err(format!("Unreachable: {}", e)),
+            },
+            Some(Ok(url)) => Ok(url.to_string()),
+        }
+    } else {
+        match server.command.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+            None => Err("No command set".to_string()),
+            Some(command) => find_on_path(command)
+                .map(|path| path.to_string_lossy().to_string())
+                .ok_or_else(|| format!("Command not found on PATH: {}", command)),
+        }
+    };
+
+    let (resolved, problem) = match checked {
+        Ok(resolved) => (Some(resolved), None),
+        Err(problem) => (None, Some(problem)),
+    };
+    McpValidation {
+        agent: server.agent,
+        name: server.name,
+        transport: server.transport,
+        disabled: server.disabled == Some(true),
+        ok: problem.is_none(),
+        resolved,
+        problem,
+    }
+}
+
+/// Resolves a command the way a shell would: as given when it contains a
+/// path separator, otherwise from `PATH` (trying `PATHEXT` on Windows)
+fn find_on_path(command: &str) -> Option<PathBuf> {
+    let is_runnable = |path: &Path| {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        }
+        #[cfg(not(unix))]
+        {
+            path.is_file()
+        }
+    };
+
+    if command.contains('/') || command.contains(std::path::MAIN_SEPARATOR) {
+        let path = match command.strip_prefix("~/") {
+            Some(rest) => app_home().ok()?.join(rest),
+            None => PathBuf::from(command),
+        };
+        return is_runnable(&path).then_some(path);
+    }
+
+    let extensions: Vec<String> = if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT".to_string())
+            .split(';')
+            .map(String::from)
+            .chain(std::iter::once(String::new()))
+            .collect()
+    } else {
+        vec![String::new()]
+    };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .flat_map(|dir| extensions.iter().map(move |ext| dir.join(format!("{}{}", command, ext))))
+        .find(|candidate| is_runnable(candidate))
+}
+
+/// What makes two servers the same: command, args and env for stdio, url
+/// and headers for http. Name and enabled state don't count.
+fn mcp_definition_key(server: &McpServerInfo) -> String {
+    fn sorted(map: &Option<HashMap<String, String>>) -> std::collections::BTreeMap<&String, &String> {
+        map.as_ref().map(|m| m.iter().collect()).unwrap_or_default()
+    }
+    let key = if server.transport == "http" {
+        serde_json::json!(["http", server.url, sorted(&server.headers)])
+    } else {
+        serde_json::json!(["stdio", server.command, server.args, sorted(&server.env)])
+    };
+    key.to_string()
+}
+
+/// Well-known servers: (name, command, leading args). Used to flag a server
+/// that takes a familiar name but runs something else.
+const MCP_PRESETS: &[(&str, &str, &[&str])] = &[
+    ("filesystem", "npx", &["-y", "@modelcontextprotocol/server-filesystem"]),
+    ("github", "npx", &["-y", "@modelcontextprotocol/server-github"]),
+    ("memory", "npx", &["-y", "@modelcontextprotocol/server-memory"]),
+    ("sequential-thinking", "npx", &["-y", "@modelcontextprotocol/server-sequential-thinking"]),
+    ("fetch", "uvx", &["mcp-server-fetch"]),
+    ("playwright", "npx", &["@playwright/mcp@latest"]),
+];
+
+/// Adds a server, returning any warnings. Fails if the name is taken unless
+/// `replace` is set; the error then carries the existing definition.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(agent = agent.id(), server = %config.name, ?replace), err)]
+fn add_mcp_server(
+    app: AppHandle,
+    agent: AgentType,
+    config: AddMcpServerRequest,
+    replace: Option<bool>,
+) -> Result<Vec<String>, String> {
+    if agent == AgentType::All {
+        return add_mcp_server_to_all(&app, config, replace);
+    }
+
+    let target = config.name.clone();
+    let result = write_mcp_server(agent, config, replace);
+    record_activity(&app, ActivityEvent::new(ActivityKind::AddMcpServer, Some(agent), target, &result));
+    result
+}
+
+/// Adds the server to every MCP-capable agent in the `All` scope. Agents
+/// that refuse it, or whose config format isn't supported, are reported as
+/// warnings prefixed with the agent id; it's an error only if none took it.
+fn add_mcp_server_to_all(
+    app: &AppHandle,
+    config: AddMcpServerRequest,
+    replace: Option<bool>,
+) -> Result<Vec<String>, String> {
+    let agents = get_mcp_scope_agents();
+    if agents.is_empty() {
+        return Err("No agent in the All scope supports MCP".to_string());
+    }
+
+    let mut warnings: Vec<String> = get_all_scope_agents()
+        .into_iter()
+        .filter_map(|agent| unsupported_mcp_config(agent).map(|e| format!("{}: not added: {}", agent.id(), e)))
+        .collect();
+    let mut added = 0;
+    for agent in agents {
+        let result = write_mcp_server(agent, config.clone(), replace);
+        record_activity(app, ActivityEvent::new(ActivityKind::AddMcpServer, Some(agent), &config.name, &result));
+        match result {
+            Ok(agent_warnings) => {
+                added += 1;
+                warnings.extend(agent_warnings.into_iter().map(|w| format!("{}: {}", agent.id(), w)));
+            }
+            Err(e) => warnings.push(format!("{}: not added: {}", agent.id(), e)),
+        }
+    }
+
+    if added == 0 {
+        return Err(warnings.join("\n"));
+    }
+    Ok(warnings)
+}
+
+fn write_mcp_server(agent: AgentType, mut config: AddMcpServerRequest, replace: Option<bool>) -> Result<Vec<String>, String> {
+    if !agent_has_mcp_support(agent) {
+        return Err("MCP is not supported for this agent".to_string());
+    }
+    let mut warnings = normalize_mcp_request(&mut config)?;
+    let config_path = get_mcp_config_path(agent)?;
+
+    let mut root = read_mcp_config(&config_path)?;
+
+    let mcp_servers = mcp_servers_mut(&mut root, &config_path)?;
+
+    if let Some(previous) = mcp_servers.get(&config.name) {
+        if !replace.unwrap_or(false) {
+            return Err(format!(
+                "MCP server \"{}\" already exists: {}",
+                config.name, previous
+            ));
+        }
+    }
+
+    if let Some((_, command, args)) = MCP_PRESETS.iter().find(|(name, _, _)| *name == config.name) {
+        let matches_preset = config.command.as_deref() == Some(*command)
+            && config
+                .args
+                .as_deref()
+                .is_some_and(|a| a.len() >= args.len() && a.iter().zip(args.iter()).all(|(x, y)| x == y));
+        if !matches_preset {
+            warnings.push(format!(
+                "\"{}\" is usually `{} {}`; this server runs something else",
+                config.name,
+                command,
+                args.join(" ")
+            ));
+        }
+    }
+
+    let mut server_config = serde_json::Map::new();
+
+    if config.transport == "stdio" {
+        server_config.insert("type".to_string(), serde_json::json!("stdio"));
+        if let Some(cmd) = config.command {
+            server_config.insert("command".to_string(), serde_json::json!(cmd));
+        }
+        if let Some(args) = config.args {
+            server_config.insert("args".to_string(), serde_json::json!(args));
+        }
+        if let Some(env) = config.env {
+            server_config.insert("env".to_string(), serde_json::json!(env));
+        }
+    } else {
+        server_config.insert("type".to_string(), serde_json::json!("http"));
+        if let Some(url) = config.url {
+            server_config.insert("url".to_string(), serde_json::json!(url));
+        }
+        if let Some(headers) = config.headers {
+            server_config.insert("headers".to_string(), serde_json::json!(headers));
+        }
+    }
+
+    mcp_servers.insert(config.name, serde_json::Value::Object(server_config));
+
+    write_mcp_config(&config_path, &root)?;
+    Ok(warnings)
+}
+
+/// Tidies a stdio server before it's written: trims the command and args,
+/// drops empty args and trims env names. Returns a warning for each
+/// adjustment; env names that aren't valid identifiers, or collide once
+/// trimmed, are refused.
+fn normalize_mcp_request(config: &mut AddMcpServerRequest) -> Result<Vec<String>, String> {
+    let mut warnings = Vec::new();
+
+    if let Some(command) = config.command.as_mut() {
+        let trimmed = command.trim();
+        if trimmed != command {
+            warnings.push("Trimmed whitespace around the command".to_string());
+            *command = trimmed.to_string();
+        }
+    }
+
+    if let Some(args) = config.args.as_mut() {
+        let before = args.len();
+        let mut trimmed = 0;
+        for arg in args.iter_mut() {
+            if arg.trim() != arg {
+                *arg = arg.trim().to_string();
+                trimmed += 1;
+            }
+        }
+        args.retain(|arg| !arg.is_empty());
+        if trimmed > 0 {
+            warnings.push(format!("Trimmed whitespace around {} argument(s)", trimmed));
+        }
+        if args.len() < before {
+            warnings.push(format!("Dropped {} empty argument(s)", before - args.len()));
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for flag in args.iter().filter(|arg| arg.starts_with("--")) {
+            let name = flag.split('=').next().unwrap_or(flag);
+            if !seen.insert(name) {
+                warnings.push(format!("{} is given more than once", name));
+            }
+        }
+    }
+
+    if let Some(env) = config.env.take() {
+        let mut normalized = HashMap::new();
+        let mut keys: Vec<_> = env.into_iter().collect();
+        keys.sort();
+        for (key, value) in keys {
+            let name = key.trim();
+            let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                return Err(format!("Invalid environment variable name: \"{}\"", key));
+            }
+            if name != key {
+                warnings.push(format!("Trimmed whitespace around environment variable {}", name));
+            }
+            if normalized.insert(name.to_string(), value).is_some() {
+                return Err(format!("Environment variable {} is set more than once", name));
+            }
+        }
+        config.env = Some(normalized);
+    }
+
+    Ok(warnings)
+}
+
+#[tauri::command]
+#[tracing::instrument(fields(agent = agent.id()), skip(app, agent), err)]
+fn remove_mcp_server(app: AppHandle, agent: AgentType, name: String) -> Result<(), String> {
+    let result = delete_mcp_server(agent, &name);
+    record_activity(&app, ActivityEvent::new(ActivityKind::RemoveMcpServer, Some(agent), name, &result));
+    result
+}
+
+fn delete_mcp_server(agent: AgentType, name: &str) -> Result<(), String> {
+    if !agent_has_mcp_support(agent) {
+        return Err("MCP is not supported for this agent".to_string());
+    }
+    let config_path = get_mcp_config_path(agent)?;
+
+    if !config_path.exists() {
+        return Ok(());
+    }
+
+    let mut root = read_mcp_config(&config_path)?;
+    check_mcp_servers(&root, &config_path)?;
+
+    if let Some(mcp_servers) = root
+        .get_mut("mcpServers")
+        .and_then(|s| s.as_object_mut())
+    {
+        mcp_servers.shift_remove(name);
+    }
+
+    write_mcp_config(&config_path, &root)
+}
+
+#[tauri::command]
+#[tracing::instrument(fields(agent = agent.id()), skip(app, agent), err)]
+fn toggle_mcp_server(app: AppHandle, agent: AgentType, name: String, disabled: bool) -> Result<(), String> {
+    let result = set_mcp_server_disabled(agent, &name, disabled);
+    record_activity(
+        &app,
+        ActivityEvent::new(ActivityKind::ToggleMcpServer, Some(agent), name, &result)
+            .with_detail(if disabled { "disabled" } else { "enabled" }),
+    );
+    result
+}
+
+fn set_mcp_server_disabled(agent: AgentType, name: &str, disabled: bool) -> Result<(), String> {
+    if !agent_has_mcp_support(agent) {
+        return Err("MCP is not supported for this agent".to_string());
+    }
+    let config_path = get_mcp_config_path(agent)?;
+
+    if !config_path.exists() {
+        return Err("Config file not found".to_string());
+    }
+
+    let mut root = read_mcp_config(&config_path)?;
+    check_mcp_servers(&root, &config_path)?;
+
+    if let Some(server) = root
+        .get_mut("mcpServers")
+        .and_then(|s| s.get_mut(name))
+        .and_then(|s| s.as_object_mut())
+    {
+        // Keep whichever representation the server already uses
+        if server.contains_key("enabled") && !server.contains_key("disabled") {
+            server.insert("enabled".to_string(), serde_json::json!(!disabled));
+        } else if disabled {
+            server.insert("disabled".to_string(), serde_json::json!(true));
+        } else {
+            server.shift_remove("disabled");
+        }
+    }
+
+    write_mcp_config(&config_path, &root)
+}
+
+/// Rewrites `mcpServers` in the given order, for agents that start or list
+/// servers in config order. `order` must name every configured server
+/// exactly once.
+#[tauri::command]
+fn reorder_mcp_servers(app: AppHandle, agent: AgentType, order: Vec<String>) -> Result<(), String> {
+    let result = write_mcp_server_order(agent, &order);
+    record_activity(
+        &app,
+        ActivityEvent::new(ActivityKind::EditMcpConfig, Some(agent), "mcpServers", &result).with_detail("reordered"),
+    );
+    result
+}
+
+fn write_mcp_server_order(agent: AgentType, order: &[String]) -> Result<(), String> {
+    if !agent_has_mcp_support(agent) {
+        return Err("MCP is not supported for this agent".to_string());
+    }
+    let config_path = get_mcp_config_path(agent)?;
+    if !config_path.exists() {
+        return Err("Config file not found".to_string());
+    }
+
+    let mut root = read_mcp_config(&config_path)?;
+    check_mcp_servers(&root, &config_path)?;
+    let Some(servers) = root.get_mut("mcpServers").and_then(|s| s.as_object_mut()) else {
+        return Err("No MCP servers configured".to_string());
+    };
+
+    let mut remaining = std::mem::take(servers);
+    let mut reordered = serde_json::Map::new();
+    for name in order {
+        let server = remaining
+            .remove(name)
+            .ok_or_else(|| format!("Unknown or repeated server: {}", name))?;
+        reordered.insert(name.clone(), server);
+    }
+    if !remaining.is_empty() {
+        let missing: Vec<&str> = remaining.keys().map(String::as_str).collect();
+        return Err(format!("Order is missing: {}", missing.join(", ")));
+    }
+
+    *servers = reordered;
+    write_mcp_config(&config_path, &root)
+}
+
+/// Whether the agent's MCP config can be saved: the file itself when it
+/// exists, and the folder it lives in
+#[tauri::command]
+fn can_write_mcp_config(agent: AgentType) -> Result<bool, String> {
+    if !agent_has_mcp_support(agent) {
+        return Ok(false);
+    }
+    let config_path = get_mcp_config_path(agent)?;
+    let parent = config_path.parent().ok_or("Invalid config path")?;
+    Ok(is_dir_writable(parent) && (!config_path.exists() || is_file_writable(&config_path)))
+}
+
+/// Full contents of the agent's config file, for editing settings the
+/// structured commands don't cover. Empty if the file doesn't exist yet.
+#[tauri::command]
+fn get_mcp_config_raw(agent: AgentType) -> Result<String, String> {
+    let config_path = get_mcp_config_path(agent)?;
+
+    if !config_path.exists() {
+        return Ok(String::new());
+    }
+
+    read_text(&config_path).map_err(|e| e.to_string())
+}
+
+/// Replaces the agent's config file after checking that `content` parses.
+/// The previous file is kept alongside as `<name>.bak`.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(agent = agent.id(), bytes = content.len()), err)]
+fn set_mcp_config_raw(app: AppHandle, agent: AgentType, content: String) -> Result<(), String> {
+    let result = replace_mcp_config(agent, &content);
+    let target = get_mcp_config_path(agent)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| agent.id().to_string());
+    record_activity(&app, ActivityEvent::new(ActivityKind::EditMcpConfig, Some(agent), target, &result));
+    result
+}
+
+/// Replaces a malformed `mcpServers` (an array, string, ...) with an empty
+/// object, leaving the rest of the config alone. The previous file is kept
+/// as `<name>.bak`. Refuses when `mcpServers` is fine, so it can't empty a
+/// working config by accident.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(agent = agent.id()), err)]
+fn reset_mcp_servers(app: AppHandle, agent: AgentType) -> Result<(), String> {
+    let result = reset_malformed_mcp_servers(agent);
+    let target = get_mcp_config_path(agent)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| agent.id().to_string());
+    record_activity(
+        &app,
+        ActivityEvent::new(ActivityKind::EditMcpConfig, Some(agent), target, &result).with_detail("reset mcpServers"),
+    );
+    result
+}
+
+fn reset_malformed_mcp_servers(agent: AgentType) -> Result<(), String> {
+    if !agent_has_mcp_support(agent) {
+        return Err("MCP is not supported for this agent".to_string());
+    }
+    let config_path = get_mcp_config_path(agent)?;
+    let mut root = read_mcp_config(&config_path)?;
+    let config = root.as_object_mut().ok_or("Invalid config format")?;
+    if config.get("mcpServers").is_none_or(|s| s.is_object()) {
+        return Err("mcpServers is not malformed; nothing to reset".to_string());
+    }
+
+    backup_mcp_config(&config_path)?;
+    config.insert("mcpServers".to_string(), serde_json::json!({}));
+    write_mcp_config(&config_path, &root)
+}
+
+/// The config's `mcpServers` object, created if absent or `null`
+fn mcp_servers_mut<'a>(
+    root: &'a mut serde_json::Value,
+    config_path: &Path,
+) -> Result<&'a mut serde_json::Map<String, serde_json::Value>, String> {
+    let servers = root
+        .as_object_mut()
+        .ok_or("Invalid config format")?
+        .entry("mcpServers")
+        .or_insert(serde_json::json!({}));
+    if servers.is_null() {
+        *servers = serde_json::json!({});
+    }
+    match servers {
+        serde_json::Value::Object(map) => Ok(map),
+        other => Err(malformed_mcp_servers_error(config_path, other)),
+    }
+}
+
+/// Fails with `malformed_mcp_servers_error` when `mcpServers` is present
+/// but neither an object nor `null`
+fn check_mcp_servers(root: &serde_json::Value, config_path: &Path) -> Result<(), String> {
+    match root.get("mcpServers") {
+        Some(servers) if !servers.is_object() && !servers.is_null() => {
+            Err(malformed_mcp_servers_error(config_path, servers))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn malformed_mcp_servers_error(config_path: &Path, servers: &serde_json::Value) -> String {
+    let kind = match servers {
+        serde_json::Value::Array(_) => "an array",
+        serde_json::Value::String(_) => "a string",
+        serde_json::Value::Number(_) => "a number",
+        serde_json::Value::Bool(_) => "a boolean",
+        _ => "not an object",
+    };
+    format!(
+        "mcpServers in {} is {} instead of an object. Reset it to fix this; the current file is backed up first.",
+        config_path.display(),
+        kind
+    )
+}
+
+/// Copies the config to `<name>.bak` if it exists
+fn backup_mcp_config(config_path: &Path) -> Result<(), String> {
+    if config_path.exists() {
+        let mut backup = config_path.as_os_str().to_owned();
+        backup.push(".bak");
+        fs::copy(config_path, &backup).map_err(|e| format!("Cannot back up config: {}", e))?;
+    }
+    Ok(())
+}
+
+fn replace_mcp_config(agent: AgentType, content: &str) -> Result<(), String> {
+    let config_path = get_mcp_config_path(agent)?;
+
+    if config_path.extension().is_some_and(|ext| ext == "toml") {
+        content
+            .parse::<toml::Table>()
+            .map_err(|e| format!("Invalid TOML: {}", e))?;
+    } else {
+        serde_json::from_str::<serde_json::Value>(content)
+            .map_err(|e| format!("Invalid JSON: {}", e))?;
+    }
+
+    if config_path.exists() {
+        backup_mcp_config(&config_path)?;
+    } else if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    // Renamed into place so a crash mid-write can't truncate the live config
+    let mut tmp_name = config_path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = config_path.with_file_name(tmp_name);
+    fs::write(&tmp, content).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &config_path).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Settings Commands
+// ============================================================================
+
+/// Hosts that have saved credentials; the headers themselves stay backend-only
+#[tauri::command]
+fn list_credential_hosts() -> Result<Vec<String>, String> {
+    let mut hosts: Vec<String> = load_settings()?.credentials.0.into_keys().collect();
+    hosts.sort();
+    Ok(hosts)
+}
+
+/// Saves headers sent with every request to `host`; `None` forgets them
+#[tauri::command]
+fn set_host_credentials(
+    app: AppHandle,
+    host: String,
+    headers: Option<HashMap<String, String>>,
+) -> Result<(), String> {
+    // Only the host is recorded, never the header values
+    let target = format!("credentials for {}", host.trim());
+    let result = save_host_credentials(host, headers);
+    record_activity(&app, ActivityEvent::new(ActivityKind::UpdateSettings, None, target, &result));
+    result
+}
+
+fn save_host_credentials(host: String, headers: Option<HashMap<String, String>>) -> Result<(), String> {
+    let host = host.trim().to_lowercase();
+    if host.is_empty() {
+        return Err("Host is required".to_string());
+    }
+
+    let mut settings = load_settings()?;
+    match headers {
+        Some(headers) if !headers.is_empty() => {
+            settings.credentials.0.insert(host, headers);
+        }
+        _ => {
+            settings.credentials.0.remove(&host);
+        }
+    }
+    save_settings(&settings)
+}
+
+/// Recorded activity, newest first
+#[tauri::command]
+fn get_activity_history(limit: Option<usize>) -> Result<Vec<ActivityEvent>, String> {
+    let path = get_app_data_dir()?.join(ACTIVITY_FILE);
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(vec![]);
+    };
+
+    let mut events: Vec<ActivityEvent> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    events.reverse();
+    events.truncate(limit.unwrap_or(ACTIVITY_HISTORY_MAX));
+    Ok(events)
+}
+
+/// The folder an `All` install of `skill_name` would use for each agent in
+/// scope, so the user can see every location before confirming
+#[tauri::command]
+fn resolve_all_targets(skill_name: String, namespace: Option<String>) -> Result<Vec<InstallTarget>, String> {
+    let name = sanitize_name(&skill_name);
+    if name.is_empty() {
+        return Err(format!("Invalid skill name: {}", skill_name));
+    }
+
+    get_all_scope_agents()
+        .into_iter()
+        .map(|agent| {
+            let skill_dir = resolve_skill_dir(agent, &name, namespace.as_deref())?;
+            Ok(InstallTarget {
+                agent,
+                exists: skill_dir.exists(),
+                pinned: read_metadata(&skill_dir).is_some_and(|m| m.pinned),
+                path: skill_dir.to_string_lossy().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The agents `All` currently resolves to
+#[tauri::command]
+fn get_all_scope() -> Vec<AgentType> {
+    get_all_scope_agents()
+}
+
+/// Limits `All` to `agents`; `None` goes back to every agent
+#[tauri::command]
+fn set_all_scope(app: AppHandle, agents: Option<Vec<AgentType>>) -> Result<(), String> {
+    let result = save_all_scope(agents);
+    record_activity(&app, ActivityEvent::new(ActivityKind::UpdateSettings, None, "All scope", &result));
+    result
+}
+
+fn save_all_scope(agents: Option<Vec<AgentType>>) -> Result<(), String> {
+    if let Some(agents) = &agents {
+        if agents.is_empty() {
+            return Err("Choose at least one agent".to_string());
+        }
+        if agents.contains(&AgentType::All) {
+            return Err("All cannot be part of its own scope".to_string());
+        }
+    }
+
+    let mut settings = load_settings()?;
+    settings.all_scope = agents;
+    save_settings(&settings)
+}
+
+/// The frontmatter keys skill names are read from, in priority order
+#[tauri::command]
+fn get_name_keys() -> Vec<String> {
+    skill_name_keys()
+}
+
+/// Sets the keys skill names are read from; `None` restores the default
+#[tauri::command]
+fn set_name_keys(app: AppHandle, keys: Option<Vec<String>>) -> Result<(), String> {
+    let result = save_name_keys(keys);
+    record_activity(&app, ActivityEvent::new(ActivityKind::UpdateSettings, None, "name keys", &result));
+    result
+}
+
+fn save_name_keys(keys: Option<Vec<String>>) -> Result<(), String> {
+    let keys = keys.map(|keys| {
+        keys.into_iter()
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+            .collect::<Vec<_>>()
+    });
+    if keys.as_ref().is_some_and(|k| k.is_empty()) {
+        return Err("Choose at least one key".to_string());
+    }
+
+    let mut settings = load_settings()?;
+    settings.name_keys = keys;
+    save_settings(&settings)
+}
+
+/// Runs `cleanup` for all agents on each launch
+#[tauri::command]
+fn set_cleanup_on_startup(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let result = load_settings().and_then(|mut settings| {
+        settings.cleanup_on_startup = enabled;
+        save_settings(&settings)
+    });
+    record_activity(
+        &app,
+        ActivityEvent::new(ActivityKind::UpdateSettings, None, "cleanup on startup", &result)
+            .with_detail(if enabled { "enabled" } else { "disabled" }),
+    );
+    result
+}
+
+/// Shows servers' error bodies in failed install errors, for diagnosing
+/// URLs that won't install
+#[tauri::command]
+fn set_debug_http_errors(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let result = load_settings().and_then(|mut settings| {
+        settings.debug_http_errors = enabled;
+        save_settings(&settings)
+    });
+    record_activity(
+        &app,
+        ActivityEvent::new(ActivityKind::UpdateSettings, None, "debug HTTP errors", &result)
+            .with_detail(if enabled { "enabled" } else { "disabled" }),
+    );
+    result
+}
+
+fn skill_name_keys() -> Vec<String> {
+    load_settings()
+        .ok()
+        .and_then(|s| s.name_keys)
+        .unwrap_or_else(|| DEFAULT_NAME_KEYS.iter().map(|k| k.to_string()).collect())
+}
+
+/// Directory holding the rolling log files, one per day
+#[tauri::command]
+fn get_log_path() -> Result<String, String> {
+    Ok(get_log_dir()?.to_string_lossy().to_string())
+}
+
+/// Saves the log filter and applies it immediately; `None` restores the default
+#[tauri::command]
+fn set_log_level(app: AppHandle, level: Option<String>) -> Result<(), String> {
+    let target = format!("log level {}", level.as_deref().unwrap_or(DEFAULT_LOG_LEVEL));
+    let result = apply_log_level(&app, level);
+    record_activity(&app, ActivityEvent::new(ActivityKind::UpdateSettings, None, target, &result));
+    result
+}
+
+fn apply_log_level(app: &AppHandle, level: Option<String>) -> Result<(), String> {
+    let level = level.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    let filter = tracing_subscriber::EnvFilter::try_new(level.as_deref().unwrap_or(DEFAULT_LOG_LEVEL))
+        .map_err(|e| format!("Invalid log level: {}", e))?;
+
+    let mut settings = load_settings()?;
+    settings.log_level = level;
+    save_settings(&settings)?;
+
+    if let Some(logging) = app.try_state::<LogState>() {
+        logging.filter.reload(filter).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// The hook for `agent`, or the global one when `agent` is `None`
+#[tauri::command]
+fn get_post_install_hook(agent: Option<AgentType>) -> Result<Option<PostInstallHook>, String> {
+    let settings = load_settings()?;
+    Ok(match agent {
+        Some(agent) => settings.agent_post_install_hooks.get(agent.id()).cloned(),
+        None => settings.post_install_hook,
+    })
+}
+
+/// Saves or removes a hook. Approval is kept only while the command text is
+/// unchanged, so an edited hook has to be approved again.
+#[tauri::command]
+fn set_post_install_hook(
+    app: AppHandle,
+    agent: Option<AgentType>,
+    hook: Option<PostInstallHook>,
+) -> Result<(), String> {
+    let result = save_post_install_hook(agent, hook);
+    record_activity(&app, ActivityEvent::new(ActivityKind::UpdateSettings, agent, "post-install hook", &result));
+    result
+}
+
+fn save_post_install_hook(agent: Option<AgentType>, hook: Option<PostInstallHook>) -> Result<(), String> {
+    let mut settings = load_settings()?;
+    let slot = match agent {
+        Some(agent) => settings.agent_post_install_hooks.remove(agent.id()),
+        None => settings.post_install_hook.take(),
+    };
+
+    let hook = hook.map(|mut hook| {
+        hook.approved_command = slot
+            .and_then(|previous| previous.approved_command)
+            .filter(|approved| *approved == hook.command);
+        hook
+    });
+
+    match (agent, hook) {
+        (Some(agent), Some(hook)) => {
+            settings.agent_post_install_hooks.insert(agent.id().to_string(), hook);
+        }
+        (None, hook) => settings.post_install_hook = hook,
+        (Some(_), None) => {}
+    }
+    save_settings(&settings)
+}
+
+/// Records that the user has seen and accepted `command`. Fails if the
+/// saved hook has changed since it was shown.
+#[tauri::command]
+fn approve_post_install_hook(app: AppHandle, agent: Option<AgentType>, command: String) -> Result<(), String> {
+    let result = mark_post_install_hook_approved(agent, command);
+    record_activity(
+        &app,
+        ActivityEvent::new(ActivityKind::UpdateSettings, agent, "post-install hook approval", &result),
+    );
+    result
+}
+
+fn mark_post_install_hook_approved(agent: Option<AgentType>, command: String) -> Result<(), String> {
+    let mut settings = load_settings()?;
+    let hook = match agent {
+        Some(agent) => settings.agent_post_install_hooks.get_mut(agent.id()),
+        None => settings.post_install_hook.as_mut(),
+    }
+    .ok_or("No post-install hook configured")?;
+
+    if hook.command != command {
+        return Err("Post-install hook changed; review it again".to_string());
+    }
+    hook.approved_command = Some(command);
+    save_settings(&settings)
+}
+
+// ============================================================================
+// App Data Commands
+// ============================================================================
+
+#[tauri::command]
+fn get_data_dir() -> Result<String, String> {
+    Ok(get_app_data_dir()?.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn set_data_dir(app: AppHandle, path: String) -> Result<String, String> {
+    let target = format!("data directory {}", path.trim());
+    let result = move_data_dir(path);
+    record_activity(&app, ActivityEvent::new(ActivityKind::UpdateSettings, None, target, &result));
+    result
+}
+
+fn move_data_dir(path: String) -> Result<String, String> {
+    let target = PathBuf::from(path.trim());
+    if !target.is_absolute() {
+        return Err("Data directory must be an absolute path".to_string());
+    }
+
+    let current = get_app_data_dir()?;
+    if target == current {
+        return Ok(target.to_string_lossy().to_string());
+    }
+    if target.starts_with(&current) {
+        return Err("Data directory cannot be moved inside itself".to_string());
+    }
+
+    if target.exists() {
+        let is_empty = fs::read_dir(&target)
+            .map(|mut entries| entries.next().is_none())
+            .unwrap_or(false);
+        if !target.is_dir() || !is_empty {
+            return Err(format!("{} already exists and is not empty", target.display()));
+        }
+        fs::remove_dir(&target).map_err(|e| e.to_string())?;
+    }
+
+    if !is_dir_writable(&target) {
+        return Err(format!("{} is not writable", target.display()));
+    }
+
+    // Whether the data was renamed into place, or copied and the original
+    // still has to go
+    let mut renamed = false;
+    if current.exists() {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        renamed = fs::rename(&current, &target).is_ok();
+        // Across disks, copy to a staging dir first so a failed copy never
+        // leaves a partial data dir at the target.
+        if !renamed {
+            let staging = target.with_file_name(format!(
+                ".{}.partial",
+                target.file_name().and_then(|n| n.to_str()).unwrap_or("oh-my-skills")
+            ));
+            let copied = copy_dir_all(&current, &staging)
+                .and_then(|_| fs::rename(&staging, &target).map_err(|e| e.to_string()));
+            if let Err(e) = copied {
+                let _ = fs::remove_dir_all(&staging);
+                return Err(e);
+            }
+        }
+    } else {
+        fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+    }
+
+    // Until the pointer names the target the app still reads `current`, so
+    // undo the move if it can't be written
+    if let Err(e) = write_data_dir_pointer(&target) {
+        let rolled_back = if renamed {
+            fs::rename(&target, &current)
+        } else {
+            fs::remove_dir_all(&target)
+        };
+        if let Err(rollback) = rolled_back {
+            tracing::error!(error = %rollback, target = %target.display(), "could not undo the data dir move");
+        }
+        return Err(e);
+    }
+    if current.exists() && !renamed {
+        // The copy is in use now; a leftover original only costs space
+        if let Err(e) = fs::remove_dir_all(&current) {
+            tracing::warn!(error = %e, path = %current.display(), "could not remove the old data dir");
+        }
+    }
+
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Points the app at `target`, or back at the default data dir
+fn write_data_dir_pointer(target: &Path) -> Result<(), String> {
+    let pointer = get_data_dir_pointer_path()?;
+    if let Some(parent) = pointer.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    if target == get_default_app_data_dir()? {
+        return match fs::remove_file(&pointer) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        };
+    }
+    let tmp = pointer.with_extension("tmp");
+    fs::write(&tmp, target.to_string_lossy().as_bytes()).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &pointer).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Trash Commands
+// ============================================================================
+
+#[tauri::command]
+fn list_trash() -> Result<Vec<TrashItem>, String> {
+    let trash_dir = get_trash_dir()?;
+
+    if !trash_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut items = Vec::new();
+    let entries = fs::read_dir(&trash_dir).map_err(|e| e.to_string())?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let id = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string();
+
+        if let Some(mut item) = parse_trash_id(&id, &path) {
+            item.namespace = read_trash_namespace(&trash_dir, &id);
+            items.push(item);
+        }
+    }
+
+    // Most recently trashed first
+    items.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(items)
+}
+
+#[tauri::command]
+fn restore_from_trash(app: AppHandle, id: String) -> Result<String, String> {
+    let result = restore_trash_item(&id);
+    let agent = parse_trash_id(&id, Path::new("")).map(|item| item.agent);
+    record_activity(&app, ActivityEvent::new(ActivityKind::Restore, agent, &id, &result));
+    result
+}
+
+fn restore_trash_item(id: &str) -> Result<String, String> {
+    // Ids come from the frontend and name a single entry in the trash
+    if id.is_empty() || id.contains(['/', '\\', '\0']) || id.contains("..") {
+        return Err(format!("Invalid trash item: {}", id));
+    }
+    let trash_dir = get_trash_dir()?;
+    let trashed = trash_dir.join(id);
+
+    let item = parse_trash_id(id, &trashed).ok_or("Invalid trash item")?;
+    if !trashed.is_dir() {
+        return Err(format!("Trash item not found: {}", id));
+    }
+
+    let namespace = read_trash_namespace(&trash_dir, id);
+    let skill_dir = resolve_skill_dir(item.agent, &item.name, namespace.as_deref())?;
+    if skill_dir.exists() {
+        return Err(format!("Skill already exists: {}", item.name));
+    }
+
+    if let Some(parent) = skill_dir.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    move_dir(&trashed, &skill_dir)?;
+    let _ = fs::remove_file(trash_namespace_path(&trash_dir, id));
+
+    Ok(format!("Restored: {}", item.name))
+}
+
+/// Namespaced skills get a `<id>.namespace` file beside their trash entry
+fn trash_namespace_path(trash_dir: &Path, id: &str) -> PathBuf {
+    trash_dir.join(format!("{}.namespace", id))
+}
+
+fn read_trash_namespace(trash_dir: &Path, id: &str) -> Option<String> {
+    let namespace = fs::read_to_string(trash_namespace_path(trash_dir, id)).ok()?;
+    let namespace = namespace.trim();
+    validate_namespace(namespace).ok()?;
+    Some(namespace.to_string())
+}
+
+/// The namespace folder `skill_dir` sits in, `None` at the top level
+fn skill_dir_namespace(agent: AgentType, skill_dir: &Path) -> Option<String> {
+    let parent = skill_dir.parent()?;
+    let skills_dir = get_skills_dir(agent).ok()?;
+    if parent == skills_dir || parent.parent()? != skills_dir {
+        return None;
+    }
+    Some(parent.file_name()?.to_str()?.to_string())
+}
+
+/// Trash entries are named `<millis>__<agent>__<dir name>`
+fn parse_trash_id(id: &str, path: &Path) -> Option<TrashItem> {
+    let mut parts = id.splitn(3, "__");
+    let millis: i64 = parts.next()?.parse().ok()?;
+    let agent = AgentType::from_id(parts.next()?)?;
+    let name = parts.next()?.to_string();
+
+    let trashed_at = chrono::DateTime::from_timestamp_millis(millis)?.to_rfc3339();
+
+    Some(TrashItem {
+        id: id.to_string(),
+        agent,
+        name,
+        namespace: None,
+        trashed_at,
+        path: path.to_string_lossy().to_string(),
+    })
+}
+
+/// Moves a skill directory into the trash and returns its trash id. A
+/// namespaced skill's namespace is recorded so it restores back into it.
+fn move_to_trash(agent: AgentType, skill_dir: &Path) -> Result<String, String> {
+    let name = skill_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Invalid skill directory")?;
+
+    let trash_dir = get_trash_dir()?;
+    fs::create_dir_all(&trash_dir).map_err(|e| e.to_string())?;
+
+    // Bump the timestamp on collision, e.g. bulk clears within one millisecond
+    let mut millis = chrono::Utc::now().timestamp_millis();
+    let mut id = format!("{}__{}__{}", millis, agent.id(), name);
+    while trash_dir.join(&id).exists() {
+        millis += 1;
+        id = format!("{}__{}__{}", millis, agent.id(), name);
+    }
+    if let Some(namespace) = skill_dir_namespace(agent, skill_dir) {
+        fs::write(trash_namespace_path(&trash_dir, &id), namespace).map_err(|e| e.to_string())?;
+    }
+    if let Err(e) = move_dir(skill_dir, &trash_dir.join(&id)) {
+        let _ = fs::remove_file(trash_namespace_path(&trash_dir, &id));
+        return Err(e);
+    }
+
+    Ok(id)
+}
+
+/// Moves an existing skill out of the way before a fresh install, so the
+/// previous version can be restored from trash. Returns its trash id, if
+/// one existed.
+fn displace_existing_skill(app: &AppHandle, agent: AgentType, skill_dir: &Path) -> Result<Option<String>, String> {
+    if !skill_dir.exists() {
+        return Ok(None);
+    }
+
+    let previous = read_metadata(skill_dir);
+    let name = skill_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_string();
+
+    let trash_id = move_to_trash(agent, skill_dir)?;
+
+    let _ = app.emit(
+        "skill-overwritten",
+        SkillOverwrittenEvent {
+            agent,
+            name,
+            previous,
+            trash_id: trash_id.clone(),
+        },
+    );
+
+    Ok(Some(trash_id))
+}
+
+// ============================================================================
+// Bundle Commands
+// ============================================================================
+
+/// Packs skills from every agent, MCP servers and the app's own settings
+/// into one base64 ZIP for moving to another machine
+#[tauri::command]
+fn export_bundle() -> Result<BundleExport, String> {
+    let mut warnings = Vec::new();
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let mut manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        skills: vec![],
+        mcp_agents: vec![],
+        app_files: vec![],
+    };
+
+    let skill_agents = get_distinct_skill_agents();
+    for agent in get_all_individual_agents() {
+        let skills = if skill_agents.contains(&agent) {
+            list_skills_for_agent(agent, None, true).unwrap_or_default()
+        } else {
+            vec![]
+        };
+        for skill in skills {
+            let entry = BundleSkill {
+                agent,
+                name: skill.name,
+                namespace: skill.namespace,
+            };
+            // One unreadable skill is left out rather than failing the bundle
+            let files = match collect_skill_files(Path::new(&skill.path)) {
+                Ok(files) => files,
+                Err(e) => {
+                    warnings.push(format!("{} left out: {}", entry.bundle_dir(), e));
+                    continue;
+                }
+            };
+            if !skill_has_license(Path::new(&skill.path)) {
+                warnings.push(format!("{} declares no license", entry.bundle_dir()));
+            }
+            for file in files {
+                let path = format!("skills/{}/{}", entry.bundle_dir(), file.path);
+                add_zip_file(&mut zip, &path, &file.content, file.mode)?;
+            }
+            manifest.skills.push(entry);
+        }
+
+        if agent_has_mcp_support(agent) {
+            // Only the servers: agent configs hold plenty of unrelated state
+            let config = read_mcp_config(&get_mcp_config_path(agent)?).unwrap_or_default();
+            if let Some(servers) = config.get("mcpServers").filter(|s| s.is_object()) {
+                let json = serde_json::to_vec_pretty(&serde_json::json!({ "mcpServers": servers }))
+                    .map_err(|e| e.to_string())?;
+                add_zip_file(&mut zip, &format!("mcp/{}.json", agent.id()), &json, None)?;
+                manifest.mcp_agents.push(agent);
+            }
+        }
+    }
+
+    // Top-level app files only; the trash stays behind
+    let data_dir = get_app_data_dir()?;
+    if let Ok(entries) = fs::read_dir(&data_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !path.is_file() || is_junk_file(&name) {
+                continue;
+            }
+            let content = if name == SETTINGS_FILE {
+                // Saved tokens are machine-local and never leave in a bundle
+                let mut settings = load_settings()?;
+                settings.credentials = HostCredentials::default();
+                serde_json::to_vec_pretty(&settings).map_err(|e| e.to_string())?
+            } else {
+                fs::read(&path).map_err(|e| e.to_string())?
+            };
+            add_zip_file(&mut zip, &format!("app/{}", name), &content, None)?;
+            manifest.app_files.push(name);
+        }
+    }
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    add_zip_file(&mut zip, "manifest.json", &manifest_json, None)?;
+
+    let cursor = zip.finish().map_err(|e| e.to_string())?;
+    Ok(BundleExport {
+        bundle: STANDARD.encode(cursor.into_inner()),
+        warnings,
+    })
+}
+
+#[tauri::command]
+fn import_bundle(
+    app: AppHandle,
+    zip_base64: String,
+    options: Option<ImportBundleOptions>,
+) -> Result<ImportBundleReport, String> {
+    let result = import_bundle_contents(&app, zip_base64, options);
+    record_activity(&app, ActivityEvent::new(ActivityKind::ImportBundle, None, "bundle", &result));
+    result
+}
+
+/// Settings from someone else's bundle, made safe to save here: no hook
+/// runs until approved on this machine, and credentials and SSH hosts
+/// stay as they are locally
+fn untrusted_bundle_settings(mut settings: Settings, local: Settings) -> Settings {
+    for hook in settings.post_install_hook.iter_mut().chain(settings.agent_post_install_hooks.values_mut()) {
+        hook.approved_command = None;
+    }
+    settings.credentials = local.credentials;
+    settings.remote_hosts = local.remote_hosts;
+    settings
+}
+
+fn import_bundle_contents(
+    app: &AppHandle,
+    zip_base64: String,
+    options: Option<ImportBundleOptions>,
+) -> Result<ImportBundleReport, String> {
+    let options = options.unwrap_or_default();
+    let zip_data = STANDARD
+        .decode(&zip_base64)
+        .map_err(|e| format!("Invalid base64: {}", e))?;
+
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(&zip_data)).map_err(|e| format!("Invalid ZIP: {}", e))?;
+
+    let manifest: BundleManifest = {
+        let mut file = archive
+            .by_name("manifest.json")
+            .map_err(|_| "Not a bundle: manifest.json is missing".to_string())?;
+        let mut content = String::new();
+        file.read_to_string(&mut content).map_err(|e| e.to_string())?;
+        serde_json::from_str(&content).map_err(|e| format!("Invalid manifest: {}", e))?
+    };
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(format!(
+            "Bundle format {} is newer than this app supports",
+            manifest.format_version
+        ));
     }
 
-    let mut items = Vec::new();
-    let entries = fs::read_dir(&target_dir).map_err(|e| e.to_string())?;
+    let mut report = ImportBundleReport::default();
+    // Paths relative to `skills/`; `manifest.skills` says which folder is which skill
+    let mut skill_entries: Vec<SkillFile> = Vec::new();
 
-    for entry in entries.flatten() {
-        let path = entry.path();
-        let file_name = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+        if file.is_dir() {
+            continue;
+        }
+        let Some(entry_path) = file.enclosed_name() else {
+            report.warnings.push(format!("Skipped unsafe entry: {}", file.name()));
+            continue;
+        };
+        let entry_path = entry_path.to_string_lossy().replace('\\', "/");
+        let parts: Vec<&str> = entry_path.splitn(2, '/').collect();
+
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).map_err(|e| e.to_string())?;
+
+        match parts.as_slice() {
+            ["skills", relative] if options.skills => {
+                skill_entries.push(SkillFile {
+                    path: relative.to_string(),
+                    content,
+                    mode: file.unix_mode(),
+                });
+            }
+            ["mcp", file_name] if options.mcp => {
+                let agent = file_name
+                    .strip_suffix(".json")
+                    .and_then(AgentType::from_id)
+                    .filter(|a| agent_has_mcp_support(*a));
+                let Some(agent) = agent else {
+                    report.warnings.push(format!("Skipped MCP config: {}", file_name));
+                    continue;
+                };
+                report.mcp_servers_imported +=
+                    import_bundle_mcp_servers(agent, &content, options.overwrite)?;
+            }
+            ["app", file_name] if options.app_files => {
+                let target = get_app_data_dir()?.join(file_name);
+                if target.exists() && !options.overwrite {
+                    continue;
+                }
+                if *file_name == SETTINGS_FILE {
+                    let settings: Settings = serde_json::from_slice(&content)
+                        .map_err(|e| format!("Invalid settings in bundle: {}", e))?;
+                    let settings = untrusted_bundle_settings(settings, load_settings().unwrap_or_default());
+                    if settings.post_install_hook.is_some() || !settings.agent_post_install_hooks.is_empty() {
+                        report
+                            .warnings
+                            .push("Imported post-install hooks stay off until approved in Settings".to_string());
+                    }
+                    save_settings(&settings)?;
+                } else {
+                    fs::create_dir_all(get_app_data_dir()?).map_err(|e| e.to_string())?;
+                    fs::write(&target, &content).map_err(|e| e.to_string())?;
+                }
+                report.app_files_imported += 1;
+            }
+            _ => {}
+        }
+    }
 
-        // Skip hidden files except .metadata.json
-        if file_name.starts_with('.') && file_name != ".metadata.json" {
+    for entry in manifest.skills.into_iter().filter(|_| options.skills) {
+        if entry.agent == AgentType::All {
+            report.warnings.push(format!("Skipped skill for unknown agent: {}", entry.bundle_dir()));
             continue;
         }
+        let prefix = format!("{}/", entry.bundle_dir());
+        let files: Vec<SkillFile> = skill_entries
+            .iter()
+            .filter_map(|f| {
+                Some(SkillFile {
+                    path: f.path.strip_prefix(&prefix)?.to_string(),
+                    content: f.content.clone(),
+                    mode: f.mode,
+                })
+            })
+            .collect();
+
+        // A skill that can't be written is reported and the rest still import
+        match import_bundle_skill(app, &entry, files, options.overwrite) {
+            Ok(Some(warnings)) => {
+                report
+                    .warnings
+                    .extend(warnings.into_iter().map(|w| format!("{}: {}", entry.bundle_dir(), w)));
+                report.skills_imported.push(entry);
+            }
+            Ok(None) => report.skills_skipped.push(entry),
+            Err(e) => {
+                report.warnings.push(format!("{} not imported: {}", entry.bundle_dir(), e));
+                report.skills_skipped.push(entry);
+            }
+        }
+    }
 
-        let is_directory = path.is_dir();
-        let size = if is_directory {
-            None
-        } else {
-            fs::metadata(&path).ok().map(|m| m.len())
-        };
+    Ok(report)
+}
 
-        // Get relative path from skill root
-        let relative_path = path
-            .strip_prefix(&skills_dir.join(&name))
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|_| file_name.clone());
+/// Writes one bundled skill, returning its install warnings, or `None`
+/// when it already exists and `overwrite` is off
+fn import_bundle_skill(
+    app: &AppHandle,
+    entry: &BundleSkill,
+    mut files: Vec<SkillFile>,
+    overwrite: bool,
+) -> Result<Option<Vec<String>>, String> {
+    // Keep the original metadata (install time, source) instead of
+    // the fresh one write_skill generates
+    let metadata_file = files
+        .iter()
+        .position(|f| f.path == ".metadata.json")
+        .map(|i| files.remove(i));
+    let metadata = metadata_file
+        .as_ref()
+        .and_then(|f| serde_json::from_slice::<SkillMetadata>(&f.content).ok());
+
+    // write_skill names the folder after this, so check the same folder
+    let display_name = metadata.as_ref().map(|m| m.name.clone()).unwrap_or(entry.name.clone());
+    let skills_root = get_skills_root(entry.agent, entry.namespace.as_deref())?;
+    if skills_root.join(sanitize_name(&display_name)).exists() && !overwrite {
+        return Ok(None);
+    }
 
-        items.push(FileItem {
-            name: file_name,
-            path: relative_path,
-            is_directory,
-            size,
+    let source = metadata.and_then(|m| m.source);
+    let options = InstallOptions {
+        namespace: entry.namespace.clone(),
+        ..InstallOptions::default()
+    };
+    let result = write_skill(app, entry.agent, &display_name, files, source, &options)?;
+
+    if let Some(metadata_file) = metadata_file {
+        fs::write(Path::new(&result.path).join(".metadata.json"), metadata_file.content)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(Some(result.warnings))
+}
+
+/// Merges a bundle's `mcp/<agent>.json` into the agent's config, returning
+/// how many servers were written
+fn import_bundle_mcp_servers(agent: AgentType, content: &[u8], overwrite: bool) -> Result<usize, String> {
+    let bundled: serde_json::Value =
+        serde_json::from_slice(content).map_err(|e| format!("Invalid MCP config in bundle: {}", e))?;
+    let Some(servers) = bundled.get("mcpServers").and_then(|s| s.as_object()) else {
+        return Ok(0);
+    };
+
+    let config_path = get_mcp_config_path(agent)?;
+    let mut root = read_mcp_config(&config_path)?;
+    let mcp_servers = mcp_servers_mut(&mut root, &config_path)?;
+
+    let mut imported = 0;
+    for (name, server) in servers {
+        if mcp_servers.contains_key(name) && !overwrite {
+            continue;
+        }
+        mcp_servers.insert(name.clone(), server.clone());
+        imported += 1;
+    }
+
+    if imported > 0 {
+        write_mcp_config(&config_path, &root)?;
+    }
+    Ok(imported)
+}
+
+// ============================================================================
+// Diagnostics Commands
+// ============================================================================
+
+#[tauri::command]
+async fn diagnose() -> Result<Diagnostics, String> {
+    let home_dir = app_home().ok().map(|h| h.to_string_lossy().to_string());
+
+    let mut agents = Vec::new();
+    for agent in get_all_individual_agents() {
+        let Ok(skills_dir) = get_skills_dir(agent) else {
+            continue;
+        };
+        // An agent counts as detected when its config folder exists
+        let detected = skills_dir.parent().map(|p| p.exists()).unwrap_or(false);
+
+        agents.push(AgentDiagnostics {
+            agent,
+            skills_path: skills_dir.to_string_lossy().to_string(),
+            detected,
+            skills_dir_writable: is_dir_writable(&skills_dir),
         });
     }
 
-    // Sort: directories first, then by name
-    items.sort_by(|a, b| {
-        match (a.is_directory, b.is_directory) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.cmp(&b.name),
+    let client = diagnostics_client()?;
+    let registry_reachable = registry_reachable(&client).await;
+
+    let github_rate_limit_remaining = match client.get("https://api.github.com/rate_limit").send().await {
+        Ok(response) => response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|data| data.pointer("/resources/core/remaining").and_then(|v| v.as_u64())),
+        Err(_) => None,
+    };
+
+    Ok(Diagnostics {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        home_dir,
+        agents,
+        registry_reachable,
+        github_rate_limit_remaining,
+    })
+}
+
+/// Checks that the data dir can be created and written, that the default
+/// agent's skills dir is writable and that the registry answers, so
+/// problems show up before the first install. Emits `preflight-complete`.
+#[tauri::command]
+async fn preflight(app: AppHandle) -> Result<PreflightReport, String> {
+    let data_dir = get_app_data_dir()?;
+    let data_dir_error = match fs::create_dir_all(&data_dir) {
+        Ok(()) if is_dir_writable(&data_dir) => None,
+        Ok(()) => Some(format!("{} is not writable", data_dir.display())),
+        Err(e) => Some(format!("Cannot create {}: {}", data_dir.display(), e)),
+    };
+
+    let skills_dir = get_skills_dir(PREFLIGHT_AGENT)?;
+    let skills_dir_writable = is_dir_writable(&skills_dir);
+    let network_reachable = registry_reachable(&diagnostics_client()?).await;
+
+    let data_dir_writable = data_dir_error.is_none();
+    let report = PreflightReport {
+        data_dir: data_dir.to_string_lossy().to_string(),
+        data_dir_writable,
+        data_dir_error,
+        skills_dir: skills_dir.to_string_lossy().to_string(),
+        skills_dir_writable,
+        network_reachable,
+        ok: data_dir_writable && skills_dir_writable,
+    };
+    let _ = app.emit("preflight-complete", &report);
+    Ok(report)
+}
+
+/// `http_client` with a short timeout, so an offline check fails fast
+fn diagnostics_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent("Oh-My-Skills/0.1")
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+async fn registry_reachable(client: &reqwest::Client) -> bool {
+    client
+        .get("https://skills.sh/api/search?q=skill&limit=1")
+        .send()
+        .await
+        .map(|r| r.status().is_success())
+        .unwrap_or(false)
+}
+
+// ============================================================================
+// Release Notes Commands
+// ============================================================================
+
+const APP_RELEASES_API: &str = "https://api.github.com/repos/stevensu1977/oh-my-skills/releases";
+
+/// An update the frontend hasn't shown yet, from `take_whats_new`
+#[derive(Debug, Clone, Serialize)]
+pub struct WhatsNewEvent {
+    pub previous_version: String,
+    pub version: String,
+}
+
+/// The release body for `version` as markdown with raw HTML removed.
+/// Notes are cached after the first fetch since releases don't change.
+#[tauri::command]
+async fn get_release_notes(version: String) -> Result<String, String> {
+    let version = version.trim().trim_start_matches('v').to_string();
+    if version.is_empty() || !version.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')) {
+        return Err(format!("Invalid version: {}", version));
+    }
+
+    let cache_path = get_release_notes_dir()?.join(format!("{}.md", version));
+    if let Ok(notes) = fs::read_to_string(&cache_path) {
+        return Ok(notes);
+    }
+
+    let client = http_client()?;
+    let mut body = None;
+    for tag in [format!("v{}", version), version.clone()] {
+        let api_url = format!("{}/tags/{}", APP_RELEASES_API, tag);
+        let response = authorized_get(&client, &api_url, None)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            continue;
+        }
+        if !response.status().is_success() {
+            return Err(format!("GitHub returned {} for release {}", response.status(), tag));
         }
-    });
 
-    Ok(items)
+        let release: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+        body = Some(release.get("body").and_then(|v| v.as_str()).unwrap_or("").to_string());
+        break;
+    }
+
+    let notes = sanitize_release_notes(&body.ok_or_else(|| format!("No release found for version {}", version))?);
+    if let Some(parent) = cache_path.parent() {
+        if fs::create_dir_all(parent).is_ok() {
+            let _ = fs::write(&cache_path, &notes);
+        }
+    }
+
+    Ok(notes)
+}
+
+/// Drops HTML comments and tags, keeping `<https://...>` autolinks
+fn sanitize_release_notes(body: &str) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut rest = body;
+
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+
+        if let Some(comment) = tail.strip_prefix("<!--") {
+            rest = comment.find("-->").map(|end| &comment[end + 3..]).unwrap_or("");
+            continue;
+        }
+
+        let is_tag = tail[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/');
+        let is_autolink = tail[1..].starts_with("http://") || tail[1..].starts_with("https://");
+        match tail.find('>') {
+            Some(end) if is_tag && !is_autolink => rest = &tail[end + 1..],
+            _ => {
+                out.push('<');
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+
+    out.replace("\r\n", "\n").trim().to_string()
+}
+
+/// On the first launch after the app version changes, saves the version
+/// updated from for `take_whats_new`. The webview isn't listening yet at
+/// startup, so the frontend asks once it has mounted.
+fn check_whats_new() -> Result<(), String> {
+    let version = env!("CARGO_PKG_VERSION").to_string();
+    let mut settings = load_settings()?;
+    let previous = settings.last_seen_version.replace(version.clone());
+    if previous.as_deref() == Some(version.as_str()) {
+        return Ok(());
+    }
+
+    // A fresh install has nothing to compare against
+    if let Some(previous_version) = previous {
+        tracing::info!(%previous_version, %version, "app updated");
+        settings.whats_new_from = Some(previous_version);
+    }
+    save_settings(&settings)
 }
 
+/// The update since the what's-new notice was last shown, if any; cleared
+/// once taken
 #[tauri::command]
-fn read_skill_file(agent: AgentType, name: String, file_path: String) -> Result<String, String> {
-    let skills_dir = get_skills_dir(agent)?;
-    let full_path = skills_dir.join(&name).join(&file_path);
+fn take_whats_new() -> Result<Option<WhatsNewEvent>, String> {
+    let mut settings = load_settings()?;
+    let Some(previous_version) = settings.whats_new_from.take() else {
+        return Ok(None);
+    };
+    save_settings(&settings)?;
+    Ok(Some(WhatsNewEvent { previous_version, version: env!("CARGO_PKG_VERSION").to_string() }))
+}
 
-    // Security: ensure path doesn't escape skill directory
-    let canonical = full_path.canonicalize().map_err(|e| e.to_string())?;
-    let skill_dir = skills_dir.join(&name).canonicalize().map_err(|e| e.to_string())?;
+// ============================================================================
+// Remote Commands
+// ============================================================================
 
-    if !canonical.starts_with(&skill_dir) {
-        return Err("Access denied: path outside skill directory".to_string());
+#[tauri::command]
+fn get_remote_hosts() -> Result<Vec<RemoteHost>, String> {
+    Ok(load_settings()?.remote_hosts)
+}
+
+#[tauri::command]
+fn set_remote_hosts(app: AppHandle, hosts: Vec<RemoteHost>) -> Result<(), String> {
+    let result = save_remote_hosts(hosts);
+    record_activity(&app, ActivityEvent::new(ActivityKind::UpdateSettings, None, "remote hosts", &result));
+    result
+}
+
+fn save_remote_hosts(hosts: Vec<RemoteHost>) -> Result<(), String> {
+    for host in &hosts {
+        let destination = host.destination.trim();
+        if destination.is_empty() || destination.starts_with('-') || destination.contains(char::is_whitespace) {
+            return Err(format!("Invalid SSH destination for {}: {}", host.name, host.destination));
+        }
     }
+    let mut settings = load_settings()?;
+    settings.remote_hosts = hosts;
+    save_settings(&settings)
+}
+
+/// Skills in an agent's default skills dir on a remote host. Read-only;
+/// token counts come from SKILL.md sizes as they do locally.
+#[tauri::command]
+#[tracing::instrument(fields(agent = agent.id()), skip(agent), err)]
+async fn list_remote_skills(host: String, agent: AgentType) -> Result<Vec<SkillInfo>, String> {
+    let host = find_remote_host(&host)?;
+    let skills_dir = host.skills_dir(agent)?;
+    let transport = SshTransport { host: &host };
+    let script = format!(
+        "cd \"$HOME/$1\" 2>/dev/null || exit 0; for d in */; do d=${{d%/}}; \
+         [ -f \"$d/{manifest}\" ] && printf '%s\\t%s\\n' \"$d\" \"$(wc -c < \"$d/{manifest}\")\"; done; true",
+        manifest = SKILL_MANIFEST
+    );
 
-    fs::read_to_string(&canonical).map_err(|e| e.to_string())
+    let output = transport.run(&script, &[&skills_dir]).await?;
+    let mut skills: Vec<SkillInfo> = output
+        .lines()
+        .filter_map(|line| {
+            let (name, size) = line.split_once('\t')?;
+            Some(SkillInfo {
+                name: name.to_string(),
+                path: format!("{}:~/{}/{}", host.destination, skills_dir, name),
+                token_count: size.trim().parse().ok().map(estimate_tokens),
+                namespace: None,
+                description: None,
+                builtin: false,
+                last_used_at: None,
+                favorite: false,
+            })
+        })
+        .collect();
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(skills)
 }
 
+/// A remote skill's SKILL.md
 #[tauri::command]
-async fn install_skill_from_url(agent: AgentType, url: String) -> Result<String, String> {
-    // Handle "All" agent - install to all agents
-    if agent == AgentType::All {
-        let url_clone = url.clone();
-        let mut success_count = 0;
-        let mut skill_name = String::new();
-
-        for individual_agent in get_all_individual_agents() {
-            if let Ok(result) = Box::pin(install_skill_from_url(individual_agent, url_clone.clone())).await {
-                success_count += 1;
-                if skill_name.is_empty() {
-                    skill_name = result.replace("Installed: ", "");
-                }
-            }
+#[tracing::instrument(fields(agent = agent.id()), skip(agent), err)]
+async fn get_remote_skill_content(host: String, agent: AgentType, name: String) -> Result<String, String> {
+    if name.is_empty() || sanitize_name(&name) != name {
+        return Err(format!("Invalid skill name: {}", name));
+    }
+    let host = find_remote_host(&host)?;
+    let skills_dir = host.skills_dir(agent)?;
+    let script = format!("cat -- \"$HOME/$1/$2/{}\"", SKILL_MANIFEST);
+    SshTransport { host: &host }.run(&script, &[&skills_dir, &name]).await
+}
+
+fn find_remote_host(name: &str) -> Result<RemoteHost, String> {
+    load_settings()?
+        .remote_hosts
+        .into_iter()
+        .find(|h| h.name == name)
+        .ok_or_else(|| format!("No remote host named {}", name))
+}
+
+impl RemoteHost {
+    /// The agent's skills dir on the host, relative to the remote home.
+    /// Local overrides such as `CLAUDE_CONFIG_DIR` don't apply there.
+    fn skills_dir(&self, agent: AgentType) -> Result<String, String> {
+        let home = Path::new("/home");
+        let dir = default_skills_dir(agent, home, &home.join(".config"))?;
+        let relative = dir.strip_prefix(home).map_err(|_| format!("{} has no skills dir under home", agent.id()))?;
+        Ok(relative.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+/// How the remote commands reach a host: each one is a `sh` script whose
+/// stdout is the result. `args` reach the script as `$1`, `$2`, ... and
+/// are never parsed by a shell.
+trait RemoteTransport {
+    async fn run(&self, script: &str, args: &[&str]) -> Result<String, String>;
+}
+
+/// An `ssh2` session. The host key has to be in `~/.ssh/known_hosts`
+/// already, and login goes through the SSH agent, then the default key
+/// files; there's no password prompt to fall back to.
+struct SshTransport<'a> {
+    host: &'a RemoteHost,
+}
+
+impl RemoteTransport for SshTransport<'_> {
+    /// The script runs under `sh -c` since the login shell may not be POSIX
+    async fn run(&self, script: &str, args: &[&str]) -> Result<String, String> {
+        let mut command = format!("sh -c {} sh", posix_quote(script));
+        for arg in args {
+            command.push(' ');
+            command.push_str(&posix_quote(arg));
+        }
+        let host = self.host.clone();
+        tokio::task::spawn_blocking(move || ssh_exec(&host, &command).map_err(|e| format!("{}: {}", host.name, e)))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+}
+
+/// `value` in single quotes for the remote `sh`, which is POSIX even when
+/// the local `shell_quote` is quoting for Windows
+fn posix_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Connects, checks the host key, logs in and runs `command`, returning
+/// its stdout. A non-zero exit fails with its stderr.
+fn ssh_exec(host: &RemoteHost, command: &str) -> Result<String, String> {
+    use std::net::ToSocketAddrs;
+
+    let destination = host.destination.trim();
+    let (user, hostname) = match destination.split_once('@') {
+        Some((user, hostname)) => (user.to_string(), hostname),
+        None => (
+            std::env::var("USER")
+                .or_else(|_| std::env::var("USERNAME"))
+                .map_err(|_| "No user in the destination and no local user name".to_string())?,
+            destination,
+        ),
+    };
+    let port = host.port.unwrap_or(22);
+
+    let address = (hostname, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("Cannot resolve {}: {}", hostname, e))?
+        .next()
+        .ok_or_else(|| format!("Cannot resolve {}", hostname))?;
+    let tcp = std::net::TcpStream::connect_timeout(&address, SSH_TIMEOUT).map_err(|e| e.to_string())?;
+    let mut session = ssh2::Session::new().map_err(|e| e.to_string())?;
+    session.set_tcp_stream(tcp);
+    session.set_timeout(SSH_TIMEOUT.as_millis() as u32);
+    session.handshake().map_err(|e| e.to_string())?;
+
+    verify_ssh_host_key(&session, hostname, port)?;
+    ssh_authenticate(&session, &user)?;
+
+    let mut channel = session.channel_session().map_err(|e| e.to_string())?;
+    channel.exec(command).map_err(|e| e.to_string())?;
+    let mut stdout = Vec::new();
+    channel.read_to_end(&mut stdout).map_err(|e| e.to_string())?;
+    let mut stderr = String::new();
+    let _ = channel.stderr().read_to_string(&mut stderr);
+    channel.wait_close().map_err(|e| e.to_string())?;
+
+    if channel.exit_status().map_err(|e| e.to_string())? != 0 {
+        return Err(stderr.trim().to_string());
+    }
+    Ok(decode_text(&stdout, &host.name))
+}
+
+/// Refuses hosts whose key isn't recorded in `~/.ssh/known_hosts`, or
+/// doesn't match what's recorded
+fn verify_ssh_host_key(session: &ssh2::Session, hostname: &str, port: u16) -> Result<(), String> {
+    let (key, _) = session.host_key().ok_or("The host sent no key")?;
+    let known_hosts_file = app_home()?.join(".ssh").join("known_hosts");
+    let mut known_hosts = session.known_hosts().map_err(|e| e.to_string())?;
+    known_hosts
+        .read_file(&known_hosts_file, ssh2::KnownHostFileKind::OpenSSH)
+        .map_err(|e| format!("Cannot read {}: {}", known_hosts_file.display(), e))?;
+
+    match known_hosts.check_port(hostname, port, key) {
+        ssh2::CheckResult::Match => Ok(()),
+        ssh2::CheckResult::Mismatch => Err("Host key doesn't match known_hosts; refusing to connect".to_string()),
+        ssh2::CheckResult::NotFound => {
+            Err("Host isn't in known_hosts; connect once with ssh to add it".to_string())
+        }
+        ssh2::CheckResult::Failure => Err("Cannot check the host key".to_string()),
+    }
+}
+
+/// The SSH agent first, then each of `SSH_DEFAULT_KEYS` that exists
+fn ssh_authenticate(session: &ssh2::Session, user: &str) -> Result<(), String> {
+    if session.userauth_agent(user).is_ok() && session.authenticated() {
+        return Ok(());
+    }
+    let ssh_dir = app_home()?.join(".ssh");
+    for key in SSH_DEFAULT_KEYS {
+        let path = ssh_dir.join(key);
+        if path.is_file() && session.userauth_pubkey_file(user, None, &path, None).is_ok() {
+            return Ok(());
+        }
+    }
+    Err(format!("No key from the SSH agent or {} was accepted for {}", ssh_dir.display(), user))
+}
+
+// ============================================================================
+// Tray Commands
+// ============================================================================
+
+#[tauri::command]
+fn get_tray_icon_theme() -> Result<TrayIconTheme, String> {
+    Ok(load_settings()?.tray_icon_theme)
+}
+
+#[tauri::command]
+fn set_tray_icon_theme(app: AppHandle, theme: TrayIconTheme) -> Result<(), String> {
+    let result = load_settings().and_then(|mut settings| {
+        settings.tray_icon_theme = theme;
+        save_settings(&settings)
+    });
+    if result.is_ok() {
+        apply_tray_icon_theme(&app, None);
+    }
+    record_activity(
+        &app,
+        ActivityEvent::new(ActivityKind::UpdateSettings, None, "tray icon theme", &result)
+            .with_detail(format!("{:?}", theme).to_lowercase()),
+    );
+    result
+}
+
+/// Sets the tray icon for the chosen theme. `system_theme` is the OS
+/// appearance when the caller knows it (a theme-change event); otherwise
+/// it's read from the main window.
+fn apply_tray_icon_theme(app: &AppHandle, system_theme: Option<tauri::Theme>) {
+    let Some(tray) = app.try_state::<tauri::tray::TrayIcon>() else {
+        return;
+    };
+    let theme = load_settings().map(|s| s.tray_icon_theme).unwrap_or_default();
+    let system_theme = system_theme.or_else(|| app.get_webview_window("main").and_then(|w| w.theme().ok()));
+
+    let (color, template) = match theme {
+        // macOS tints template images to the menu bar itself
+        TrayIconTheme::System if cfg!(target_os = "macos") => (None, true),
+        TrayIconTheme::System if system_theme == Some(tauri::Theme::Dark) => (Some([0xFF; 3]), false),
+        TrayIconTheme::System | TrayIconTheme::Light => (Some([0x00; 3]), false),
+        TrayIconTheme::Dark => (Some([0xFF; 3]), false),
+        TrayIconTheme::Colored => (Some(TRAY_ICON_COLOR), false),
+    };
+
+    let base = tauri::include_image!("icons/tray-icon.png");
+    let icon = match color {
+        Some(rgb) => tint_icon(&base, rgb),
+        None => base,
+    };
+    if let Err(e) = tray.set_icon(Some(icon)).and_then(|_| tray.set_icon_as_template(template)) {
+        tracing::warn!(error = %e, "failed to update the tray icon");
+    }
+}
+
+/// `icon` in a single color, keeping its alpha
+fn tint_icon(icon: &tauri::image::Image<'_>, rgb: [u8; 3]) -> tauri::image::Image<'static> {
+    let rgba = icon
+        .rgba()
+        .chunks_exact(4)
+        .flat_map(|px| [rgb[0], rgb[1], rgb[2], px[3]])
+        .collect();
+    tauri::image::Image::new_owned(rgba, icon.width(), icon.height())
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Parsed `oci://registry/repository[:tag|@digest]`; the tag defaults to `latest`
+struct OciReference {
+    registry: String,
+    repository: String,
+    reference: String,
+}
+
+fn parse_oci_reference(url: &str) -> Result<OciReference, String> {
+    let rest = url.strip_prefix("oci://").ok_or("Not an oci:// reference")?;
+    let (registry, path) = rest
+        .split_once('/')
+        .ok_or("OCI reference needs a registry and a repository")?;
+
+    let (repository, reference) = match path.split_once('@') {
+        Some((repository, digest)) => (repository, digest),
+        // Only a colon in the last path segment starts a tag
+        None => match path.rsplit_once(':') {
+            Some((repository, tag)) if !tag.contains('/') => (repository, tag),
+            _ => (path, "latest"),
+        },
+    };
+
+    if registry.is_empty() || repository.is_empty() || reference.is_empty() {
+        return Err(format!("Invalid OCI reference: {}", url));
+    }
+    Ok(OciReference {
+        registry: registry.to_string(),
+        repository: repository.to_string(),
+        reference: reference.to_string(),
+    })
+}
+
+/// Pulls the skill layer of an OCI artifact and returns it as ZIP bytes, so
+/// it can go through the normal archive path. Tar layers are repacked.
+async fn fetch_oci_artifact(url: &str) -> Result<Vec<u8>, String> {
+    let oci = parse_oci_reference(url)?;
+    let client = reqwest::Client::new();
+    let base = format!("https://{}/v2/{}", oci.registry, oci.repository);
+    let mut token = None;
+
+    let manifest_url = format!("{}/manifests/{}", base, oci.reference);
+    let mut manifest: serde_json::Value = oci_get(&client, &manifest_url, OCI_MANIFEST_ACCEPT, &mut token)
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid OCI manifest: {}", e))?;
+
+    // An index lists per-platform manifests; skills have no platform, so
+    // the first one is as good as any
+    if let Some(digest) = manifest
+        .get("manifests")
+        .and_then(|m| m.as_array())
+        .and_then(|m| m.first())
+        .and_then(|m| m.get("digest"))
+        .and_then(|d| d.as_str())
+    {
+        let manifest_url = format!("{}/manifests/{}", base, digest);
+        manifest = oci_get(&client, &manifest_url, OCI_MANIFEST_ACCEPT, &mut token)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid OCI manifest: {}", e))?;
+    }
+
+    let layers = manifest
+        .get("layers")
+        .and_then(|l| l.as_array())
+        .ok_or("OCI manifest has no layers")?;
+    let media_type = |layer: &serde_json::Value| {
+        layer.get("mediaType").and_then(|m| m.as_str()).unwrap_or("").to_lowercase()
+    };
+    let layer = layers
+        .iter()
+        .find(|l| media_type(l).contains("zip"))
+        .or_else(|| layers.iter().find(|l| media_type(l).contains("tar")))
+        .or(if layers.len() == 1 { layers.first() } else { None })
+        .ok_or("No zip or tar layer in the OCI artifact")?;
+
+    if layer.get("size").and_then(|s| s.as_u64()).unwrap_or(0) > MAX_ZIP_UNCOMPRESSED_SIZE {
+        return Err("OCI layer is too large".to_string());
+    }
+    let digest = layer
+        .get("digest")
+        .and_then(|d| d.as_str())
+        .ok_or("OCI layer has no digest")?;
+
+    let blob_url = format!("{}/blobs/{}", base, digest);
+    let data = oci_get(&client, &blob_url, "*/*", &mut token)
+        .await?
+        .bytes()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(expected) = digest.strip_prefix("sha256:") {
+        use sha2::{Digest, Sha256};
+        if format!("{:x}", Sha256::digest(&data)) != expected.to_lowercase() {
+            return Err("OCI layer digest mismatch".to_string());
         }
+    }
+
+    let kind = media_type(layer);
+    if kind.contains("zip") || data.starts_with(b"PK") {
+        Ok(data.to_vec())
+    } else {
+        tar_to_zip(&data, kind.contains("gzip") || data.starts_with(&[0x1f, 0x8b]))
+    }
+}
+
+/// GET against a registry, answering a Bearer challenge once. Credentials
+/// saved for the registry host are sent until a token replaces them.
+async fn oci_get(
+    client: &reqwest::Client,
+    url: &str,
+    accept: &str,
+    token: &mut Option<String>,
+) -> Result<reqwest::Response, String> {
+    let send = |token: Option<&str>| {
+        let request = match token {
+            Some(token) => client.get(url).bearer_auth(token),
+            None => authorized_get(client, url, None),
+        };
+        request.header(reqwest::header::ACCEPT, accept).send()
+    };
 
-        return Ok(format!("Installed {} to {} agents", skill_name, success_count));
+    let mut response = send(token.as_deref()).await.map_err(|e| e.to_string())?;
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED && token.is_none() {
+        let challenge = response
+            .headers()
+            .get(reqwest::header::WWW_AUTHENTICATE)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        if let Some(challenge) = challenge {
+            *token = Some(fetch_oci_token(client, &challenge).await?);
+            response = send(token.as_deref()).await.map_err(|e| e.to_string())?;
+        }
     }
 
-    let url = url.trim();
+    check_response(response).await
+}
 
-    // Check if it's a GitHub directory URL
-    if url.contains("github.com") && url.contains("/tree/") {
-        return install_from_github_dir(agent, url).await;
+/// Trades a `WWW-Authenticate: Bearer realm=...` challenge for a token.
+/// Credentials saved for the realm's host are used, else it's anonymous.
+async fn fetch_oci_token(client: &reqwest::Client, challenge: &str) -> Result<String, String> {
+    let params = challenge
+        .strip_prefix("Bearer ")
+        .ok_or("Registry requires an unsupported auth scheme")?;
+
+    let mut realm = None;
+    let mut query = Vec::new();
+    for part in params.split(',') {
+        let Some((key, value)) = part.trim().split_once('=') else {
+            continue;
+        };
+        let value = value.trim_matches('"');
+        match key {
+            "realm" => realm = Some(value),
+            "service" | "scope" => query.push((key, value)),
+            _ => {}
+        }
     }
 
-    // Direct file URL
-    let client = reqwest::Client::new();
-    let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+    let mut token_url =
+        reqwest::Url::parse(realm.ok_or("Auth challenge has no realm")?).map_err(|e| e.to_string())?;
+    token_url.query_pairs_mut().extend_pairs(query);
 
-    let content = response.text().await.map_err(|e| e.to_string())?;
-    let name = extract_skill_name(&content, url);
+    let body: serde_json::Value = authorized_get(client, token_url.as_str(), None)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| format!("Registry auth failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
 
-    let skills_dir = get_skills_dir(agent)?;
-    fs::create_dir_all(&skills_dir).map_err(|e| e.to_string())?;
+    body.get("token")
+        .or_else(|| body.get("access_token"))
+        .and_then(|t| t.as_str())
+        .map(String::from)
+        .ok_or_else(|| "Registry auth returned no token".to_string())
+}
 
-    let skill_dir = skills_dir.join(sanitize_name(&name));
-    fs::create_dir_all(&skill_dir).map_err(|e| e.to_string())?;
+/// Repacks a (possibly gzipped) tarball as a ZIP
+fn tar_to_zip(data: &[u8], gzipped: bool) -> Result<Vec<u8>, String> {
+    let reader: Box<dyn Read + '_> = if gzipped {
+        Box::new(flate2::read::GzDecoder::new(data))
+    } else {
+        Box::new(data)
+    };
+    let mut archive = tar::Archive::new(reader);
 
-    fs::write(skill_dir.join("SKILL.md"), &content).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let mut total: u64 = 0;
+    for entry in archive.entries().map_err(|e| format!("Invalid tar: {}", e))? {
+        let mut entry = entry.map_err(|e| format!("Invalid tar: {}", e))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
 
-    save_metadata(&skill_dir, &name, Some(url.to_string()))?;
+        let path = entry.path().map_err(|e| e.to_string())?.to_string_lossy().replace('\\', "/");
+        let path = path.trim_start_matches("./").to_string();
+        if path.is_empty() || path.split('/').any(|c| c == "..") {
+            continue;
+        }
 
-    Ok(format!("Installed: {}", name))
-}
+        total += entry.size();
+        if total > MAX_ZIP_UNCOMPRESSED_SIZE {
+            return Err("Archive is too large".to_string());
+        }
 
-#[tauri::command]
-fn install_skill_from_content(agent: AgentType, content: String, filename: String) -> Result<String, String> {
-    // Handle "All" agent - install to all agents
-    if agent == AgentType::All {
-        let mut success_count = 0;
-        let name = extract_skill_name(&content, &filename);
+        let mode = entry.header().mode().ok();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).map_err(|e| e.to_string())?;
+        add_zip_file(&mut zip, &path, &content, mode)?;
+    }
 
-        for individual_agent in get_all_individual_agents() {
-            if install_skill_from_content_for_agent(individual_agent, content.clone(), filename.clone()).is_ok() {
-                success_count += 1;
-            }
-        }
+    let cursor = zip.finish().map_err(|e| e.to_string())?;
+    Ok(cursor.into_inner())
+}
 
-        return Ok(format!("Installed {} to {} agents", name, success_count));
+/// GET with the headers saved for the URL's host, plus `extra` on top
+fn authorized_get(
+    client: &reqwest::Client,
+    url: &str,
+    extra: Option<&HashMap<String, String>>,
+) -> reqwest::RequestBuilder {
+    let mut headers = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+        .and_then(|host| load_settings().ok()?.credentials.0.remove(&host))
+        .unwrap_or_default();
+    if let Some(extra) = extra {
+        headers.extend(extra.clone());
     }
 
-    install_skill_from_content_for_agent(agent, content, filename)
+    let mut request = client.get(url);
+    for (name, value) in &headers {
+        request = request.header(name, value);
+    }
+    request
 }
 
-fn install_skill_from_content_for_agent(agent: AgentType, content: String, filename: String) -> Result<String, String> {
-    let name = extract_skill_name(&content, &filename);
+/// `Retry-After` as either delay-seconds or an HTTP date
+fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<std::time::Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
 
-    let skills_dir = get_skills_dir(agent)?;
-    fs::create_dir_all(&skills_dir).map_err(|e| e.to_string())?;
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(std::time::Duration::from_secs(secs));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().ok()
+}
 
-    let skill_dir = skills_dir.join(sanitize_name(&name));
-    fs::create_dir_all(&skill_dir).map_err(|e| e.to_string())?;
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent("Oh-My-Skills/0.1")
+        .build()
+        .map_err(|e| e.to_string())
+}
 
-    fs::write(skill_dir.join("SKILL.md"), &content).map_err(|e| e.to_string())?;
+/// Opens the file for appending, which needs write permission but changes
+/// nothing
+fn is_file_writable(path: &Path) -> bool {
+    fs::OpenOptions::new().append(true).open(path).is_ok()
+}
 
-    save_metadata(&skill_dir, &name, None)?;
+/// Checks writability by creating and removing a probe file. A missing
+/// directory is judged by its nearest existing ancestor.
+fn is_dir_writable(dir: &Path) -> bool {
+    let mut target = dir;
+    while !target.exists() {
+        match target.parent() {
+            Some(parent) => target = parent,
+            None => return false,
+        }
+    }
 
-    Ok(format!("Installed: {}", name))
+    let probe = target.join(format!(".oh-my-skills-probe-{}", std::process::id()));
+    match fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
 }
 
-#[tauri::command]
-fn install_skill_from_zip(agent: AgentType, zip_base64: String, source: String) -> Result<String, String> {
-    // Handle "All" agent - install to all agents
-    if agent == AgentType::All {
-        let mut success_count = 0;
-        let mut skill_name = String::new();
-
-        for individual_agent in get_all_individual_agents() {
-            if let Ok(result) = install_skill_from_zip_for_agent(individual_agent, zip_base64.clone(), source.clone()) {
-                success_count += 1;
-                if skill_name.is_empty() {
-                    skill_name = result.replace("Installed: ", "");
-                }
-            }
+/// Fails early when the volume holding `dir` (or its nearest existing
+/// ancestor) can't fit `required` bytes plus a little headroom
+fn ensure_disk_space(dir: &Path, required: u64) -> Result<(), String> {
+    let mut target = dir;
+    while !target.exists() {
+        match target.parent() {
+            Some(parent) => target = parent,
+            None => return Ok(()),
         }
+    }
+
+    // If the volume can't be queried, let the write itself report errors
+    let Ok(available) = fs2::available_space(target) else {
+        return Ok(());
+    };
+    if required + DISK_SPACE_HEADROOM > available {
+        return Err(format!(
+            "Insufficient disk space: need {} KB, {} KB available on {}",
+            (required + DISK_SPACE_HEADROOM).div_ceil(1024),
+            available / 1024,
+            target.display()
+        ));
+    }
+    Ok(())
+}
 
-        return Ok(format!("Installed {} to {} agents", skill_name, success_count));
+/// Renames a directory, falling back to copy + delete across filesystems
+fn move_dir(from: &Path, to: &Path) -> Result<(), String> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
     }
 
-    install_skill_from_zip_for_agent(agent, zip_base64, source)
+    copy_dir_all(from, to)?;
+    fs::remove_dir_all(from).map_err(|e| e.to_string())
 }
 
-fn install_skill_from_zip_for_agent(agent: AgentType, zip_base64: String, source: String) -> Result<String, String> {
-    let zip_data = STANDARD
-        .decode(&zip_base64)
-        .map_err(|e| format!("Invalid base64: {}", e))?;
+fn copy_dir_all(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| e.to_string())?;
 
-    let cursor = Cursor::new(&zip_data);
-    let mut archive =
-        zip::ZipArchive::new(cursor).map_err(|e| format!("Invalid ZIP: {}", e))?;
+    for entry in fs::read_dir(from).map_err(|e| e.to_string())?.flatten() {
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+        if path.is_dir() {
+            copy_dir_all(&path, &target)?;
+        } else {
+            fs::copy(&path, &target).map_err(|e| e.to_string())?;
+        }
+    }
 
-    // First pass: find SKILL.md and get prefix
-    let mut skill_content = None;
-    let mut skill_path_prefix = String::new();
+    Ok(())
+}
+
+/// Finds the first SKILL.md in an archive, returning its directory prefix
+/// (with trailing slash, empty at the root) and content
+fn find_zip_skill_md<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    entrypoint: Option<&str>,
+) -> Result<Option<(String, String)>, String> {
+    match zip_skill_md_names(archive, entrypoint)?.first() {
+        Some(file_name) => read_zip_skill_md(archive, file_name).map(Some),
+        None => Ok(None),
+    }
+}
 
+/// Every SKILL.md (or `entrypoint`) in the archive, in archive order
+fn zip_skill_md_names<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    entrypoint: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let mut names = Vec::new();
     for i in 0..archive.len() {
         let file = archive.by_index(i).map_err(|e| e.to_string())?;
         let file_name = file.name().to_string();
         drop(file); // Release borrow
 
-        if file_name.contains("__MACOSX") {
+        if is_junk_path(&file_name) {
             continue;
         }
 
-        if file_name.to_lowercase().ends_with("skill.md") {
-            // Re-open to read content
-            let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
-            let mut content = String::new();
-            file.read_to_string(&mut content)
-                .map_err(|e| e.to_string())?;
-
-            if let Some(idx) = file_name.rfind('/') {
-                skill_path_prefix = file_name[..=idx].to_string();
-            }
-
-            skill_content = Some(content);
-            break;
+        let base_name = file_name.rsplit('/').next().unwrap_or(&file_name);
+        let matches = match entrypoint {
+            Some(entrypoint) => base_name.eq_ignore_ascii_case(entrypoint),
+            None => is_skill_manifest_name(base_name),
+        };
+        if matches {
+            names.push(file_name);
         }
     }
+    Ok(names)
+}
 
-    let content = skill_content.ok_or("No SKILL.md found in ZIP")?;
-    let name = extract_skill_name(&content, &source);
-
-    let skills_dir = get_skills_dir(agent)?;
-    let skill_dir = skills_dir.join(sanitize_name(&name));
-    fs::create_dir_all(&skill_dir).map_err(|e| e.to_string())?;
-
-    // Second pass: extract files
-    let cursor2 = Cursor::new(&zip_data);
-    let mut archive2 =
-        zip::ZipArchive::new(cursor2).map_err(|e| format!("Invalid ZIP: {}", e))?;
-
-    for i in 0..archive2.len() {
-        let mut file = archive2.by_index(i).map_err(|e| e.to_string())?;
-        let file_name = file.name().to_string();
-
-        if file_name.contains("__MACOSX") || file.is_dir() {
+/// The SKILL.md files not inside another one's folder
+fn top_level_zip_skill_mds<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    entrypoint: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let prefix_of = |name: &str| name.rfind('/').map_or(String::new(), |idx| name[..=idx].to_string());
+    let mut names = zip_skill_md_names(archive, entrypoint)?;
+    names.sort_by_key(|name| prefix_of(name).len());
+
+    let mut top_level: Vec<String> = Vec::new();
+    for name in names {
+        let prefix = prefix_of(&name);
+        // Also drops a second manifest name in the same folder
+        if top_level.iter().any(|kept| prefix.starts_with(&prefix_of(kept))) {
             continue;
         }
+        top_level.push(name);
+    }
+    Ok(top_level)
+}
+
+/// Reads one SKILL.md out of an archive, merging any sidecar manifest beside
+/// it; returns its directory prefix and content
+fn read_zip_skill_md<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    file_name: &str,
+) -> Result<(String, String), String> {
+    let mut file = archive.by_name(file_name).map_err(|e| e.to_string())?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+    drop(file);
+    let mut content = decode_text(&bytes, file_name);
+
+    let prefix = match file_name.rfind('/') {
+        Some(idx) => file_name[..=idx].to_string(),
+        None => String::new(),
+    };
 
-        if !skill_path_prefix.is_empty() && !file_name.starts_with(&skill_path_prefix) {
+    for sidecar_name in SIDECAR_MANIFEST_NAMES {
+        let Ok(mut sidecar) = archive.by_name(&format!("{}{}", prefix, sidecar_name)) else {
             continue;
+        };
+        let mut sidecar_bytes = Vec::new();
+        if sidecar.read_to_end(&mut sidecar_bytes).is_ok() {
+            let sidecar_content = decode_text(&sidecar_bytes, sidecar_name);
+            content = apply_sidecar_manifest(&content, sidecar_name, &sidecar_content);
         }
+        break;
+    }
 
-        let relative_path = if skill_path_prefix.is_empty() {
-            file_name.clone()
-        } else {
-            file_name
-                .strip_prefix(&skill_path_prefix)
-                .unwrap_or(&file_name)
-                .to_string()
+    Ok((prefix, content))
+}
+
+/// SKILL.md content with a sidecar manifest's keys merged into its
+/// frontmatter, ahead of the existing ones so the frontmatter only fills
+/// gaps. Every extractor reads the first matching line, so the rest of the
+/// code keeps working on plain frontmatter.
+fn apply_sidecar_manifest(content: &str, sidecar_name: &str, sidecar: &str) -> String {
+    let lines = if sidecar_name.ends_with(".toml") {
+        let Ok(table) = sidecar.parse::<toml::Table>() else {
+            return content.to_string();
         };
+        table
+            .iter()
+            .filter_map(|(key, value)| Some(format!("{}: {}", key, value.as_str()?)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        sidecar.trim().to_string()
+    };
 
-        if relative_path.is_empty() {
-            continue;
-        }
+    let split = content
+        .strip_prefix("---")
+        .and_then(|rest| rest.find("---").map(|end| (&rest[..end], &rest[end + 3..])));
+    match split {
+        Some((frontmatter, body)) => format!("---\n{}\n{}---{}", lines, frontmatter.trim_start_matches(['\r', '\n']), body),
+        None => format!("---\n{}\n---\n{}", lines, content),
+    }
+}
 
-        let out_path = skill_dir.join(&relative_path);
+/// Reads a text file that may not be UTF-8, see `decode_text`
+fn read_text(path: impl AsRef<Path>) -> std::io::Result<String> {
+    let path = path.as_ref();
+    fs::read(path).map(|bytes| decode_text(&bytes, &path.display().to_string()))
+}
 
-        if let Some(parent) = out_path.parent() {
-            fs::create_dir_all(parent).ok();
-        }
+/// Decodes skill or config text without failing on foreign encodings. A
+/// UTF-16 BOM is honoured; other non-UTF-8 input is read as Windows-1252,
+/// which covers the Latin-1 files editors most often leave behind.
+fn decode_text(bytes: &[u8], source: &str) -> String {
+    decode_text_detailed(bytes, source).0
+}
 
-        let mut file_content = Vec::new();
-        file.read_to_end(&mut file_content)
-            .map_err(|e| e.to_string())?;
-        fs::write(&out_path, file_content).map_err(|e| e.to_string())?;
+/// `decode_text`, also returning the encoding used and whether any bytes
+/// had to be replaced
+fn decode_text_detailed(bytes: &[u8], source: &str) -> (String, &'static encoding_rs::Encoding, bool) {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text).to_string();
+        return (text, encoding_rs::UTF_8, false);
     }
 
-    save_metadata(&skill_dir, &name, Some(source))?;
+    let encoding = encoding_rs::Encoding::for_bom(bytes)
+        .map(|(encoding, _)| encoding)
+        .unwrap_or(encoding_rs::WINDOWS_1252);
+    let (text, _, lossy) = encoding.decode(bytes);
+    if lossy {
+        tracing::warn!("{} is not valid {}; unreadable bytes were replaced", source, encoding.name());
+    } else {
+        tracing::info!("{} is not UTF-8; read it as {}", source, encoding.name());
+    }
+    (text.into_owned(), encoding, lossy)
+}
 
-    Ok(format!("Installed: {}", name))
+/// A skill dir's SKILL.md with any sidecar manifest beside it applied
+fn read_skill_manifest(dir: &Path) -> Option<String> {
+    let content = read_text(find_skill_md(&dir.to_path_buf())?).ok()?;
+    Some(apply_dir_sidecar_manifest(dir, content))
 }
 
-#[tauri::command]
-fn delete_skill(agent: AgentType, name: String) -> Result<(), String> {
-    // Handle "All" agent - delete from all agents
-    if agent == AgentType::All {
-        for individual_agent in get_all_individual_agents() {
-            let _ = delete_skill_for_agent(individual_agent, name.clone());
-        }
-        return Ok(());
+/// `read_skill_manifest`, reading only files `skill_content_files` counts. Every token figure is taken from this.
+fn read_content_manifest(dir: &PathBuf) -> Option<String> {
+    // The root's files come first, before any subfolder is read
+    let content_files: std::collections::HashSet<String> = skill_content_files(dir)
+        .map(|(path, _)| path)
+        .take_while(|path| !path.contains('/'))
+        .collect();
+
+    let manifest = find_skill_md(dir)?;
+    let manifest_name = manifest.file_name()?.to_str()?;
+    if !content_files.contains(manifest_name) {
+        return None;
     }
+    let content = read_text(&manifest).ok()?;
+    Some(
+        SIDECAR_MANIFEST_NAMES
+            .iter()
+            .filter(|name| content_files.contains(**name))
+            .find_map(|name| Some((name, read_text(dir.join(name)).ok()?)))
+            .map(|(name, sidecar)| apply_sidecar_manifest(&content, name, &sidecar))
+            .unwrap_or(content),
+    )
+}
 
-    delete_skill_for_agent(agent, name)
+/// Tokens a skill costs, from what `read_content_manifest` returned
+fn manifest_tokens(manifest: &str) -> u64 {
+    estimate_tokens(manifest.len() as u64)
 }
 
-fn delete_skill_for_agent(agent: AgentType, name: String) -> Result<(), String> {
-    let skills_dir = get_skills_dir(agent)?;
-    let skill_dir = skills_dir.join(&name);
+fn apply_dir_sidecar_manifest(dir: &Path, content: String) -> String {
+    SIDECAR_MANIFEST_NAMES
+        .iter()
+        .find_map(|name| Some((name, read_text(dir.join(name)).ok()?)))
+        .map(|(name, sidecar)| apply_sidecar_manifest(&content, name, &sidecar))
+        .unwrap_or(content)
+}
 
-    if skill_dir.exists() {
-        fs::remove_dir_all(&skill_dir).map_err(|e| e.to_string())?;
+/// The top-level manifest among files about to be installed, with any
+/// sidecar manifest applied
+fn skill_files_manifest(files: &[SkillFile]) -> Option<String> {
+    let manifest = files.iter().find(|f| is_skill_manifest_name(&f.path))?;
+    let content = decode_text(&manifest.content, &manifest.path);
+    Some(
+        files
+            .iter()
+            .find(|f| SIDECAR_MANIFEST_NAMES.iter().any(|n| n.eq_ignore_ascii_case(&f.path)))
+            .map(|f| apply_sidecar_manifest(&content, &f.path, &decode_text(&f.content, &f.path)))
+            .unwrap_or(content),
+    )
+}
+
+/// Problems with a SKILL.md's frontmatter that agents may trip over
+fn frontmatter_warnings(content: &str) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if frontmatter_block(content).is_none() {
+        warnings.push("SKILL.md has no frontmatter block".to_string());
+        return warnings;
+    }
+    if extract_skill_name(content, "").is_empty() {
+        warnings.push("Frontmatter is missing `name`".to_string());
+    }
+    if extract_skill_description(content).is_none() {
+        warnings.push("Frontmatter is missing `description`".to_string());
     }
 
-    Ok(())
+    warnings
 }
 
-#[tauri::command]
-fn open_skill_folder(agent: AgentType, name: String) -> Result<(), String> {
-    if agent == AgentType::All {
-        return Err("Cannot open folder for All agents".to_string());
+/// Writes a skill's files into the agent's skills dir, replacing any previous copy
+fn write_skill(
+    app: &AppHandle,
+    agent: AgentType,
+    name: &str,
+    files: Vec<SkillFile>,
+    source: Option<String>,
+    options: &InstallOptions,
+) -> Result<InstallResult, String> {
+    let skills_dir = get_skills_root(agent, options.namespace.as_deref())?;
+    fs::create_dir_all(&skills_dir).map_err(|e| e.to_string())?;
+
+    let mut warnings = Vec::new();
+
+    let mut files = files;
+    let has_frontmatter_name = skill_files_manifest(&files)
+        .map(|content| !extract_skill_name(&content, "").is_empty())
+        .unwrap_or(false);
+    if !has_frontmatter_name {
+        warnings.push(format!("SKILL.md has no name in frontmatter, using \"{}\"", name));
     }
 
-    let skills_dir = get_skills_dir(agent)?;
-    let skill_dir = skills_dir.join(&name);
+    if options.skill_md_only {
+        let before = files.len();
+        files = lightweight_files(files);
+        if files.len() < before {
+            warnings.push(format!("Lightweight install: left out {} bundled files", before - files.len()));
+        }
+    }
 
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("open")
-            .arg(&skill_dir)
-            .spawn()
-            .map_err(|e| e.to_string())?;
+    let skill_dir = skills_dir.join(sanitize_name(name));
+    let _lock = InstallLock::acquire(app.state::<InstallLocks>().inner(), &skill_dir)?;
+
+    let previous = read_metadata(&skill_dir);
+    let was_pinned = previous.as_ref().is_some_and(|m| m.pinned);
+    if was_pinned && !options.force {
+        let mut result = conflict_result(agent, name, &skill_dir);
+        result.status = InstallStatus::Skipped;
+        result.warnings.push("skipped: pinned".to_string());
+        return Ok(result);
     }
 
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("explorer")
-            .arg(&skill_dir)
-            .spawn()
-            .map_err(|e| e.to_string())?;
+    if skill_dir.exists() {
+        match options.on_conflict {
+            ConflictPolicy::Skip => {
+                let mut result = conflict_result(agent, name, &skill_dir);
+                result.status = InstallStatus::Skipped;
+                return Ok(result);
+            }
+            ConflictPolicy::Ask if is_conflicting_skill(&skill_dir, &files) => {
+                return Ok(conflict_result(agent, name, &skill_dir));
+            }
+            _ => {}
+        }
     }
 
-    #[cfg(target_os = "linux")]
-    {
-        std::process::Command::new("xdg-open")
-            .arg(&skill_dir)
-            .spawn()
-            .map_err(|e| e.to_string())?;
+    let required = files.iter().map(|f| f.content.len() as u64).sum();
+    ensure_disk_space(&skills_dir, required)?;
+
+    // Build the new version beside the old one and swap it in, so a failed
+    // install never leaves a half-written skill behind
+    let staging = skills_dir.join(format!(".{}.installing", sanitize_name(name)));
+    let _ = fs::remove_dir_all(&staging);
+    let written = match stage_skill(&staging, files, name, source) {
+        Ok(written) => written,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    let local_changes = has_local_changes(&skill_dir);
+    let displaced = match displace_existing_skill(app, agent, &skill_dir) {
+        Ok(displaced) => displaced,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+    if let Err(e) = fs::rename(&staging, &skill_dir) {
+        let _ = fs::remove_dir_all(&staging);
+        // Put the previous copy back rather than leave the skill missing
+        if let Some(trash_id) = &displaced {
+            if let Err(restore) = restore_trash_item(trash_id) {
+                tracing::error!(error = %restore, trash_id, "could not restore the replaced skill");
+            }
+        }
+        return Err(e.to_string());
+    }
+    let replaced = displaced.is_some();
+    if replaced {
+        warnings.push("Replaced an existing skill (previous version moved to trash)".to_string());
+    }
+    if local_changes {
+        warnings.push("The replaced copy had local edits; restore it from trash to keep them".to_string());
+    }
+    let favorite = previous.as_ref().is_some_and(|m| m.favorite);
+    let notes = previous.and_then(|m| m.notes);
+    if was_pinned || favorite || notes.is_some() || options.skill_md_only || options.entrypoint.is_some() {
+        // A forced update keeps the pin, and notes and favorites always
+        // carry over
+        update_metadata(&skill_dir, name, |m| {
+            m.pinned = was_pinned;
+            m.favorite = favorite;
+            m.notes = notes;
+            m.skill_md_only = options.skill_md_only;
+            m.entrypoint = options.entrypoint.clone();
+        })?;
     }
 
-    Ok(())
+    let status = if replaced {
+        InstallStatus::Replaced
+    } else {
+        InstallStatus::Installed
+    };
+    tracing::info!(agent = agent.id(), name, ?status, files = written.len(), "skill written");
+
+    Ok(InstallResult {
+        agent,
+        name: name.to_string(),
+        path: skill_dir.to_string_lossy().to_string(),
+        status,
+        files: written,
+        warnings,
+        hook: None,
+    })
 }
 
-#[tauri::command]
-async fn search_skills(query: String) -> Result<Vec<SearchSkill>, String> {
-    if query.trim().is_empty() {
-        return Ok(vec![]);
+fn stage_skill(
+    staging: &PathBuf,
+    files: Vec<SkillFile>,
+    name: &str,
+    source: Option<String>,
+) -> Result<Vec<String>, String> {
+    fs::create_dir_all(staging).map_err(|e| e.to_string())?;
+
+    let mut written = Vec::new();
+    for file in files {
+        if is_junk_path(&file.path) {
+            continue;
+        }
+        let out_path = staging.join(&file.path);
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).ok();
+        }
+        fs::write(&out_path, &file.content).map_err(|e| e.to_string())?;
+        apply_file_mode(&out_path, file.mode)?;
+        written.push(file.path);
     }
 
-    let client = reqwest::Client::builder()
-        .user_agent("Oh-My-Skills/0.1")
-        .build()
-        .map_err(|e| e.to_string())?;
+    save_metadata(staging, name, source)?;
+    Ok(written)
+}
 
-    let url = format!(
-        "https://skills.sh/api/search?q={}&limit=20",
-        urlencoding::encode(&query)
-    );
+/// Result for a target left untouched because a skill is already there
+fn conflict_result(agent: AgentType, name: &str, skill_dir: &Path) -> InstallResult {
+    InstallResult {
+        agent,
+        name: name.to_string(),
+        path: skill_dir.to_string_lossy().to_string(),
+        status: InstallStatus::Conflict,
+        files: vec![],
+        warnings: vec![format!("A different \"{}\" is already installed", name)],
+        hook: None,
+    }
+}
 
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to search: {}", e))?;
+/// The manifest, its sidecar and whatever the manifest links to directly;
+/// the rest of a bundle (scripts, large references) is dropped
+fn lightweight_files(files: Vec<SkillFile>) -> Vec<SkillFile> {
+    let Some(content) = skill_files_manifest(&files) else {
+        return files;
+    };
+    let linked: std::collections::HashSet<String> = extract_markdown_links(&content)
+        .iter()
+        .filter_map(|target| resolve_relative_link(Path::new(""), target))
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+        .collect();
 
-    if !response.status().is_success() {
-        return Ok(vec![]);
+    files
+        .into_iter()
+        .filter(|f| {
+            is_skill_manifest_name(&f.path) || SIDECAR_MANIFEST_NAMES.contains(&f.path.as_str()) || linked.contains(&f.path)
+        })
+        .collect()
+}
+
+/// Targets of `[text](target)` links and images, in order of appearance
+fn extract_markdown_links(content: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("](") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find([')', '\n']) else {
+            break;
+        };
+        // `[a](path "title")` carries an optional title after the path
+        let target = rest[..end].split_whitespace().next().unwrap_or("");
+        let target = target.trim_start_matches('<').trim_end_matches('>');
+        if !target.is_empty() {
+            links.push(target.to_string());
+        }
+        rest = &rest[end..];
     }
+    links
+}
 
-    let data: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Invalid response: {}", e))?;
+/// `content` with each link target replaced by what `rewrite` returns for
+/// it; targets it returns `None` for are left as they are
+fn rewrite_markdown_links(content: &str, mut rewrite: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("](") {
+        out.push_str(&rest[..start + 2]);
+        rest = &rest[start + 2..];
+        let end = rest.find([')', '\n']).unwrap_or(rest.len());
+        let link = &rest[..end];
+        let target = link.split_whitespace().next().unwrap_or("");
+        let target = target.trim_start_matches('<').trim_end_matches('>');
+        match rewrite(target).filter(|_| !target.is_empty()) {
+            Some(new_target) => out.push_str(&link.replacen(target, &new_target, 1)),
+            None => out.push_str(link),
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
 
-    let skills = data
-        .get("skills")
-        .and_then(|s| s.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|item| {
-                    let name = item.get("name")?.as_str()?.to_string();
-                    let slug = item.get("id")?.as_str()?.to_string();
-                    let source = item
-                        .get("topSource")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string();
-                    let installs = item
-                        .get("installs")
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(0);
+/// Resolves a link against the linking file's directory. URLs, anchors and
+/// absolute paths aren't local references and yield `None`.
+fn resolve_relative_link(base: &Path, target: &str) -> Option<PathBuf> {
+    if target.starts_with('#') || target.starts_with('/') || target.contains("://") || target.starts_with("mailto:") {
+        return None;
+    }
+    let target = target.split(['#', '?']).next()?;
+    let target = urlencoding::decode(target).map(|t| t.into_owned()).unwrap_or_else(|_| target.to_string());
+
+    let mut path = base.to_path_buf();
+    for component in Path::new(&target).components() {
+        match component {
+            std::path::Component::ParentDir => {
+                path.pop();
+            }
+            std::path::Component::Normal(part) => path.push(part),
+            _ => {}
+        }
+    }
+    Some(path)
+}
 
-                    Some(SearchSkill {
-                        name,
-                        slug,
-                        source,
-                        installs,
-                    })
-                })
-                .collect()
-        })
-        .unwrap_or_default();
+/// Renders markdown to HTML for the preview. Raw HTML in a skill is shown
+/// as text and link targets other than `is_safe_link` ones are dropped, so
+/// the output can go straight into the page.
+fn render_markdown_html(markdown: &str) -> String {
+    use pulldown_cmark::{CowStr, Event, Options, Parser, Tag};
+
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Link { link_type, dest_url, title, id }) if !is_safe_link(&dest_url) => {
+            Event::Start(Tag::Link { link_type, dest_url: CowStr::Borrowed(""), title, id })
+        }
+        Event::Start(Tag::Image { link_type, dest_url, title, id }) if !is_safe_link(&dest_url) => {
+            Event::Start(Tag::Image { link_type, dest_url: CowStr::Borrowed(""), title, id })
+        }
+        event => event,
+    });
 
-    Ok(skills)
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, events);
+    html
 }
 
-// ============================================================================
-// MCP Server Commands
-// ============================================================================
+/// Web, mail, anchor and relative links; anything else (`javascript:`,
+/// `data:`, `file:`) is dropped from the preview
+fn is_safe_link(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    let scheme_end = lower.find([':', '/', '?', '#']);
+    match scheme_end {
+        Some(i) if lower[i..].starts_with(':') => {
+            ["http:", "https:", "mailto:"].iter().any(|s| lower.starts_with(s))
+        }
+        _ => !lower.is_empty(),
+    }
+}
 
-#[tauri::command]
-fn list_mcp_servers(agent: AgentType) -> Result<Vec<McpServerInfo>, String> {
-    if !agent_has_mcp_support(agent) {
-        return Ok(vec![]);
+/// Whether an installed skill's files no longer match the hash recorded at
+/// install. Skills without a recorded hash are assumed unchanged.
+fn has_local_changes(skill_dir: &Path) -> bool {
+    let Some(installed) = read_metadata(skill_dir).and_then(|m| m.content_hash) else {
+        return false;
+    };
+    hash_skill_dir(skill_dir).is_ok_and(|current| current != installed)
+}
+
+/// Whether `skill_dir` holds a skill whose content differs from `files`
+fn is_conflicting_skill(skill_dir: &Path, files: &[SkillFile]) -> bool {
+    if !skill_dir.exists() {
+        return false;
     }
-    let config_path = get_mcp_config_path(agent)?;
 
-    if !config_path.exists() {
-        return Ok(vec![]);
+    match collect_skill_files(skill_dir) {
+        Ok(existing) => hash_skill_files(&existing) != hash_skill_files(files),
+        Err(_) => true,
     }
+}
 
-    let content = fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-    let config: serde_json::Value =
-        serde_json::from_str(&content).map_err(|e| format!("Invalid JSON: {}", e))?;
+/// Applies a Unix mode from the source (zip entry, git tree) so bundled
+/// scripts stay executable. The owner always keeps read/write access.
+#[cfg(unix)]
+fn apply_file_mode(path: &Path, mode: Option<u32>) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
 
-    let servers = config
-        .get("mcpServers")
-        .and_then(|s| s.as_object())
-        .map(|obj| {
-            obj.iter()
-                .map(|(name, value)| parse_mcp_server(name, value))
-                .collect()
-        })
-        .unwrap_or_default();
+    if let Some(mode) = mode {
+        let permissions = fs::Permissions::from_mode((mode & 0o777) | 0o600);
+        fs::set_permissions(path, permissions).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
 
-    Ok(servers)
+#[cfg(not(unix))]
+fn apply_file_mode(_path: &Path, _mode: Option<u32>) -> Result<(), String> {
+    Ok(())
 }
 
-#[tauri::command]
-fn add_mcp_server(agent: AgentType, config: AddMcpServerRequest) -> Result<(), String> {
-    if !agent_has_mcp_support(agent) {
-        return Err("MCP is not supported for this agent".to_string());
-    }
-    let config_path = get_mcp_config_path(agent)?;
+/// The files a skill's size, file count and token figures are made of,
+/// with their `/`-separated path relative to `dir`. Install metadata,
+/// dotfiles and OS junk are never counted, and symlinks aren't followed.
+fn skill_content_files(dir: &Path) -> impl Iterator<Item = (String, fs::Metadata)> {
+    let mut pending = vec![(dir.to_path_buf(), String::new())];
+    let mut files = Vec::new().into_iter();
 
-    let mut root: serde_json::Value = if config_path.exists() {
-        let content = fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-        serde_json::from_str(&content).map_err(|e| e.to_string())?
-    } else {
-        serde_json::json!({})
-    };
+    std::iter::from_fn(move || loop {
+        if let Some(file) = files.next() {
+            return Some(file);
+        }
+        let (dir, prefix) = pending.pop()?;
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
 
-    let mcp_servers = root
-        .as_object_mut()
-        .ok_or("Invalid config format")?
-        .entry("mcpServers")
-        .or_insert(serde_json::json!({}))
-        .as_object_mut()
-        .ok_or("Invalid mcpServers format")?;
+        let mut found = Vec::new();
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || is_junk_file(&name) {
+                continue;
+            }
+            let Ok(meta) = fs::symlink_metadata(entry.path()) else {
+                continue;
+            };
+            let relative = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            if meta.is_dir() {
+                pending.push((entry.path(), relative));
+            } else if meta.is_file() {
+                found.push((relative, meta));
+            }
+        }
+        files = found.into_iter();
+    })
+}
 
-    let mut server_config = serde_json::Map::new();
+/// Reads every file under a skill directory, junk excluded, in sorted order
+fn collect_skill_files(dir: &Path) -> Result<Vec<SkillFile>, String> {
+    let mut files = Vec::new();
+    collect_skill_files_into(dir, "", &mut files)?;
+    Ok(files)
+}
 
-    if config.transport == "stdio" {
-        server_config.insert("type".to_string(), serde_json::json!("stdio"));
-        if let Some(cmd) = config.command {
-            server_config.insert("command".to_string(), serde_json::json!(cmd));
-        }
-        if let Some(args) = config.args {
-            server_config.insert("args".to_string(), serde_json::json!(args));
-        }
-        if let Some(env) = config.env {
-            server_config.insert("env".to_string(), serde_json::json!(env));
-        }
-    } else {
-        server_config.insert("type".to_string(), serde_json::json!("http"));
-        if let Some(url) = config.url {
-            server_config.insert("url".to_string(), serde_json::json!(url));
+fn collect_skill_files_into(dir: &Path, prefix: &str, files: &mut Vec<SkillFile>) -> Result<(), String> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if is_junk_file(&name) {
+            continue;
         }
-        if let Some(headers) = config.headers {
-            server_config.insert("headers".to_string(), serde_json::json!(headers));
+
+        let relative = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let path = entry.path();
+
+        if path.is_dir() {
+            collect_skill_files_into(&path, &relative, files)?;
+        } else {
+            let content = fs::read(&path).map_err(|e| e.to_string())?;
+            let mode = fs::metadata(&path).ok().and_then(|m| file_mode(&m));
+            files.push(SkillFile {
+                path: relative,
+                content,
+                mode,
+            });
         }
     }
 
-    mcp_servers.insert(config.name, serde_json::Value::Object(server_config));
+    Ok(())
+}
 
-    // Ensure parent directory exists (for Gemini: ~/.gemini/)
-    if let Some(parent) = config_path.parent() {
-        fs::create_dir_all(parent).ok();
-    }
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode())
+}
 
-    let json_str = serde_json::to_string_pretty(&root).map_err(|e| e.to_string())?;
-    fs::write(&config_path, json_str).map_err(|e| e.to_string())?;
+#[cfg(not(unix))]
+fn file_mode(_metadata: &fs::Metadata) -> Option<u32> {
+    None
+}
 
-    Ok(())
+/// SHA-256 over every file's relative path and content, in path order.
+/// Ignores `.metadata.json`, junk files and mtimes, so the same skill hashes
+/// the same wherever and whenever it was installed.
+fn hash_skill_dir(dir: &Path) -> Result<String, String> {
+    Ok(hash_skill_files(&collect_skill_files(dir)?))
 }
 
-#[tauri::command]
-fn remove_mcp_server(agent: AgentType, name: String) -> Result<(), String> {
-    if !agent_has_mcp_support(agent) {
-        return Err("MCP is not supported for this agent".to_string());
-    }
-    let config_path = get_mcp_config_path(agent)?;
+/// Content hash of a skill's files, independent of order and install metadata
+fn hash_skill_files(files: &[SkillFile]) -> String {
+    use sha2::{Digest, Sha256};
 
-    if !config_path.exists() {
-        return Ok(());
+    let mut files: Vec<&SkillFile> = files
+        .iter()
+        .filter(|f| f.path != ".metadata.json" && !is_junk_path(&f.path))
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        // Length prefixes keep path/content boundaries unambiguous
+        hasher.update((file.path.len() as u64).to_le_bytes());
+        hasher.update(file.path.as_bytes());
+        hasher.update((file.content.len() as u64).to_le_bytes());
+        hasher.update(&file.content);
     }
 
-    let content = fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-    let mut root: serde_json::Value =
-        serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    format!("{:x}", hasher.finalize())
+}
 
-    if let Some(mcp_servers) = root
-        .get_mut("mcpServers")
-        .and_then(|s| s.as_object_mut())
-    {
-        mcp_servers.remove(&name);
+fn add_zip_file<W: Write + std::io::Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    path: &str,
+    content: &[u8],
+    mode: Option<u32>,
+) -> Result<(), String> {
+    let mut options = zip::write::SimpleFileOptions::default();
+    if let Some(mode) = mode {
+        options = options.unix_permissions(mode & 0o777);
     }
 
-    let json_str = serde_json::to_string_pretty(&root).map_err(|e| e.to_string())?;
-    fs::write(&config_path, json_str).map_err(|e| e.to_string())?;
-
-    Ok(())
+    zip.start_file(path, options).map_err(|e| e.to_string())?;
+    zip.write_all(content).map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-fn toggle_mcp_server(agent: AgentType, name: String, disabled: bool) -> Result<(), String> {
-    if !agent_has_mcp_support(agent) {
-        return Err("MCP is not supported for this agent".to_string());
+/// Settings from the data dir; defaults when the file doesn't exist yet
+fn load_settings() -> Result<Settings, String> {
+    let path = get_app_data_dir()?.join(SETTINGS_FILE);
+    if !path.exists() {
+        return Ok(Settings::default());
     }
-    let config_path = get_mcp_config_path(agent)?;
 
-    if !config_path.exists() {
-        return Err("Config file not found".to_string());
-    }
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid settings: {}", e))
+}
 
-    let content = fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-    let mut root: serde_json::Value =
-        serde_json::from_str(&content).map_err(|e| e.to_string())?;
+fn save_settings(settings: &Settings) -> Result<(), String> {
+    let data_dir = get_app_data_dir()?;
+    fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
 
-    if let Some(server) = root
-        .get_mut("mcpServers")
-        .and_then(|s| s.get_mut(&name))
-        .and_then(|s| s.as_object_mut())
-    {
-        if disabled {
-            server.insert("disabled".to_string(), serde_json::json!(true));
-        } else {
-            server.remove("disabled");
-        }
-    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    let path = data_dir.join(SETTINGS_FILE);
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &path).map_err(|e| e.to_string())
+}
 
-    let json_str = serde_json::to_string_pretty(&root).map_err(|e| e.to_string())?;
-    fs::write(&config_path, json_str).map_err(|e| e.to_string())?;
+fn read_metadata(skill_dir: &Path) -> Option<SkillMetadata> {
+    let content = fs::read_to_string(skill_dir.join(".metadata.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
 
-    Ok(())
+/// A folder that groups skills rather than being one: no manifest of its
+/// own, but at least one subdirectory that has one. Only the folder itself
+/// and its children are checked, since `find_skill_md` would find a child's
+/// manifest for the folder too.
+fn is_namespace_dir(dir: &PathBuf) -> bool {
+    if !dir.is_dir() || has_own_manifest(dir) {
+        return false;
+    }
+
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|e| e.path().is_dir() && has_own_manifest(&e.path()))
+        })
+        .unwrap_or(false)
 }
 
-// ============================================================================
-// Helper Functions
-// ============================================================================
+/// Whether `dir` itself holds a SKILL.md or sidecar manifest
+fn has_own_manifest(dir: &Path) -> bool {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries.flatten().any(|e| {
+                let name = e.file_name();
+                let name = name.to_string_lossy();
+                e.path().is_file()
+                    && (is_skill_manifest_name(&name) || SIDECAR_MANIFEST_NAMES.contains(&name.as_ref()))
+            })
+        })
+        .unwrap_or(false)
+}
 
 fn find_skill_md(dir: &PathBuf) -> Option<PathBuf> {
-    let direct = dir.join("SKILL.md");
+    let direct = dir.join(SKILL_MANIFEST);
     if direct.exists() {
         return Some(direct);
     }
@@ -921,9 +8617,12 @@ fn find_skill_md(dir: &PathBuf) -> Option<PathBuf> {
     if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
+            if is_junk_file(&entry.file_name().to_string_lossy()) {
+                continue;
+            }
             if path.is_file() {
                 if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    if name.to_lowercase() == "skill.md" {
+                    if is_skill_manifest_name(name) {
                         return Some(path);
                     }
                 }
@@ -938,17 +8637,61 @@ fn find_skill_md(dir: &PathBuf) -> Option<PathBuf> {
     None
 }
 
+/// OS metadata that should never end up in a skill: macOS resource forks
+/// (`._*`, `__MACOSX`), Finder and Explorer caches
+fn is_junk_file(name: &str) -> bool {
+    name.starts_with("._")
+        || matches!(name, ".DS_Store" | "__MACOSX" | "Thumbs.db" | "desktop.ini")
+}
+
+/// Like `is_junk_file`, for any component of a `/`-separated relative path
+fn is_junk_path(path: &str) -> bool {
+    path.split(['/', '\\']).any(is_junk_file)
+}
+
+/// Rough token count from a byte length, ~4 bytes per token. Every token
+/// figure the app reports goes through this so they stay comparable.
+fn estimate_tokens(byte_len: u64) -> u64 {
+    byte_len / 4
+}
+
+fn extract_skill_license(content: &str) -> Option<String> {
+    frontmatter_value(frontmatter_block(content)?, "license")
+}
+
+/// A license from the install metadata, the frontmatter, or a LICENSE file
+fn skill_has_license(skill_dir: &Path) -> bool {
+    if read_metadata(skill_dir).is_some_and(|m| m.license.is_some()) {
+        return true;
+    }
+
+    let from_frontmatter = read_skill_manifest(skill_dir).and_then(|content| extract_skill_license(&content));
+    if from_frontmatter.is_some() {
+        return true;
+    }
+
+    fs::read_dir(skill_dir)
+        .map(|entries| {
+            entries.flatten().any(|e| {
+                let name = e.file_name().to_string_lossy().to_uppercase();
+                name.starts_with("LICENSE") || name.starts_with("LICENCE") || name.starts_with("COPYING")
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// The name from the first of `skill_name_keys` set in the frontmatter,
+/// otherwise derived from `fallback` (a URL or filename)
 fn extract_skill_name(content: &str, fallback: &str) -> String {
-    if content.starts_with("---") {
-        if let Some(end) = content[3..].find("---") {
-            let frontmatter = &content[3..3 + end];
-            for line in frontmatter.lines() {
-                if line.starts_with("name:") {
-                    let name = line[5..].trim().trim_matches('"').trim_matches('\'');
-                    if !name.is_empty() {
-                        return name.to_string();
-                    }
-                }
+    extract_skill_name_by(content, fallback, &skill_name_keys())
+}
+
+/// `extract_skill_name` with the keys given rather than from settings
+fn extract_skill_name_by(content: &str, fallback: &str, keys: &[String]) -> String {
+    if let Some(frontmatter) = frontmatter_block(content) {
+        for key in keys {
+            if let Some(name) = frontmatter_value(frontmatter, key) {
+                return name;
             }
         }
     }
@@ -962,21 +8705,88 @@ fn extract_skill_name(content: &str, fallback: &str) -> String {
         .to_string()
 }
 
-fn extract_skill_description(content: &str) -> Option<String> {
-    if content.starts_with("---") {
-        if let Some(end) = content[3..].find("---") {
-            let frontmatter = &content[3..3 + end];
-            for line in frontmatter.lines() {
-                if line.starts_with("description:") {
-                    let desc = line[12..].trim().trim_matches('"').trim_matches('\'');
-                    if !desc.is_empty() {
-                        return Some(desc.to_string());
-                    }
-                }
-            }
+/// The text between the opening and closing `---`
+fn frontmatter_block(content: &str) -> Option<&str> {
+    content.strip_prefix("---").and_then(|rest| rest.find("---").map(|end| &rest[..end]))
+}
+
+/// The frontmatter block (as `frontmatter_block` returns it) and the body
+/// after it and its fences, with leading blank lines dropped
+fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    match frontmatter_block(content) {
+        // Skip the block and both `---` fences
+        Some(block) => (Some(block), content[block.len() + 6..].trim_start()),
+        None => (None, content),
+    }
+}
+
+/// A frontmatter list, written either as a YAML sequence or as one
+/// comma-separated string
+fn frontmatter_list(frontmatter: &str, key: &str) -> Vec<String> {
+    let split = |s: &str| -> Vec<String> {
+        s.trim_matches(['[', ']'])
+            .split(',')
+            .map(|item| item.trim().trim_matches('"').trim_matches('\'').to_string())
+            .filter(|item| !item.is_empty())
+            .collect()
+    };
+
+    match serde_yaml::from_str::<serde_yaml::Value>(frontmatter) {
+        Ok(yaml) => match yaml.get(key) {
+            Some(serde_yaml::Value::Sequence(items)) => items
+                .iter()
+                .filter_map(|item| item.as_str().map(|s| s.trim().to_string()))
+                .filter(|item| !item.is_empty())
+                .collect(),
+            Some(serde_yaml::Value::String(s)) => split(s),
+            _ => Vec::new(),
+        },
+        Err(_) => {
+            let mut lines = frontmatter.lines();
+            let Some(inline) = lines.find_map(|line| line.strip_prefix(key)?.strip_prefix(':')) else {
+                return Vec::new();
+            };
+            let mut items = split(inline);
+            // Block-style `- item` lines following the key
+            items.extend(
+                lines
+                    .map_while(|line| line.trim_start().strip_prefix("- "))
+                    .flat_map(split),
+            );
+            items
         }
     }
-    None
+}
+
+/// A scalar frontmatter value, so quoted and multi-line strings come out
+/// right. Plenty of frontmatter isn't valid YAML (unquoted colons in a
+/// description, say), so that falls back to matching `key:` lines.
+fn frontmatter_value(frontmatter: &str, key: &str) -> Option<String> {
+    let value = match serde_yaml::from_str::<serde_yaml::Value>(frontmatter) {
+        Ok(yaml) => match yaml.get(key)? {
+            serde_yaml::Value::String(s) => s.trim().to_string(),
+            serde_yaml::Value::Number(n) => n.to_string(),
+            _ => return None,
+        },
+        Err(_) => frontmatter.lines().find_map(|line| {
+            let value = line.strip_prefix(key)?.trim_start().strip_prefix(':')?;
+            Some(value.trim().trim_matches('"').trim_matches('\'').to_string())
+        })?,
+    };
+    Some(value).filter(|v| !v.is_empty())
+}
+
+fn extract_skill_description(content: &str) -> Option<String> {
+    frontmatter_value(frontmatter_block(content)?, "description")
+}
+
+/// A description collapsed to one line and cut at a character boundary
+fn list_description(description: &str) -> String {
+    let line = description.split_whitespace().collect::<Vec<_>>().join(" ");
+    match line.char_indices().nth(LIST_DESCRIPTION_MAX) {
+        Some((end, _)) => format!("{}…", line[..end].trim_end()),
+        None => line,
+    }
 }
 
 fn sanitize_name(name: &str) -> String {
@@ -994,22 +8804,81 @@ fn sanitize_name(name: &str) -> String {
     sanitized.to_lowercase().chars().take(50).collect()
 }
 
-fn save_metadata(skill_dir: &PathBuf, name: &str, source: Option<String>) -> Result<(), String> {
+/// Canonical form of a skill source: trimmed, without a fragment or
+/// trailing slashes. GitHub page URLs also drop their query string, which
+/// only carries UI state and breaks path parsing.
+fn normalize_source(source: &str) -> String {
+    let mut source = source.trim();
+    if let Some(idx) = source.find('#') {
+        source = &source[..idx];
+    }
+    if source.starts_with("https://github.com/") {
+        if let Some(idx) = source.find('?') {
+            source = &source[..idx];
+        }
+    }
+    source.trim_end_matches('/').to_string()
+}
+
+fn classify_source(source: Option<&str>) -> SourceKind {
+    let Some(source) = source else {
+        return SourceKind::Local;
+    };
+    let lower = source.to_lowercase();
+
+    if lower.starts_with("oci://") {
+        return SourceKind::Oci;
+    }
+    if !lower.starts_with("http://") && !lower.starts_with("https://") {
+        return if lower.ends_with(".zip") {
+            SourceKind::Zip
+        } else {
+            SourceKind::Local
+        };
+    }
+
+    let host = reqwest::Url::parse(source)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+        .unwrap_or_default();
+    match host.as_str() {
+        "github.com" if lower.contains("/tree/") => SourceKind::GithubDir,
+        "github.com" if lower.contains("/blob/") => SourceKind::GithubBlob,
+        "gist.github.com" | "gist.githubusercontent.com" => SourceKind::Gist,
+        "skills.sh" => SourceKind::Registry,
+        _ if lower.ends_with(".zip") => SourceKind::Zip,
+        _ => SourceKind::DirectUrl,
+    }
+}
+
+fn save_metadata(skill_dir: &Path, name: &str, source: Option<String>) -> Result<(), String> {
     let now = chrono::Utc::now().to_rfc3339();
 
-    // Try to extract description from SKILL.md
-    let description = find_skill_md(skill_dir)
-        .and_then(|skill_md_path| fs::read_to_string(skill_md_path).ok())
-        .and_then(|content| extract_skill_description(&content));
+    // Try to extract description and license from SKILL.md
+    let content = read_skill_manifest(skill_dir);
+    let description = content.as_deref().and_then(extract_skill_description);
+    let license = content.as_deref().and_then(extract_skill_license);
+
+    let source = source.map(|s| normalize_source(&s));
+    let source_kind = Some(classify_source(source.as_deref()));
 
     let metadata = SkillMetadata {
         name: name.to_string(),
         description,
         source,
+        source_kind,
         version: None,
         author: None,
+        license,
         installed_at: now.clone(),
         updated_at: now,
+        pinned: false,
+        content_hash: hash_skill_dir(skill_dir).ok(),
+        notes: None,
+        skill_md_only: false,
+        entrypoint: None,
+        externalized_assets: vec![],
+        favorite: false,
     };
 
     let json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
@@ -1018,7 +8887,27 @@ fn save_metadata(skill_dir: &PathBuf, name: &str, source: Option<String>) -> Res
     Ok(())
 }
 
-fn parse_mcp_server(name: &str, value: &serde_json::Value) -> McpServerInfo {
+/// Reads an agent's JSON config, treating a missing file as empty
+fn read_mcp_config(config_path: &Path) -> Result<serde_json::Value, String> {
+    if !config_path.exists() {
+        return Ok(serde_json::json!({}));
+    }
+
+    let content = read_text(config_path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid JSON: {}", e))
+}
+
+fn write_mcp_config(config_path: &Path, root: &serde_json::Value) -> Result<(), String> {
+    // Ensure parent directory exists (for Gemini: ~/.gemini/)
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+
+    let json_str = serde_json::to_string_pretty(root).map_err(|e| e.to_string())?;
+    fs::write(config_path, json_str).map_err(|e| e.to_string())
+}
+
+fn parse_mcp_server(agent: AgentType, name: &str, value: &serde_json::Value) -> McpServerInfo {
     let obj = value.as_object();
 
     let transport = if value.get("url").is_some() {
@@ -1028,9 +8917,14 @@ fn parse_mcp_server(name: &str, value: &serde_json::Value) -> McpServerInfo {
     };
 
     McpServerInfo {
+        agent,
         name: name.to_string(),
         transport: transport.to_string(),
-        disabled: value.get("disabled").and_then(|v| v.as_bool()),
+        // Some agents write `enabled: false` rather than `disabled: true`
+        disabled: value
+            .get("disabled")
+            .and_then(|v| v.as_bool())
+            .or_else(|| value.get("enabled").and_then(|v| v.as_bool()).map(|enabled| !enabled)),
         command: value
             .get("command")
             .and_then(|v| v.as_str())
@@ -1060,104 +8954,290 @@ fn parse_mcp_server(name: &str, value: &serde_json::Value) -> McpServerInfo {
     }
 }
 
-async fn install_from_github_dir(agent: AgentType, url: &str) -> Result<String, String> {
+/// `owner/repo[/path][@ref]`, as typed instead of a full `/tree/` URL
+struct GithubShorthand {
+    owner: String,
+    repo: String,
+    path: String,
+    reference: Option<String>,
+}
+
+fn parse_github_shorthand(input: &str) -> Option<GithubShorthand> {
+    if input.contains("://") || input.starts_with(['.', '/', '~']) || input.contains(['\\', ':', ' ']) {
+        return None;
+    }
+
+    let (location, reference) = match input.rsplit_once('@') {
+        Some((location, reference)) if !reference.is_empty() => (location, Some(reference.to_string())),
+        Some(_) => return None,
+        None => (input, None),
+    };
+
+    let mut parts = location.split('/');
+    let owner = parts.next()?;
+    let repo = parts.next()?;
+    let is_name = |s: &str| {
+        !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    if !is_name(owner) || !is_name(repo) {
+        return None;
+    }
+
+    let path: Vec<&str> = parts.collect();
+    if path.iter().any(|p| p.is_empty() || *p == "..") {
+        return None;
+    }
+
+    Some(GithubShorthand {
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+        path: path.join("/"),
+        reference,
+    })
+}
+
+/// `https://github.com/owner/repo` with nothing after it, installed as the
+/// repo root on its default branch
+fn parse_github_repo_url(url: &str) -> Option<GithubShorthand> {
+    let rest = url.strip_prefix("https://github.com/")?;
+    if rest.matches('/').count() != 1 {
+        return None;
+    }
+    parse_github_shorthand(rest).filter(|s| s.reference.is_none())
+}
+
+/// Splits what follows `/tree/` into a ref and a path. Refs can contain
+/// slashes, so with several segments the shortest prefix GitHub knows as a
+/// branch, tag or commit wins. If GitHub can't be asked, the first segment
+/// is used as before.
+async fn resolve_github_tree_ref(
+    client: &reqwest::Client,
+    owner: &str,
+    repo: &str,
+    rest: &str,
+) -> Result<(String, String), String> {
+    if rest.split('/').all(|s| s.is_empty()) {
+        return Ok((fetch_github_default_branch(owner, repo).await?, String::new()));
+    }
+
+    for candidate in github_tree_ref_candidates(rest) {
+        match github_ref_exists(client, owner, repo, &candidate).await {
+            Some(true) => return Ok(split_github_tree_ref(rest, Some(&candidate))),
+            Some(false) => continue,
+            None => break,
+        }
+    }
+    Ok(split_github_tree_ref(rest, None))
+}
+
+/// The refs worth asking GitHub about for what follows `/tree/`, shortest
+/// first. None when there's nothing ambiguous: a single segment, or a
+/// commit SHA up front.
+fn github_tree_ref_candidates(rest: &str) -> Vec<String> {
+    let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+    let is_commit_sha = |s: &str| s.len() == 40 && s.chars().all(|c| c.is_ascii_hexdigit());
+    if segments.len() < 2 || is_commit_sha(segments[0]) {
+        return Vec::new();
+    }
+    (1..=segments.len()).map(|i| segments[..i].join("/")).collect()
+}
+
+/// Splits what follows `/tree/` at `reference`, or after the first segment
+/// when GitHub couldn't confirm one
+fn split_github_tree_ref(rest: &str, reference: Option<&str>) -> (String, String) {
+    let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+    let ref_len = reference.map_or(1, |r| r.split('/').count()).min(segments.len());
+    (segments[..ref_len].join("/"), segments[ref_len..].join("/"))
+}
+
+/// Whether `reference` resolves to a commit, or `None` if GitHub couldn't
+/// answer (offline, rate limited)
+async fn github_ref_exists(client: &reqwest::Client, owner: &str, repo: &str, reference: &str) -> Option<bool> {
+    let api_url = format!("https://api.github.com/repos/{}/{}/commits/{}", owner, repo, reference);
+    let response = authorized_get(client, &api_url, None)
+        .header(reqwest::header::ACCEPT, "application/vnd.github.sha")
+        .send()
+        .await
+        .ok()?;
+
+    match response.status() {
+        status if status.is_success() => Some(true),
+        reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::UNPROCESSABLE_ENTITY => Some(false),
+        _ => None,
+    }
+}
+
+/// The `/tree/` URL for a shorthand, looking up the repo's default branch
+/// when no ref was given
+async fn expand_github_shorthand(shorthand: GithubShorthand) -> Result<String, String> {
+    let reference = match shorthand.reference {
+        Some(reference) => reference,
+        None => fetch_github_default_branch(&shorthand.owner, &shorthand.repo).await?,
+    };
+
+    let mut url = format!(
+        "https://github.com/{}/{}/tree/{}",
+        shorthand.owner, shorthand.repo, reference
+    );
+    if !shorthand.path.is_empty() {
+        url.push('/');
+        url.push_str(&shorthand.path);
+    }
+    Ok(url)
+}
+
+async fn fetch_github_default_branch(owner: &str, repo: &str) -> Result<String, String> {
+    let client = http_client()?;
+    let api_url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+    let response = authorized_get(&client, &api_url, None)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("GitHub repo {}/{} not found ({})", owner, repo, response.status()));
+    }
+
+    let repo_info: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+    repo_info
+        .get("default_branch")
+        .and_then(|b| b.as_str())
+        .map(String::from)
+        .ok_or_else(|| format!("Cannot determine the default branch of {}/{}", owner, repo))
+}
+
+/// Fetches a GitHub directory, keeping each downloaded file in `staging`
+/// so a retry after a failure only fetches what's missing. `on_file` is
+/// called as each file is ready.
+async fn fetch_github_dir(
+    url: &str,
+    staging: &Path,
+    entrypoint: Option<&str>,
+    on_file: &(dyn Fn(&str) + Send + Sync),
+) -> Result<PreparedSkill, String> {
     let parts: Vec<&str> = url
         .trim_start_matches("https://github.com/")
-        .split('/')
+        .splitn(4, '/')
         .collect();
 
-    if parts.len() < 4 {
+    if parts.len() < 3 || parts[2] != "tree" {
         return Err("Invalid GitHub URL format".to_string());
     }
 
     let owner = parts[0];
     let repo = parts[1];
-    let branch = parts[3];
-    let path = if parts.len() > 4 {
-        parts[4..].join("/")
-    } else {
-        String::new()
-    };
+    let client = http_client()?;
+    let (branch, path) = resolve_github_tree_ref(&client, owner, repo, parts.get(3).unwrap_or(&"")).await?;
+    let branch = branch.as_str();
 
     let api_url = format!(
         "https://api.github.com/repos/{}/{}/contents/{}?ref={}",
         owner, repo, path, branch
     );
 
-    let client = reqwest::Client::builder()
-        .user_agent("Oh-My-Skills/0.1")
-        .build()
-        .map_err(|e| e.to_string())?;
-
-    let files = fetch_github_files(&client, &api_url).await?;
+    let mut files = fetch_github_files(&client, &api_url, "", staging, on_file).await?;
 
     if files.is_empty() {
         return Err("No files found in GitHub directory".to_string());
     }
 
-    let skill_name = files
-        .iter()
-        .find(|(name, _)| name.to_lowercase() == "skill.md")
-        .and_then(|(_, content)| {
-            let name = extract_skill_name(content, "");
-            if name.is_empty() {
-                None
-            } else {
-                Some(name)
-            }
-        })
-        .unwrap_or_else(|| path.rsplit('/').next().unwrap_or("skill").to_string());
-
-    let skills_dir = get_skills_dir(agent)?;
-    let skill_dir = skills_dir.join(sanitize_name(&skill_name));
-    fs::create_dir_all(&skill_dir).map_err(|e| e.to_string())?;
-
-    for (file_path, content) in &files {
-        let out_path = skill_dir.join(file_path);
-        if let Some(parent) = out_path.parent() {
-            fs::create_dir_all(parent).ok();
+    let executables = fetch_github_executables(&client, owner, repo, branch, &path).await;
+    for file in &mut files {
+        if executables.contains(&file.path) {
+            file.mode = Some(0o755);
         }
-        fs::write(&out_path, content).map_err(|e| e.to_string())?;
     }
 
-    save_metadata(&skill_dir, &skill_name, Some(url.to_string()))?;
+    if let Some(entrypoint) = entrypoint {
+        use_entrypoint(&mut files, entrypoint)?;
+    }
+
+    let Some(manifest) = skill_files_manifest(&files) else {
+        let present: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        return Err(format!(
+            "Directory is not a skill (no SKILL.md found). Found: {}",
+            present.join(", ")
+        ));
+    };
+
+    let skill_name = match extract_skill_name(&manifest, "") {
+        name if name.is_empty() => path.rsplit('/').next().unwrap_or("skill").to_string(),
+        name => name,
+    };
 
-    Ok(format!("Installed: {}", skill_name))
+    Ok(PreparedSkill {
+        name: skill_name,
+        files,
+        source: Some(url.to_string()),
+    })
 }
 
 async fn fetch_github_files(
     client: &reqwest::Client,
     api_url: &str,
-) -> Result<Vec<(String, String)>, String> {
-    let response = client.get(api_url).send().await.map_err(|e| e.to_string())?;
+    prefix: &str,
+    staging: &Path,
+    on_file: &(dyn Fn(&str) + Send + Sync),
+) -> Result<Vec<SkillFile>, String> {
+    let response = authorized_get(client, api_url, None)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = check_response(response).await?;
 
     let items: Vec<serde_json::Value> = response.json().await.map_err(|e| e.to_string())?;
 
+    // Sizes come with the listing, so check space before fetching anything
+    let pending_bytes = items
+        .iter()
+        .filter(|item| item.get("type").and_then(|v| v.as_str()) == Some("file"))
+        .filter(|item| {
+            let name = item.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            !staging.join(format!("{}{}", prefix, name)).exists()
+        })
+        .filter_map(|item| item.get("size")?.as_u64())
+        .sum();
+    ensure_disk_space(staging, pending_bytes)?;
+
     let mut files = Vec::new();
 
     for item in items {
         let item_type = item.get("type").and_then(|v| v.as_str()).unwrap_or("");
         let name = item.get("name").and_then(|v| v.as_str()).unwrap_or("");
 
+        if is_junk_file(name) {
+            continue;
+        }
+
+        let relative_path = format!("{}{}", prefix, name);
+
         if item_type == "file" {
             if let Some(download_url) = item.get("download_url").and_then(|v| v.as_str()) {
-                let content = client
-                    .get(download_url)
-                    .send()
-                    .await
-                    .map_err(|e| e.to_string())?
-                    .text()
-                    .await
-                    .map_err(|e| e.to_string())?;
+                let staged = staging.join(&relative_path);
+                let sha = item.get("sha").and_then(|v| v.as_str()).unwrap_or("");
+                let content = match read_staged_github_file(&staged, sha) {
+                    Some(content) => content,
+                    None => {
+                        let response = authorized_get(client, download_url, None)
+                            .header(reqwest::header::ACCEPT_ENCODING, "gzip")
+                            .send()
+                            .await
+                            .map_err(|e| e.to_string())?;
+                        let response = check_response(response).await?;
+                        if let Some(parent) = staged.parent() {
+                            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                        }
+                        download_to_file(response, &staged).await?;
+                        fs::read(&staged).map_err(|e| e.to_string())?
+                    }
+                };
+                on_file(&relative_path);
 
-                files.push((name.to_string(), content));
+                files.push(SkillFile::new(&relative_path, content));
             }
         } else if item_type == "dir" {
             if let Some(url) = item.get("url").and_then(|v| v.as_str()) {
-                let sub_files = Box::pin(fetch_github_files(client, url)).await?;
-                for (sub_name, content) in sub_files {
-                    files.push((format!("{}/{}", name, sub_name), content));
-                }
+                let sub_prefix = format!("{}/", relative_path);
+                files.extend(Box::pin(fetch_github_files(client, url, &sub_prefix, staging, on_file)).await?);
             }
         }
     }
@@ -1165,6 +9245,436 @@ async fn fetch_github_files(
     Ok(files)
 }
 
+/// A file an earlier attempt downloaded, if it still matches the blob `sha`
+/// GitHub lists for it now; a changed or truncated file is fetched again
+fn read_staged_github_file(staged: &Path, sha: &str) -> Option<Vec<u8>> {
+    use sha1::{Digest, Sha1};
+
+    let content = fs::read(staged).ok()?;
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", content.len()));
+    hasher.update(&content);
+    let actual = format!("{:x}", hasher.finalize());
+    actual.eq_ignore_ascii_case(sha).then_some(content)
+}
+
+/// Starts watching a dev link's working directory
+fn watch_dev_link(app: &AppHandle, link: &DevLink) -> Result<(), String> {
+    use notify::Watcher;
+
+    let link_path = resolve_skill_dir(link.agent, &link.name, None)?;
+    let emitter = app.clone();
+    let (agent, name) = (link.agent, link.name.clone());
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if event.kind.is_access() {
+            return;
+        }
+        let _ = emitter.emit(
+            "dev-skill-changed",
+            DevSkillChangedEvent {
+                agent,
+                name: name.clone(),
+                paths: event.paths.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+            },
+        );
+    })
+    .map_err(|e| e.to_string())?;
+    watcher
+        .watch(Path::new(&link.source_dir), notify::RecursiveMode::Recursive)
+        .map_err(|e| e.to_string())?;
+
+    app.state::<DevWatchers>()
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(link_path, watcher);
+    Ok(())
+}
+
+/// Re-watches dev links saved in settings whose symlinks are still there
+fn watch_dev_links(app: &AppHandle) {
+    let Ok(settings) = load_settings() else {
+        return;
+    };
+    for link in &settings.dev_links {
+        let linked = resolve_skill_dir(link.agent, &link.name, None)
+            .is_ok_and(|path| fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink()));
+        if !linked {
+            continue;
+        }
+        if let Err(e) = watch_dev_link(app, link) {
+            tracing::warn!("Not watching {}: {}", link.source_dir, e);
+        }
+    }
+}
+
+/// Emits `event` to the UI and appends it to the history file. Recording
+/// is best effort and never fails the operation itself.
+fn record_activity(app: &AppHandle, event: ActivityEvent) {
+    let _ = app.emit("activity", &event);
+
+    let Some(log) = app.try_state::<ActivityLog>() else {
+        return;
+    };
+    let Ok(_held) = log.0.lock() else {
+        return;
+    };
+    if let Err(e) = append_activity(&event) {
+        tracing::warn!("Failed to record activity: {}", e);
+    }
+}
+
+fn append_activity(event: &ActivityEvent) -> Result<(), String> {
+    let data_dir = get_app_data_dir()?;
+    fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
+    let path = data_dir.join(ACTIVITY_FILE);
+
+    let mut lines: Vec<String> = fs::read_to_string(&path)
+        .map(|content| content.lines().map(String::from).collect())
+        .unwrap_or_default();
+    lines.push(serde_json::to_string(event).map_err(|e| e.to_string())?);
+    if lines.len() > ACTIVITY_HISTORY_MAX {
+        lines.drain(..lines.len() - ACTIVITY_HISTORY_MAX);
+    }
+
+    let tmp = path.with_extension("jsonl.tmp");
+    fs::write(&tmp, lines.join("\n") + "\n").map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &path).map_err(|e| e.to_string())
+}
+
+/// One activity entry per skill written, or one for the whole request if it
+/// failed before reaching any agent
+fn record_install_activity(
+    app: &AppHandle,
+    agent: AgentType,
+    source: String,
+    result: &Result<Vec<InstallResult>, String>,
+) {
+    match result {
+        Ok(results) => {
+            for installed in results {
+                let status = serde_json::to_value(installed.status)
+                    .ok()
+                    .and_then(|v| v.as_str().map(String::from))
+                    .unwrap_or_default();
+                let outcome = match installed.status {
+                    InstallStatus::Failed => Err(installed.warnings.join("; ")),
+                    _ => Ok(()),
+                };
+                record_activity(
+                    app,
+                    ActivityEvent::new(ActivityKind::Install, Some(installed.agent), &installed.name, &outcome)
+                        .with_detail(format!("{} from {}", status, source)),
+                );
+            }
+        }
+        Err(_) => record_activity(app, ActivityEvent::new(ActivityKind::Install, Some(agent), source, result)),
+    }
+}
+
+/// Starts writing logs to a daily file in the log dir, at the level saved
+/// in settings
+fn init_logging() -> Result<LogState, String> {
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    let log_dir = get_log_dir()?;
+    fs::create_dir_all(&log_dir).map_err(|e| e.to_string())?;
+
+    let level = load_settings().ok().and_then(|s| s.log_level);
+    let filter = tracing_subscriber::EnvFilter::try_new(level.as_deref().unwrap_or(DEFAULT_LOG_LEVEL))
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(DEFAULT_LOG_LEVEL));
+    let (filter, handle) = tracing_subscriber::reload::Layer::new(filter);
+
+    let appender = tracing_appender::rolling::daily(&log_dir, LOG_FILE_PREFIX);
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(false))
+        .try_init()
+        .map_err(|e| e.to_string())?;
+
+    Ok(LogState {
+        filter: handle,
+        _guard: guard,
+    })
+}
+
+/// Passes successful responses through. For any other status the error
+/// names the status and URL, plus the start of the body (secrets redacted)
+/// when `debug_http_errors` is on.
+async fn check_response(response: reqwest::Response) -> Result<reqwest::Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let mut message = format!("HTTP {} from {}", status, redact_url(response.url().as_str()));
+    if load_settings().is_ok_and(|s| s.debug_http_errors) {
+        let body = response.text().await.unwrap_or_default();
+        let mut body = redact_secrets(body.trim());
+        if body.len() > DEBUG_BODY_LIMIT {
+            let end = (0..=DEBUG_BODY_LIMIT).rev().find(|i| body.is_char_boundary(*i)).unwrap_or(0);
+            body.truncate(end);
+            body.push_str("...");
+        }
+        if !body.is_empty() {
+            message.push_str(":\n");
+            message.push_str(&body);
+        }
+    }
+    Err(message)
+}
+
+/// Masks things that look like credentials: known token prefixes, long
+/// opaque strings, and whatever follows a key such as `token` or `password`
+fn redact_secrets(text: &str) -> String {
+    const KEYWORDS: &[&str] = &["token", "access_token", "password", "secret", "api_key", "apikey", "authorization", "bearer"];
+    let is_word_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | '+' | '=');
+
+    let mut out = String::with_capacity(text.len());
+    let mut previous_word = String::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let split = rest.find(|c: char| !is_word_char(c)).unwrap_or(rest.len());
+        if split == 0 {
+            let c = rest.chars().next().unwrap_or_default();
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let word = &rest[..split];
+        let opaque = word.len() >= 32
+            && word.chars().any(|c| c.is_ascii_digit())
+            && word.chars().any(|c| c.is_ascii_alphabetic());
+        if opaque || SECRET_PREFIXES.iter().any(|p| word.starts_with(p)) || KEYWORDS.contains(&previous_word.as_str()) {
+            out.push_str("[redacted]");
+        } else {
+            out.push_str(word);
+        }
+        previous_word = word.trim_end_matches('=').to_lowercase();
+        rest = &rest[split..];
+    }
+    out
+}
+
+/// A URL safe to log: credentials and the query string are dropped, since
+/// either may carry a token
+fn redact_url(url: &str) -> String {
+    // The payload of a data URL is the skill itself; keep only its type
+    if let Some(rest) = url.trim_start().strip_prefix("data:") {
+        return format!("data:{}", rest.split([';', ',']).next().unwrap_or(""));
+    }
+    let url = url.split(['?', '#']).next().unwrap_or("");
+    match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let (authority, path) = rest.split_once('/').map_or((rest, ""), |(a, p)| (a, p));
+            let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+            if path.is_empty() {
+                format!("{}://{}", scheme, host)
+            } else {
+                format!("{}://{}/{}", scheme, host, path)
+            }
+        }
+        None => url.to_string(),
+    }
+}
+
+/// Streams a response body to `path` in chunks, gunzipping it when the
+/// server sent it gzip-encoded, and gives up past `MAX_DOWNLOAD_SIZE`.
+/// Writes via a `.part` file so an interrupted write is never mistaken for
+/// a complete download on resume.
+async fn download_to_file(mut response: reqwest::Response, path: &Path) -> Result<u64, String> {
+    if response.content_length().is_some_and(|len| len > MAX_DOWNLOAD_SIZE) {
+        return Err(download_too_large());
+    }
+    let gzipped = response
+        .headers()
+        .get(reqwest::header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("gzip"));
+
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    let part = PathBuf::from(part);
+
+    let written = async {
+        let mut file = CappedWriter {
+            inner: fs::File::create(&part).map_err(|e| e.to_string())?,
+            written: 0,
+        };
+        if gzipped {
+            let mut decoder = flate2::write::GzDecoder::new(file);
+            let received = copy_body(&mut response, &mut decoder).await?;
+            decoder.try_finish().map_err(|e| e.to_string())?;
+            Ok(received)
+        } else {
+            copy_body(&mut response, &mut file).await
+        }
+    }
+    .await;
+
+    match written {
+        Ok(received) => {
+            fs::rename(&part, path).map_err(|e| e.to_string())?;
+            Ok(received)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&part);
+            Err(e)
+        }
+    }
+}
+
+async fn copy_body(response: &mut reqwest::Response, out: &mut impl Write) -> Result<u64, String> {
+    let mut received = 0u64;
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        received += chunk.len() as u64;
+        if received > MAX_DOWNLOAD_SIZE {
+            return Err(download_too_large());
+        }
+        out.write_all(&chunk).map_err(|e| e.to_string())?;
+    }
+    out.flush().map_err(|e| e.to_string())?;
+    Ok(received)
+}
+
+fn download_too_large() -> String {
+    format!("Download is larger than {} MB", MAX_DOWNLOAD_SIZE / (1024 * 1024))
+}
+
+/// A file writer that fails once more than `MAX_DOWNLOAD_SIZE` bytes go
+/// through it, bounding what a gzip body can expand to
+struct CappedWriter {
+    inner: fs::File,
+    written: u64,
+}
+
+impl Write for CappedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.written + buf.len() as u64 > MAX_DOWNLOAD_SIZE {
+            return Err(std::io::Error::other(download_too_large()));
+        }
+        let written = self.inner.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// The contents API doesn't report file modes, so executable bits come from
+/// the git tree. Best effort: any failure just yields an empty set.
+async fn fetch_github_executables(
+    client: &reqwest::Client,
+    owner: &str,
+    repo: &str,
+    branch: &str,
+    path: &str,
+) -> std::collections::HashSet<String> {
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/git/trees/{}?recursive=1",
+        owner, repo, branch
+    );
+    let prefix = if path.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", path.trim_end_matches('/'))
+    };
+
+    let data: serde_json::Value = match authorized_get(client, &url, None).send().await {
+        Ok(response) => response.json().await.unwrap_or_default(),
+        Err(_) => return Default::default(),
+    };
+
+    data.get("tree")
+        .and_then(|t| t.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| item.get("mode").and_then(|m| m.as_str()) == Some("100755"))
+                .filter_map(|item| {
+                    let full_path = item.get("path")?.as_str()?;
+                    full_path.strip_prefix(&prefix).map(String::from)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Directories in the repo holding a SKILL.md where either the SKILL.md
+/// path or the directory matches `pattern`, sorted, `""` for the root
+async fn find_github_skill_dirs(
+    client: &reqwest::Client,
+    owner: &str,
+    repo: &str,
+    reference: &str,
+    pattern: &str,
+) -> Result<Vec<String>, String> {
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/git/trees/{}?recursive=1",
+        owner, repo, reference
+    );
+    let response = authorized_get(client, &url, None).send().await.map_err(|e| e.to_string())?;
+    let response = check_response(response).await?;
+    let data: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+    if data.get("truncated").and_then(|t| t.as_bool()) == Some(true) {
+        tracing::warn!(owner, repo, "GitHub truncated the tree listing; some skills may be missed");
+    }
+
+    let mut dirs = std::collections::BTreeSet::new();
+    for item in data.get("tree").and_then(|t| t.as_array()).into_iter().flatten() {
+        if item.get("type").and_then(|t| t.as_str()) != Some("blob") {
+            continue;
+        }
+        let Some(path) = item.get("path").and_then(|p| p.as_str()) else {
+            continue;
+        };
+        let (dir, file_name) = path.rsplit_once('/').unwrap_or(("", path));
+        if !file_name.eq_ignore_ascii_case(SKILL_MANIFEST) {
+            continue;
+        }
+        if glob_match(pattern, path) || (!dir.is_empty() && glob_match(pattern, dir)) {
+            dirs.insert(dir.to_string());
+        }
+    }
+    Ok(dirs.into_iter().collect())
+}
+
+/// Matches a `/`-separated path against a glob: `*` and `?` within one
+/// segment, `**` for any number of segments
+fn glob_match(pattern: &str, path: &str) -> bool {
+    fn segments(pattern: &[&str], path: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => path.is_empty(),
+            Some((&"**", rest)) => (0..=path.len()).any(|i| segments(rest, &path[i..])),
+            Some((segment, rest)) => path
+                .split_first()
+                .is_some_and(|(name, path)| wildcard(segment.as_bytes(), name.as_bytes()) && segments(rest, path)),
+        }
+    }
+    fn wildcard(pattern: &[u8], name: &[u8]) -> bool {
+        match pattern.split_first() {
+            None => name.is_empty(),
+            Some((b'*', rest)) => (0..=name.len()).any(|i| wildcard(rest, &name[i..])),
+            Some((b'?', rest)) => !name.is_empty() && wildcard(rest, &name[1..]),
+            Some((c, rest)) => name.first() == Some(c) && wildcard(rest, &name[1..]),
+        }
+    }
+
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').collect();
+    segments(&pattern, &path)
+}
+
 // ============================================================================
 // App Entry
 // ============================================================================
@@ -1173,27 +9683,153 @@ async fn fetch_github_files(
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .manage(SearchState::default())
+        .manage(InstallLocks::default())
+        .manage(ActivityLog::default())
+        .manage(TokenCounts::default())
+        .manage(DevWatchers::default())
+        .manage(InstallJobs::default())
         .invoke_handler(tauri::generate_handler![
             list_agents,
+            get_agent_metadata,
+            can_write_skills_dir,
+            can_write_mcp_config,
             list_skills,
+            list_skills_matrix,
+            recount_tokens,
+            total_active_token_cost,
+            find_unused_skills,
             get_skill_content,
+            get_skill_content_text,
+            get_skill_changelog,
+            get_skill_preview,
+            analyze_skill_content,
             get_skill_metadata,
+            get_skill_hash,
+            compare_skills,
+            preview_update,
+            skill_dependencies,
+            skill_mcp_requirements,
+            get_skill_badges,
             list_skill_files,
             read_skill_file,
             install_skill_from_url,
+            install_skills_from_github_glob,
+            reinstall_skill,
+            update_skill,
+            resolve_qr_url,
             install_skill_from_content,
             install_skill_from_zip,
+            is_skill_dir,
+            link_dev_skill,
+            unlink_dev_skill,
+            list_dev_skills,
+            preview_zip,
+            export_skill,
+            copy_skill_install_command,
+            prepare_skill_for_share,
             delete_skill,
+            preview_delete,
             open_skill_folder,
             search_skills,
+            estimate_search_skill_tokens,
+            resolve_slug_url,
+            install_skill_by_slug,
             list_mcp_servers,
+            find_duplicate_mcp_servers,
+            validate_all_mcp_servers,
             add_mcp_server,
             remove_mcp_server,
             toggle_mcp_server,
+            reorder_mcp_servers,
+            get_mcp_config_raw,
+            set_mcp_config_raw,
+            reset_mcp_servers,
+            get_agent_skill_errors,
+            list_pending_installs,
+            resume_install,
+            discard_pending_install,
+            start_install,
+            get_job,
+            list_jobs,
+            cancel_install,
+            prepare_clear,
+            clear_all_skills,
+            cleanup,
+            normalize_skill_names,
+            fix_skills_permissions,
+            pin_skill,
+            list_enabled_skills,
+            set_skill_enabled,
+            set_skill_notes,
+            set_skill_favorite,
+            externalize_skill_assets,
+            restore_skill_assets,
+            unpin_skill,
+            list_trash,
+            restore_from_trash,
+            diagnose,
+            preflight,
+            get_post_install_hook,
+            set_post_install_hook,
+            approve_post_install_hook,
+            list_credential_hosts,
+            set_host_credentials,
+            get_log_path,
+            get_all_scope,
+            get_name_keys,
+            set_name_keys,
+            set_debug_http_errors,
+            set_cleanup_on_startup,
+            resolve_all_targets,
+            set_all_scope,
+            get_activity_history,
+            set_log_level,
+            get_data_dir,
+            set_data_dir,
+            export_bundle,
+            import_bundle,
+            get_release_notes,
+            take_whats_new,
+            get_remote_hosts,
+            set_remote_hosts,
+            list_remote_skills,
+            get_remote_skill_content,
+            get_tray_icon_theme,
+            set_tray_icon_theme,
         ])
         .setup(|app| {
             use tauri::menu::PredefinedMenuItem;
 
+            match init_logging() {
+                Ok(logging) => {
+                    app.manage(logging);
+                }
+                Err(e) => {
+                    // Without a log file, tracing goes to stderr so events aren't lost
+                    let _ = tracing_subscriber::fmt().with_writer(std::io::stderr).try_init();
+                    tracing::warn!(error = %e, "file logging disabled");
+                }
+            }
+            watch_dev_links(app.handle());
+            if let Err(e) = check_whats_new() {
+                tracing::warn!(error = %e, "version check failed");
+            }
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = preflight(handle).await {
+                    tracing::warn!(error = %e, "preflight failed");
+                }
+            });
+            if load_settings().is_ok_and(|s| s.cleanup_on_startup) {
+                let handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = cleanup(handle, AgentType::All) {
+                        tracing::warn!(error = %e, "startup cleanup failed");
+                    }
+                });
+            }
+
             // Create tray menu items
             let settings_item = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
             let guide_item = MenuItem::with_id(app, "guide", "User Guide", true, None::<&str>)?;
@@ -1246,11 +9882,12 @@ pub fn run() {
 
             // Keep tray icon alive by storing it in app state
             app.manage(tray);
+            apply_tray_icon_theme(app.handle(), None);
 
             Ok(())
         })
-        .on_window_event(|window, event| {
-            if let WindowEvent::CloseRequested { api, .. } = event {
+        .on_window_event(|window, event| match event {
+            WindowEvent::CloseRequested { api, .. } => {
                 // Hide window and remove from Dock
                 let _ = window.hide();
                 #[cfg(target_os = "macos")]
@@ -1260,7 +9897,474 @@ pub fn run() {
                 }
                 api.prevent_close();
             }
+            WindowEvent::ThemeChanged(theme) => apply_tray_icon_theme(window.app_handle(), Some(*theme)),
+            _ => {}
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serializes tests that change the process environment
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    fn env_lock() -> std::sync::MutexGuard<'static, ()> {
+        ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn apply_file_mode_keeps_executable_bit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+
+        apply_file_mode(&script, Some(0o100755)).unwrap();
+        let mode = fs::metadata(&script).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+
+        // No mode from the source leaves the file as created
+        let plain = dir.path().join("notes.md");
+        fs::write(&plain, "").unwrap();
+        let before = fs::metadata(&plain).unwrap().permissions().mode();
+        apply_file_mode(&plain, None).unwrap();
+        assert_eq!(fs::metadata(&plain).unwrap().permissions().mode(), before);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn agents_sharing_a_skills_dir_count_once() {
+        let _env = env_lock();
+        let shared = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        fs::create_dir_all(shared.path().join("skills")).unwrap();
+        // A second spelling of the same folder only matches once canonicalized
+        let alias = other.path().join("alias");
+        std::os::unix::fs::symlink(shared.path(), &alias).unwrap();
+        let separate = other.path().join("opencode");
+
+        std::env::set_var("CLAUDE_CONFIG_DIR", shared.path());
+        std::env::set_var("CODEX_HOME", &alias);
+        std::env::set_var("OPENCODE_CONFIG_DIR", &separate);
+        let agents = distinct_by_skills_dir(vec![AgentType::Claude, AgentType::Codex, AgentType::Opencode]);
+        for var in ["CLAUDE_CONFIG_DIR", "CODEX_HOME", "OPENCODE_CONFIG_DIR"] {
+            std::env::remove_var(var);
+        }
+
+        assert_eq!(agents, vec![AgentType::Claude, AgentType::Opencode]);
+    }
+
+    #[test]
+    fn traversal_skill_names_are_refused() {
+        for name in ["../x", "..", "a/b", "a\\b", ".", "", "x/../../y"] {
+            assert!(validate_skill_name(name).is_err(), "{:?} accepted", name);
+        }
+        assert!(validate_skill_name("my-skill").is_ok());
+
+        let _env = env_lock();
+        let config = tempfile::tempdir().unwrap();
+        std::env::set_var("CLAUDE_CONFIG_DIR", config.path());
+        let escaped = resolve_skill_dir(AgentType::Claude, "../x", None);
+        let inside = resolve_skill_dir(AgentType::Claude, "my-skill", None);
+        std::env::remove_var("CLAUDE_CONFIG_DIR");
+
+        assert!(escaped.is_err());
+        assert_eq!(inside.unwrap(), config.path().join("skills").join("my-skill"));
+    }
+
+    #[test]
+    fn namespaced_skills_are_listed_under_their_namespace() {
+        let _env = env_lock();
+        let config = tempfile::tempdir().unwrap();
+        fs::create_dir_all(config.path().join("skills/ns/a")).unwrap();
+        fs::write(config.path().join("skills/ns/a/SKILL.md"), "# A\n").unwrap();
+        fs::create_dir_all(config.path().join("skills/plain")).unwrap();
+        fs::write(config.path().join("skills/plain/SKILL.md"), "# Plain\n").unwrap();
+
+        std::env::set_var("CLAUDE_CONFIG_DIR", config.path());
+        let skills = list_skills_for_agent(AgentType::Claude, None, true);
+        std::env::remove_var("CLAUDE_CONFIG_DIR");
+
+        let listed: Vec<(Option<&str>, &str)> = skills
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|s| (s.namespace.as_deref(), s.name.as_str()))
+            .collect();
+        assert_eq!(listed, [(None, "plain"), (Some("ns"), "a")]);
+    }
+
+    #[test]
+    fn preview_html_escapes_raw_html_and_unsafe_links() {
+        let html = render_markdown_html(
+            "# Use `run_all`\n\nCall my_tool_name.\n<script>alert(1)</script>\n\n\
+             - [docs](docs/a.md)\n- [bad](javascript:alert(1))\n",
+        );
+        assert!(html.contains("<h1>Use <code>run_all</code></h1>"));
+        assert!(html.contains("my_tool_name"));
+        assert!(html.contains("&lt;script&gt;") && !html.contains("<script>"));
+        assert!(html.contains("<a href=\"docs/a.md\">docs</a>"));
+        assert!(html.contains("<a href=\"\">bad</a>") && !html.contains("javascript:"));
+    }
+
+    #[test]
+    fn sidecar_manifests_supply_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill_dir = dir.path().to_path_buf();
+        fs::write(skill_dir.join("SKILL.md"), "# Formatter\n\nFormats things.\n").unwrap();
+        fs::write(skill_dir.join("skill.yaml"), "name: formatter\ndescription: Formats code\n").unwrap();
+
+        let manifest = read_skill_manifest(&skill_dir).unwrap();
+        assert_eq!(extract_skill_name(&manifest, "fallback"), "formatter");
+        assert_eq!(extract_skill_description(&manifest).as_deref(), Some("Formats code"));
+        assert!(frontmatter_warnings(&manifest).is_empty());
+
+        let info = skill_info(&skill_dir, "formatter".to_string(), None);
+        assert_eq!(info.description.as_deref(), Some("Formats code"));
+        assert_eq!(info.token_count, Some(manifest_tokens(&manifest)));
+
+        // Sidecar keys win over the frontmatter, which still fills gaps
+        fs::remove_file(skill_dir.join("skill.yaml")).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "---\nname: old\nlicense: MIT\n---\nBody\n").unwrap();
+        fs::write(skill_dir.join("skill.toml"), "name = \"formatter\"\n").unwrap();
+        let manifest = read_skill_manifest(&skill_dir).unwrap();
+        assert_eq!(extract_skill_name(&manifest, "fallback"), "formatter");
+        assert_eq!(extract_skill_license(&manifest).as_deref(), Some("MIT"));
+        assert_eq!(split_frontmatter(&manifest).1, "Body\n");
+    }
+
+    #[test]
+    fn metadata_is_never_counted() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill_dir = dir.path().to_path_buf();
+        let skill_md = "---\nname: counted\n---\nBody\n";
+        fs::write(skill_dir.join("SKILL.md"), skill_md).unwrap();
+        fs::create_dir(skill_dir.join("scripts")).unwrap();
+        fs::write(skill_dir.join("scripts/run.sh"), "echo hi\n").unwrap();
+        fs::write(skill_dir.join(".metadata.json"), "x".repeat(10_000)).unwrap();
+        fs::write(skill_dir.join(".DS_Store"), "junk").unwrap();
+        fs::write(skill_dir.join("Thumbs.db"), "junk").unwrap();
+        fs::create_dir(skill_dir.join(".git")).unwrap();
+        fs::write(skill_dir.join(".git/HEAD"), "ref").unwrap();
+
+        let mut files: Vec<String> = skill_content_files(&skill_dir).map(|(path, _)| path).collect();
+        files.sort();
+        assert_eq!(files, ["SKILL.md", "scripts/run.sh"]);
+
+        let info = skill_info(&skill_dir, "counted".to_string(), None);
+        assert_eq!(info.token_count, Some(estimate_tokens(skill_md.len() as u64)));
+
+        // A delete preview still reports everything the delete removes
+        let (size, count) = dir_usage(&skill_dir);
+        assert_eq!(count, 6);
+        assert!(size > 10_000);
+    }
+
+    /// Points the agent dirs, data dir and settings at `root` until dropped
+    struct IsolatedHome {
+        _env: std::sync::MutexGuard<'static, ()>,
+    }
+
+    impl IsolatedHome {
+        const VARS: [&'static str; 4] = ["CLAUDE_CONFIG_DIR", "XDG_DATA_HOME", "XDG_CONFIG_HOME", APP_HOME_ENV];
+
+        fn new(root: &Path) -> Self {
+            let env = env_lock();
+            for var in Self::VARS {
+                std::env::set_var(var, root.join(var.to_lowercase()));
+            }
+            IsolatedHome { _env: env }
+        }
+    }
+
+    impl Drop for IsolatedHome {
+        fn drop(&mut self) {
+            for var in Self::VARS {
+                std::env::remove_var(var);
+            }
+        }
+    }
+
+    #[test]
+    fn namespaced_skills_restore_into_their_namespace() {
+        let root = tempfile::tempdir().unwrap();
+        let _home = IsolatedHome::new(root.path());
+        let namespaced = get_skills_root(AgentType::Claude, Some("team")).unwrap().join("lint");
+        let top_level = get_skills_dir(AgentType::Claude).unwrap().join("lint");
+        for dir in [&namespaced, &top_level] {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(dir.join("SKILL.md"), "---\nname: lint\n---\n").unwrap();
+        }
+
+        delete_skill_for_agent(AgentType::Claude, "lint".to_string(), Some("team"), false).unwrap();
+        delete_skill_for_agent(AgentType::Claude, "lint".to_string(), None, false).unwrap();
+        let trash = list_trash().unwrap();
+        assert_eq!(trash.len(), 2);
+        for item in &trash {
+            restore_trash_item(&item.id).unwrap();
+        }
+
+        assert!(namespaced.join("SKILL.md").is_file());
+        assert!(top_level.join("SKILL.md").is_file());
+        let namespaces: std::collections::BTreeSet<_> = trash.iter().map(|i| i.namespace.clone()).collect();
+        assert_eq!(namespaces, [None, Some("team".to_string())].into());
+        assert!(fs::read_dir(get_trash_dir().unwrap()).unwrap().next().is_none());
+        assert!(restore_trash_item("1__claude__../../x").is_err());
+    }
+
+    #[test]
+    fn skill_usage_reads_only_appended_lines() {
+        let root = tempfile::tempdir().unwrap();
+        let _home = IsolatedHome::new(root.path());
+        let log_dir = get_agent_usage_log_dir(AgentType::Claude).unwrap();
+        let transcript = log_dir.join("project").join("session.jsonl");
+        fs::create_dir_all(transcript.parent().unwrap()).unwrap();
+        let call = |timestamp: &str, skill: &str| {
+            format!(
+                "{}\n",
+                serde_json::json!({
+                    "timestamp": timestamp,
+                    "message": {"content": [{"type": "tool_use", "name": "Skill", "input": {"skill": skill}}]},
+                })
+            )
+        };
+
+        fs::write(&transcript, call("2026-01-01T00:00:00Z", "lint") + &call("2026-01-02T00:00:00Z", "team:lint")).unwrap();
+        let usage = read_skill_usage(&log_dir);
+        assert_eq!(usage.get("lint").map(String::as_str), Some("2026-01-01T00:00:00Z"));
+        assert_eq!(usage.get("team:lint").map(String::as_str), Some("2026-01-02T00:00:00Z"));
+
+        // A partial trailing line waits until it's complete
+        let mut file = fs::OpenOptions::new().append(true).open(&transcript).unwrap();
+        let line = call("2026-01-03T00:00:00Z", "lint");
+        file.write_all(&line.as_bytes()[..10]).unwrap();
+        assert_eq!(read_skill_usage(&log_dir)["lint"], "2026-01-01T00:00:00Z");
+        file.write_all(&line.as_bytes()[10..]).unwrap();
+        assert_eq!(read_skill_usage(&log_dir)["lint"], "2026-01-03T00:00:00Z");
+
+        let cache: SkillUsageCache = serde_json::from_slice(&fs::read(get_app_data_dir().unwrap().join(SKILL_USAGE_CACHE_FILE)).unwrap()).unwrap();
+        assert_eq!(cache.transcripts.values().next().unwrap().read_to, fs::metadata(&transcript).unwrap().len());
+    }
+
+    #[test]
+    fn skill_name_keys_in_priority_order() {
+        let keys: Vec<String> = DEFAULT_NAME_KEYS.iter().map(|k| k.to_string()).collect();
+        let fallback = "https://example.com/skills/from-url.md";
+        let cases = [
+            ("---\nname: by-name\n---\n", "by-name"),
+            ("---\ntitle: By Title\n---\n", "By Title"),
+            ("---\nid: 42\n---\n", "42"),
+            ("---\nid: by-id\ntitle: by-title\nname: by-name\n---\n", "by-name"),
+            ("---\nid: by-id\ntitle: by-title\n---\n", "by-title"),
+            ("---\nname: \"quoted: name\"\n---\n", "quoted: name"),
+            ("---\nname: >-\n  folded\n  name\n---\n", "folded name"),
+            ("---\nname: \"\"\ntitle: not-empty\n---\n", "not-empty"),
+            // None of the keys, or no frontmatter at all, uses the fallback
+            ("---\ndescription: nameless\n---\n", "from-url"),
+            ("# Just a heading\n", "from-url"),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(extract_skill_name_by(content, fallback, &keys), expected, "{:?}", content);
+        }
+
+        let custom = vec!["id".to_string()];
+        assert_eq!(extract_skill_name_by("---\nname: n\nid: i\n---\n", fallback, &custom), "i");
+        assert_eq!(extract_skill_name_by("---\nname: n\n---\n", "bundle.zip", &custom), "bundle");
+    }
+
+    #[test]
+    fn malformed_mcp_servers_is_reported_and_reset() {
+        let _env = env_lock();
+        let config_dir = tempfile::tempdir().unwrap();
+        std::env::set_var("CLAUDE_CONFIG_DIR", config_dir.path());
+        let config_path = get_mcp_config_path(AgentType::Claude).unwrap();
+        let request = || -> AddMcpServerRequest {
+            serde_json::from_value(serde_json::json!({ "name": "fs", "transport": "stdio", "command": "npx" })).unwrap()
+        };
+
+        fs::write(&config_path, r#"{"theme": "dark", "mcpServers": ["fs"]}"#).unwrap();
+        let listed = read_mcp_servers(AgentType::Claude, None);
+        let added = write_mcp_server(AgentType::Claude, request(), None);
+        let removed = delete_mcp_server(AgentType::Claude, "fs");
+        let toggled = set_mcp_server_disabled(AgentType::Claude, "fs", true);
+        let untouched = fs::read_to_string(&config_path).unwrap();
+        let reset = reset_malformed_mcp_servers(AgentType::Claude);
+        let after_reset = read_mcp_config(&config_path).unwrap();
+        let backup = fs::read_to_string(config_path.with_extension("json.bak"));
+        let reset_again = reset_malformed_mcp_servers(AgentType::Claude);
+
+        // `null` reads as no servers and is filled in on the first add
+        fs::write(&config_path, r#"{"mcpServers": null}"#).unwrap();
+        let listed_null = read_mcp_servers(AgentType::Claude, None);
+        let added_null = write_mcp_server(AgentType::Claude, request(), None);
+        let listed_after_add = read_mcp_servers(AgentType::Claude, None);
+        std::env::remove_var("CLAUDE_CONFIG_DIR");
+
+        for result in [listed.map(|_| ()), added.map(|_| ()), removed, toggled] {
+            let error = result.unwrap_err();
+            assert!(error.contains("is an array instead of an object"), "{}", error);
+        }
+        assert!(untouched.contains(r#""mcpServers": ["fs"]"#));
+        reset.unwrap();
+        assert_eq!(after_reset["mcpServers"], serde_json::json!({}));
+        assert_eq!(after_reset["theme"], "dark");
+        assert!(backup.unwrap().contains(r#"["fs"]"#));
+        assert!(reset_again.is_err());
+
+        assert!(listed_null.unwrap().is_empty());
+        added_null.unwrap();
+        assert_eq!(listed_after_add.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn app_home_precedence() {
+        let _env = env_lock();
+        let vars = [APP_HOME_ENV, "HOME", "USERPROFILE"];
+        let saved: Vec<_> = vars.iter().map(std::env::var_os).collect();
+        for var in vars {
+            std::env::remove_var(var);
+        }
+        let no_os_home = || None;
+
+        let cleared = resolve_app_home(no_os_home);
+        std::env::set_var("USERPROFILE", "/users/profile");
+        let from_userprofile = resolve_app_home(no_os_home);
+        std::env::set_var("HOME", "/home/env");
+        let from_home = resolve_app_home(no_os_home);
+        let from_os = resolve_app_home(|| Some(PathBuf::from("/home/os")));
+        let relative_os = resolve_app_home(|| Some(PathBuf::from("relative")));
+        std::env::set_var(APP_HOME_ENV, "relative/override");
+        let relative_override = resolve_app_home(no_os_home);
+        std::env::set_var(APP_HOME_ENV, "/custom");
+        let from_override = resolve_app_home(|| Some(PathBuf::from("/home/os")));
+        let app = app_home();
+
+        for (var, value) in vars.iter().zip(saved) {
+            match value {
+                Some(value) => std::env::set_var(var, value),
+                None => std::env::remove_var(var),
+            }
+        }
+
+        assert!(matches!(cleared, Err(NoHomeError)));
+        assert!(String::from(cleared.unwrap_err()).contains(APP_HOME_ENV));
+        assert_eq!(from_userprofile.unwrap(), PathBuf::from("/users/profile"));
+        assert_eq!(from_home.unwrap(), PathBuf::from("/home/env"));
+        assert_eq!(from_os.unwrap(), PathBuf::from("/home/os"));
+        assert_eq!(relative_os.unwrap(), PathBuf::from("/home/env"));
+        assert_eq!(relative_override.unwrap(), PathBuf::from("/home/env"));
+        assert_eq!(from_override.unwrap(), PathBuf::from("/custom"));
+        assert_eq!(app.unwrap(), PathBuf::from("/custom"));
+    }
+
+    #[test]
+    fn mcp_servers_are_written_in_the_requested_order() {
+        let _env = env_lock();
+        let config_dir = tempfile::tempdir().unwrap();
+        std::env::set_var("CLAUDE_CONFIG_DIR", config_dir.path());
+        let config_path = get_mcp_config_path(AgentType::Claude).unwrap();
+        fs::write(
+            &config_path,
+            r#"{"mcpServers": {"a": {"command": "a"}, "b": {"url": "https://b"}, "c": {"command": "c"}}, "theme": "dark"}"#,
+        )
+        .unwrap();
+        let order = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        let missing = write_mcp_server_order(AgentType::Claude, &order(&["c", "a"]));
+        let repeated = write_mcp_server_order(AgentType::Claude, &order(&["c", "a", "a", "b"]));
+        let unchanged = fs::read_to_string(&config_path).unwrap();
+        let reordered = write_mcp_server_order(AgentType::Claude, &order(&["c", "a", "b"]));
+        let written = fs::read_to_string(&config_path).unwrap();
+        let listed = read_mcp_servers(AgentType::Claude, None);
+        std::env::remove_var("CLAUDE_CONFIG_DIR");
+
+        assert!(missing.unwrap_err().contains("missing: b"));
+        assert!(repeated.unwrap_err().contains("repeated server: a"));
+        assert!(unchanged.find("\"a\"") < unchanged.find("\"c\""));
+        reordered.unwrap();
+        let position = |key: &str| written.find(&format!("\"{}\":", key)).unwrap();
+        assert!(position("c") < position("a") && position("a") < position("b"));
+        assert!(position("mcpServers") < position("theme"));
+        let names: Vec<String> = listed.unwrap().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn github_shorthand_refs() {
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        // (input, expected owner, repo, path and ref)
+        type Parsed<'a> = Option<(&'a str, &'a str, &'a str, Option<&'a str>)>;
+        let cases: &[(&str, Parsed)] = &[
+            ("owner/repo", Some(("owner", "repo", "", None))),
+            ("owner/repo/skills/foo", Some(("owner", "repo", "skills/foo", None))),
+            ("owner/repo@v1.2.0", Some(("owner", "repo", "", Some("v1.2.0")))),
+            ("owner/repo/skills/foo@feature/new-ui", Some(("owner", "repo", "skills/foo", Some("feature/new-ui")))),
+            ("https://github.com/owner/repo", None),
+            ("owner", None),
+            ("owner/repo@", None),
+            ("owner/repo/../x", None),
+            ("./owner/repo", None),
+        ];
+        for (input, expected) in cases {
+            let parsed = parse_github_shorthand(input);
+            let parsed = parsed.as_ref().map(|s| (s.owner.as_str(), s.repo.as_str(), s.path.as_str(), s.reference.as_deref()));
+            assert_eq!(parsed, *expected, "{}", input);
+        }
+
+        let with_sha = parse_github_shorthand(&format!("owner/repo@{}", sha)).unwrap();
+        assert_eq!(with_sha.reference.as_deref(), Some(sha));
+        assert!(parse_github_repo_url("https://github.com/owner/repo").is_some());
+        assert!(parse_github_repo_url("https://github.com/owner/repo/tree/main").is_none());
+    }
+
+    #[test]
+    fn github_tree_refs() {
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        // (after `/tree/`, ref GitHub confirms, expected ref, expected path)
+        let cases = [
+            ("v1.2.0/skills/foo", Some("v1.2.0"), "v1.2.0", "skills/foo"),
+            ("v1.2.0", None, "v1.2.0", ""),
+            ("feature/new-ui/skills/foo", Some("feature/new-ui"), "feature/new-ui", "skills/foo"),
+            ("feature/new-ui/skills/foo", None, "feature", "new-ui/skills/foo"),
+            ("main/", None, "main", ""),
+        ];
+        for (rest, confirmed, reference, path) in cases {
+            assert_eq!(
+                split_github_tree_ref(rest, confirmed),
+                (reference.to_string(), path.to_string()),
+                "{}",
+                rest
+            );
+        }
+
+        let sha_rest = format!("{}/skills/foo", sha);
+        assert!(github_tree_ref_candidates(&sha_rest).is_empty());
+        assert_eq!(split_github_tree_ref(&sha_rest, None), (sha.to_string(), "skills/foo".to_string()));
+        assert!(github_tree_ref_candidates("v1.2.0").is_empty());
+        assert_eq!(
+            github_tree_ref_candidates("feature/new-ui/skills"),
+            vec!["feature", "feature/new-ui", "feature/new-ui/skills"]
+        );
+    }
+
+    #[test]
+    fn zip_installs_refuse_what_preview_flags() {
+        let zip_of = |name: &str, content: &[u8]| {
+            let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+            zip.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+            zip.write_all(content).unwrap();
+            zip.finish().unwrap().into_inner()
+        };
+        let collect = |data: Vec<u8>| collect_zip_files(&mut zip::ZipArchive::new(Cursor::new(data)).unwrap(), "", &mut 0);
+
+        let files = collect(zip_of("SKILL.md", b"---\nname: ok\n---\n")).unwrap();
+        assert_eq!(files[0].path, "SKILL.md");
+        assert!(collect(zip_of("../SKILL.md", b"escape")).err().unwrap().contains("escapes"));
+        assert!(collect(zip_of("bomb.bin", &vec![0; 1024 * 1024])).err().unwrap().contains("compression ratio"));
+    }
+}
@@ -1,31 +1,17 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { ChevronDown } from "lucide-react";
-import type { Tab, AgentType, AgentInfo, SkillInfo, McpServerInfo } from "./types";
+import type { Tab, AgentType, AgentInfo, SkillInfo, SkillList, McpServerInfo, McpServerList, WhatsNewEvent } from "./types";
 import SkillsPanel from "./components/SkillsPanel";
 import MCPPanel from "./components/MCPPanel";
 
-const AGENTS: { id: AgentType; name: string; icon: string }[] = [
-  { id: "all", name: "All Agents", icon: "*" },
-  { id: "claude", name: "Claude Code", icon: "C" },
-  { id: "gemini", name: "Gemini CLI", icon: "G" },
-  { id: "codex", name: "Codex CLI", icon: "X" },
-  { id: "opencode", name: "OpenCode", icon: "O" },
-  { id: "kiro", name: "Kiro CLI", icon: "K" },
-  { id: "antigravity", name: "Antigravity", icon: "A" },
-  { id: "codebuddy", name: "CodeBuddy", icon: "B" },
-  { id: "cursor", name: "Cursor", icon: "U" },
-  { id: "kimi", name: "Kimi CLI", icon: "I" },
-  { id: "moltbot", name: "Moltbot", icon: "M" },
-  { id: "qoder", name: "Qoder", icon: "D" },
-  { id: "qwen", name: "Qwen Code", icon: "Q" },
-  { id: "zencoder", name: "Zencoder", icon: "Z" },
-];
+// "All" isn't a real agent, so list_agents doesn't return it
+const ALL_AGENTS = { id: "all" as AgentType, name: "All Agents", monogram: "*" };
 
 function App() {
   const [activeTab, setActiveTab] = useState<Tab>("skills");
   const [agent, setAgent] = useState<AgentType>("claude");
-  const [agentInfo, setAgentInfo] = useState<AgentInfo | null>(null);
+  const [agentList, setAgentList] = useState<AgentInfo[]>([]);
   const [skills, setSkills] = useState<SkillInfo[]>([]);
   const [mcpServers, setMcpServers] = useState<McpServerInfo[]>([]);
   const [toast, setToast] = useState<{ message: string; type: "success" | "error" } | null>(null);
@@ -38,39 +24,53 @@ function App() {
 
   const loadAgentInfo = useCallback(async () => {
     try {
-      const agents = await invoke<AgentInfo[]>("list_agents");
-      const info = agents.find(a => a.id === agent);
-      setAgentInfo(info || null);
+      setAgentList(await invoke<AgentInfo[]>("list_agents"));
     } catch (e) {
       console.error("Failed to load agent info:", e);
     }
-  }, [agent]);
+  }, []);
+
+  const agents = [ALL_AGENTS, ...agentList];
+  const agentInfo = agentList.find(a => a.id === agent) ?? null;
 
   const loadSkills = useCallback(async () => {
     try {
-      const data = await invoke<SkillInfo[]>("list_skills", { agent });
-      setSkills(data);
+      const data = await invoke<SkillList>("list_skills", { agent });
+      setSkills(data.skills);
+      if (data.errors.length > 0) {
+        const names = data.errors.map(e => agentList.find(a => a.id === e.agent)?.name ?? e.agent);
+        showToast(`Couldn't read skills for ${names.join(", ")}`, "error");
+      }
     } catch (e) {
       console.error("Failed to load skills:", e);
     }
-  }, [agent]);
+  }, [agent, agentList, showToast]);
 
   const loadMcpServers = useCallback(async () => {
     try {
-      const data = await invoke<McpServerInfo[]>("list_mcp_servers", { agent });
-      setMcpServers(data);
+      const data = await invoke<McpServerList>("list_mcp_servers", { agent });
+      setMcpServers(data.servers);
+      // Only All listings have errors, and All has no MCP tab to show them on
+      for (const e of data.errors) console.warn(`MCP servers for ${e.agent}:`, e.error);
     } catch (e) {
       console.error("Failed to load MCP servers:", e);
     }
   }, [agent]);
 
+  // Pulled once mounted since the backend checks before anything listens
+  useEffect(() => {
+    invoke<WhatsNewEvent | null>("take_whats_new")
+      .then((update) => update && showToast(`Updated from ${update.previous_version} to ${update.version}`))
+      .catch((e) => console.error("Failed to check for an update:", e));
+  }, [showToast]);
+
   useEffect(() => {
     loadAgentInfo();
     loadSkills();
     loadMcpServers();
   }, [loadAgentInfo, loadSkills, loadMcpServers]);
 
-  const currentAgent = AGENTS.find(a => a.id === agent) || AGENTS[0];
+  const currentAgent = agents.find(a => a.id === agent) || ALL_AGENTS;
   // "all" agent doesn't show MCP tab (too complex to manage MCP for all agents)
   const hasMcp = agent !== "all" && (agentInfo?.has_mcp ?? agent === "claude");
 
@@ -79,13 +79,13 @@ function App() {
       <header className="header">
         <div className="header-left">
           <div className="agent-selector" onClick={() => setShowAgentDropdown(!showAgentDropdown)}>
-            <div className="agent-icon">{currentAgent.icon}</div>
+            <div className="agent-icon">{currentAgent.monogram}</div>
             <span className="agent-name">{currentAgent.name}</span>
             <ChevronDown size={14} className={`agent-chevron ${showAgentDropdown ? "open" : ""}`} />
 
             {showAgentDropdown && (
               <div className="agent-dropdown">
-                {AGENTS.map(a => (
+                {agents.map(a => (
                   <div
                     key={a.id}
                     className={`agent-option ${a.id === agent ? "active" : ""}`}
@@ -95,7 +95,7 @@ function App() {
                       setShowAgentDropdown(false);
                     }}
                   >
-                    <div className="agent-icon">{a.icon}</div>
+                    <div className="agent-icon">{a.monogram}</div>
                     <span>{a.name}</span>
                   </div>
                 ))}
@@ -253,8 +253,15 @@ export default function MCPPanel({ agent, servers, onRefresh, showToast }: Props
         }
       }
 
-      await invoke("add_mcp_server", { agent, config: request });
+      const warnings = await invoke<string[]>("add_mcp_server", {
+        agent,
+        config: request,
+        replace: editingServer !== null,
+      });
       showToast(editingServer ? `Updated ${request.name}` : `Added ${request.name}`);
+      if (warnings.length > 0) {
+        showToast(warnings.join("\n"), "error");
+      }
       setShowDialog(false);
       resetForm();
       onRefresh();
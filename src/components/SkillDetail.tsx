@@ -1,7 +1,7 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { ArrowLeft, FolderOpen, ExternalLink, File, Folder, ChevronRight, ChevronDown } from "lucide-react";
-import type { AgentType, SkillInfo, SkillMetadata, FileItem } from "../types";
+import type { AgentType, SkillInfo, SkillMetadata, SkillPreview, FileItem } from "../types";
 
 interface Props {
   agent: AgentType;
@@ -125,7 +125,7 @@ function formatDate(dateStr: string): string {
 
 export default function SkillDetail({ agent, skill, onBack, showToast }: Props) {
   const [metadata, setMetadata] = useState<SkillMetadata | null>(null);
-  const [skillContent, setSkillContent] = useState<string>("");
+  const [skillHtml, setSkillHtml] = useState<string>("");
   const [files, setFiles] = useState<FileItem[]>([]);
   const [selectedFile, setSelectedFile] = useState<string | null>(null);
   const [fileContent, setFileContent] = useState<string>("");
@@ -135,13 +135,19 @@ export default function SkillDetail({ agent, skill, onBack, showToast }: Props)
   const loadData = useCallback(async () => {
     setLoadingContent(true);
     try {
-      const [meta, content, fileList] = await Promise.all([
-        invoke<SkillMetadata | null>("get_skill_metadata", { agent, name: skill.name }),
-        invoke<string>("get_skill_content", { agent, name: skill.name }),
+      const namespace = skill.namespace;
+      const [preview, fileList] = await Promise.all([
+        invoke<SkillPreview>("get_skill_preview", { agent, name: skill.name, namespace }),
         invoke<FileItem[]>("list_skill_files", { agent, name: skill.name, subpath: null }),
       ]);
-      setMetadata(meta);
-      setSkillContent(content);
+      const { content } = preview;
+      setMetadata(preview.metadata);
+      setSkillHtml(preview.html);
+      if (content.truncated) {
+        showToast("SKILL.md is too large to show in full", "error");
+      } else if (content.lossy) {
+        showToast(`SKILL.md has bytes that aren't valid ${content.encoding}`, "error");
+      }
       setFiles(fileList);
     } catch (e) {
       showToast(`Failed to load skill details: ${e}`, "error");
@@ -197,6 +203,7 @@ export default function SkillDetail({ agent, skill, onBack, showToast }: Props)
           <div className="skill-detail-meta">
             {skill.token_count && <span>{formatTokens(skill.token_count)}</span>}
             {metadata?.author && <span>by {metadata.author}</span>}
+            {metadata?.license && <span>{metadata.license}</span>}
             {metadata?.installed_at && <span>Installed: {formatDate(metadata.installed_at)}</span>}
           </div>
         </div>
@@ -240,7 +247,8 @@ export default function SkillDetail({ agent, skill, onBack, showToast }: Props)
           <div className="skill-detail-loading">Loading...</div>
         ) : activeTab === "content" ? (
           <div className="skill-detail-markdown">
-            <pre>{skillContent}</pre>
+            {/* Rendered by get_skill_preview, which escapes the skill's own HTML */}
+            <div dangerouslySetInnerHTML={{ __html: skillHtml }} />
           </div>
         ) : (
           <div className="skill-detail-files">
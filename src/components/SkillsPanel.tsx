@@ -1,7 +1,19 @@
 import { useState, useRef, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { FolderOpen, Trash2, Search, Download } from "lucide-react";
-import type { AgentType, SkillInfo, SearchSkill } from "../types";
+import type {
+  AgentType,
+  SkillInfo,
+  SearchSkill,
+  SearchResponse,
+  SearchError,
+  InstallResult,
+  InstallTarget,
+  DeletePreview,
+  DeleteResult,
+  ConflictPolicy,
+  GlobInstallResult,
+} from "../types";
 import SkillDetail from "./SkillDetail";
 
 interface Props {
@@ -11,6 +23,16 @@ interface Props {
   showToast: (message: string, type?: "success" | "error") => void;
 }
 
+// Same wording the install commands used to return
+function formatInstallResults(results: InstallResult[]): string {
+  const installed = results.filter((r) => r.status === "installed" || r.status === "replaced");
+  const failed = results.filter((r) => r.status === "failed");
+  const failures = failed.length > 0 ? ` (failed for ${failed.map((r) => r.agent).join(", ")})` : "";
+  if (installed.length === 0) return `Nothing was installed${failures}`;
+  if (installed.length === 1) return `Installed: ${installed[0].name}${failures}`;
+  return `Installed ${installed[0].name} to ${installed.length} agents${failures}`;
+}
+
 export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Props) {
   const [selectedSkill, setSelectedSkill] = useState<SkillInfo | null>(null);
   const [showDialog, setShowDialog] = useState(false);
@@ -19,29 +41,86 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
   const [loading, setLoading] = useState(false);
   const [dragActive, setDragActive] = useState(false);
   const [deleteTarget, setDeleteTarget] = useState<string | null>(null);
+  const [deletePreview, setDeletePreview] = useState<DeletePreview[]>([]);
   const [searchQuery, setSearchQuery] = useState("");
   const [searchResults, setSearchResults] = useState<SearchSkill[]>([]);
   const [searching, setSearching] = useState(false);
   const [installingSlug, setInstallingSlug] = useState<string | null>(null);
+  const [tokenEstimates, setTokenEstimates] = useState<Record<string, number>>({});
   const fileInputRef = useRef<HTMLInputElement>(null);
   const searchTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
+  const latestQueryRef = useRef("");
+
+  // Installs to all agents check for differing copies first, so one answer
+  // applies to every agent
+  const runInstall = (command: string, args: Record<string, unknown>) =>
+    withConflictPrompt((on_conflict) => invoke<InstallResult[]>(command, { ...args, agent, options: { on_conflict } }));
+
+  const withConflictPrompt = async (install: (on_conflict: ConflictPolicy) => Promise<InstallResult[]>) => {
+    const results = await install(agent === "all" ? "ask" : "replace");
+    const conflicts = results.filter((r) => r.status === "conflict");
+    if (conflicts.length === 0) return results;
+
+    const agents = conflicts.map((r) => r.agent).join(", ");
+    const replace = confirm(
+      `A different "${conflicts[0].name}" is already installed for ${agents}. ` +
+        "Replace it everywhere? Cancel keeps the existing copies."
+    );
+    return install(replace ? "replace" : "skip");
+  };
+
+  // Skills in a ZIP install one by one; those that failed come back
+  // alongside the rest
+  const runZipInstall = async (args: Record<string, unknown>) => {
+    let failed: GlobInstallResult[] = [];
+    const results = await withConflictPrompt(async (on_conflict) => {
+      const skills = await invoke<GlobInstallResult[]>("install_skill_from_zip", {
+        ...args,
+        agent,
+        options: { on_conflict },
+      });
+      failed = skills.filter((s) => s.error);
+      return skills.flatMap((s) => s.results);
+    });
+    return { results, failed };
+  };
+
+  // Lists every folder an All install writes to before anything is fetched
+  const confirmAllTargets = async (name: string) => {
+    const targets = await invoke<InstallTarget[]>("resolve_all_targets", { skillName: name });
+    const lines = targets.map((t) => {
+      const flag = t.pinned ? " (pinned, skipped)" : t.exists ? " (exists)" : "";
+      return `• ${t.agent}: ${t.path}${flag}`;
+    });
+    return confirm(`Install "${name}" to ${targets.length} agents?\n\n${lines.join("\n")}`);
+  };
 
   const doSearch = useCallback(async (query: string) => {
+    latestQueryRef.current = query;
     if (!query.trim()) {
       setSearchResults([]);
       return;
     }
     setSearching(true);
     try {
-      const results = await invoke<SearchSkill[]>("search_skills", { query });
-      setSearchResults(results);
+      const response = await invoke<SearchResponse>("search_skills", { query });
+      // A newer search has started since; its results win
+      if (response.query !== latestQueryRef.current) return;
+      setSearchResults(response.results);
     } catch (e) {
+      if (query !== latestQueryRef.current) return;
       console.error("Search failed:", e);
+      const error = e as SearchError;
+      if (error.kind === "rate_limited") {
+        showToast(`Search is rate limited, try again in ${error.retry_after_secs}s`, "error");
+      } else if (error.kind === "service_unavailable") {
+        showToast(`skills.sh is unavailable, try again in ${error.retry_after_secs}s`, "error");
+      }
       setSearchResults([]);
     } finally {
-      setSearching(false);
+      if (query === latestQueryRef.current) setSearching(false);
     }
-  }, []);
+  }, [showToast]);
 
   useEffect(() => {
     if (searchTimeoutRef.current) {
@@ -53,6 +132,7 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
         doSearch(searchQuery);
       }, delay);
     } else {
+      latestQueryRef.current = "";
       setSearchResults([]);
     }
     return () => {
@@ -63,16 +143,15 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
   }, [searchQuery, doSearch]);
 
   const handleInstallFromSearch = async (skill: SearchSkill) => {
-    const source = skill.source || skill.slug;
-    if (!source) {
+    if (!skill.source && !skill.slug) {
       showToast("No source available for this skill", "error");
       return;
     }
+    if (agent === "all" && !(await confirmAllTargets(skill.name))) return;
     setInstallingSlug(skill.slug);
     try {
-      const skillUrl = `https://github.com/${source}`;
-      const result = await invoke<string>("install_skill_from_url", { agent, url: skillUrl });
-      showToast(result);
+      const results = await runInstall("install_skill_by_slug", { slug: skill.slug });
+      showToast(formatInstallResults(results));
       onRefresh();
     } catch (e) {
       showToast(`${e}`, "error");
@@ -81,6 +160,15 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
     }
   };
 
+  const handleEstimateTokens = async (slug: string) => {
+    try {
+      const tokens = await invoke<number>("estimate_search_skill_tokens", { slug });
+      setTokenEstimates((prev) => ({ ...prev, [slug]: tokens }));
+    } catch (e) {
+      showToast(`${e}`, "error");
+    }
+  };
+
   const formatInstalls = (count: number) => {
     if (count >= 1000) return `${(count / 1000).toFixed(1)}k`;
     return String(count);
@@ -92,11 +180,33 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
     return `${count} tokens`;
   };
 
+  const requestDelete = async (name: string) => {
+    setDeleteTarget(name);
+    setDeletePreview([]);
+    try {
+      setDeletePreview(await invoke<DeletePreview[]>("preview_delete", { agent, name }));
+    } catch (e) {
+      console.error("Failed to preview delete:", e);
+    }
+  };
+
+  const formatSize = (bytes: number) => {
+    if (bytes >= 1024 * 1024) return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+    if (bytes >= 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+    return `${bytes} B`;
+  };
+
   const confirmDelete = async () => {
     if (!deleteTarget) return;
     try {
-      await invoke("delete_skill", { agent, name: deleteTarget });
-      showToast(`Deleted ${deleteTarget}`);
+      const results = await invoke<DeleteResult[]>("delete_skill", { agent, name: deleteTarget });
+      const kept = results.filter((r) => r.status !== "deleted");
+      if (kept.length === 0) {
+        showToast(`Deleted ${deleteTarget}`);
+      } else {
+        const reasons = kept.map((r) => `${r.agent}: ${r.status === "skipped" ? "skipped, " : ""}${r.reason}`);
+        showToast(`Not deleted for ${reasons.join("; ")}`, kept.some((r) => r.status === "failed") ? "error" : "success");
+      }
       onRefresh();
     } catch (e) {
       showToast(`Failed to delete: ${e}`, "error");
@@ -117,8 +227,8 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
     if (!url.trim()) return;
     setLoading(true);
     try {
-      const result = await invoke<string>("install_skill_from_url", { agent, url: url.trim() });
-      showToast(result);
+      const results = await runInstall("install_skill_from_url", { url: url.trim() });
+      showToast(formatInstallResults(results));
       setShowDialog(false);
       setUrl("");
       onRefresh();
@@ -133,20 +243,31 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
     setLoading(true);
     try {
       const content = await file.text();
-      const isZip = file.name.endsWith(".zip");
+      const isZip = file.name.endsWith(".zip") || file.name.endsWith(".skill");
+      let results: InstallResult[];
 
       if (isZip) {
         const arrayBuffer = await file.arrayBuffer();
         const base64 = btoa(String.fromCharCode(...new Uint8Array(arrayBuffer)));
-        await invoke("install_skill_from_zip", { agent, zipBase64: base64, source: file.name });
+        const installed = await runZipInstall({ zipBase64: base64, source: file.name });
+        if (installed.failed.length > 0) {
+          const errors = installed.failed.map((f) => `${f.path}: ${f.error}`).join("; ");
+          showToast(`${formatInstallResults(installed.results)}. Failed: ${errors}`, "error");
+          onRefresh();
+          return;
+        }
+        results = installed.results;
       } else {
-        await invoke("install_skill_from_content", {
-          agent,
-          content,
-          filename: file.name
-        });
+        const installContent = (force: boolean) =>
+          runInstall("install_skill_from_content", { content, filename: file.name, force });
+        try {
+          results = await installContent(false);
+        } catch (e) {
+          if (!`${e}`.startsWith("Not a skill") || !confirm(`${e}\n\nInstall ${file.name} anyway?`)) throw e;
+          results = await installContent(true);
+        }
       }
-      showToast(`Installed from ${file.name}`);
+      showToast(formatInstallResults(results));
       setShowDialog(false);
       onRefresh();
     } catch (e) {
@@ -160,10 +281,10 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
     e.preventDefault();
     setDragActive(false);
     const file = e.dataTransfer.files[0];
-    if (file && (file.name.endsWith(".md") || file.name.endsWith(".zip"))) {
+    if (file && [".md", ".zip", ".skill"].some((ext) => file.name.endsWith(ext))) {
       handleFileSelect(file);
     } else {
-      showToast("Only .md or .zip files allowed", "error");
+      showToast("Only .md, .zip or .skill files allowed", "error");
     }
   };
 
@@ -195,15 +316,18 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
             <div key={skill.name} className="list-item clickable" onClick={() => setSelectedSkill(skill)}>
               <div className="list-item-info">
                 <div className="list-item-name">{skill.name}</div>
+                {skill.description && <div className="list-item-meta">{skill.description}</div>}
                 <div className="list-item-meta">{formatTokens(skill.token_count)}</div>
               </div>
               <div className="list-item-actions" onClick={(e) => e.stopPropagation()}>
                 <button className="btn btn-icon" onClick={() => handleOpenFolder(skill.name)} title="Open folder">
                   <FolderOpen size={16} />
                 </button>
-                <button className="btn btn-icon btn-danger" onClick={() => setDeleteTarget(skill.name)} title="Delete">
-                  <Trash2 size={16} />
-                </button>
+                {!skill.builtin && (
+                  <button className="btn btn-icon btn-danger" onClick={() => requestDelete(skill.name)} title="Delete">
+                    <Trash2 size={16} />
+                  </button>
+                )}
               </div>
             </div>
           ))
@@ -271,6 +395,14 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
                             <div className="search-result-meta">
                               {skill.source && <span className="search-result-source">{skill.source}</span>}
                               <span className="search-result-installs">{formatInstalls(skill.installs)} installs</span>
+                              {skill.license && <span className="search-result-license">{skill.license}</span>}
+                              {(skill.token_estimate ?? tokenEstimates[skill.slug]) != null ? (
+                                <span>{formatTokens(skill.token_estimate ?? tokenEstimates[skill.slug])}</span>
+                              ) : (
+                                <button className="btn-link" onClick={() => handleEstimateTokens(skill.slug)}>
+                                  Size?
+                                </button>
+                              )}
                             </div>
                           </div>
                           <button
@@ -295,7 +427,7 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
                 </div>
               ) : installMode === "url" ? (
                 <div className="form-group">
-                  <label className="form-label">Skill URL or GitHub directory</label>
+                  <label className="form-label">Skill URL, GitHub directory or owner/repo/path@branch</label>
                   <input
                     type="text"
                     className="form-input"
@@ -313,12 +445,12 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
                   onDrop={handleDrop}
                   onClick={() => fileInputRef.current?.click()}
                 >
-                  <p>Drop .md or .zip file here</p>
+                  <p>Drop .md, .zip or .skill file here</p>
                   <p style={{ fontSize: 12, marginTop: 8 }}>or click to browse</p>
                   <input
                     ref={fileInputRef}
                     type="file"
-                    accept=".md,.zip"
+                    accept=".md,.zip,.skill"
                     style={{ display: "none" }}
                     onChange={(e) => e.target.files?.[0] && handleFileSelect(e.target.files[0])}
                   />
@@ -345,6 +477,19 @@ export default function SkillsPanel({ agent, skills, onRefresh, showToast }: Pro
             </div>
             <div className="dialog-body">
               <p>Are you sure you want to delete "<strong>{deleteTarget}</strong>"?</p>
+              <ul className="delete-preview">
+                {deletePreview.filter((p) => p.exists).map((p) => (
+                  <li key={p.agent}>
+                    <code>{p.path}</code>{" "}
+                    {p.is_symlink
+                      ? "(link only)"
+                      : `(${p.file_count} files, ${formatSize(p.size_bytes)})`}
+                    {p.pinned && " pinned, skipped"}
+                    {p.builtin && " built in, skipped"}
+                  </li>
+                ))}
+              </ul>
+              <p>Skill folders are moved to trash and can be restored.</p>
             </div>
             <div className="dialog-footer">
               <button className="btn" onClick={() => setDeleteTarget(null)}>Cancel</button>
@@ -14,9 +14,16 @@ export type AgentType =
   | "qwen"
   | "zencoder";
 
-export interface AgentInfo {
-  id: AgentType;
+export interface AgentMetadata {
   name: string;
+  monogram: string;
+  homepage: string | null;
+  docs_url: string | null;
+  config_format: string | null;
+}
+
+export interface AgentInfo extends AgentMetadata {
+  id: AgentType;
   skills_path: string;
   has_mcp: boolean;
 }
@@ -25,19 +32,111 @@ export interface SkillInfo {
   name: string;
   path: string;
   token_count: number | null;
+  namespace: string | null;
+  description: string | null;
+  builtin: boolean;
+  last_used_at: string | null;
+  favorite: boolean;
+}
+
+export interface SkillContent {
+  content: string;
+  encoding: string;
+  byte_size: number;
+  token_count: number;
+  truncated: boolean;
+  lossy: boolean;
+}
+
+export interface TokenRecountProgress {
+  agent: AgentType;
+  done: number;
+  total: number;
+}
+
+export interface SkillTokenCost {
+  agent: AgentType;
+  name: string;
+  namespace: string | null;
+  tokens: number;
+}
+
+export interface TokenCostSummary {
+  total_tokens: number;
+  skill_count: number;
+  heaviest: SkillTokenCost[];
+  context_window: number;
+  window_fraction: number;
 }
 
+export interface AgentError {
+  agent: AgentType;
+  error: string;
+}
+
+export interface SkillGroups {
+  favorites: SkillInfo[];
+  recent: SkillInfo[];
+  others: SkillInfo[];
+}
+
+export interface SkillList {
+  skills: SkillInfo[];
+  groups: SkillGroups | null;
+  errors: AgentError[];
+}
+
+export type SourceKind =
+  | "direct_url"
+  | "github_dir"
+  | "github_blob"
+  | "gist"
+  | "zip"
+  | "local"
+  | "registry"
+  | "oci";
+
 export interface SkillMetadata {
   name: string;
   description: string | null;
   source: string | null;
   version: string | null;
   author: string | null;
+  license: string | null;
+  source_kind: SourceKind | null;
   installed_at: string;
   updated_at: string;
+  pinned: boolean;
+  content_hash: string | null;
+  notes: string | null;
+  skill_md_only: boolean;
+  entrypoint: string | null;
+  externalized_assets: string[];
+  favorite: boolean;
+}
+
+export interface UpdatePreview {
+  name: string;
+  source: string | null;
+  pinned: boolean;
+  has_local_changes: boolean;
+}
+
+export interface ShareReport {
+  name: string;
+  files: string[];
+  token_count: number;
+  warnings: string[];
+  junk_files: string[];
+  secret_files: string[];
+  possible_secrets: string[];
+  crlf_files: string[];
+  fixed: boolean;
+  export: string | null;
 }
 
 export interface McpServerInfo {
+  agent: AgentType;
   name: string;
   transport: "stdio" | "http";
   disabled: boolean | null;
@@ -48,6 +147,29 @@ export interface McpServerInfo {
   headers: Record<string, string> | null;
 }
 
+export interface McpServerList {
+  servers: McpServerInfo[];
+  errors: AgentError[];
+}
+
+export interface McpRequirements {
+  present: string[];
+  missing: string[];
+  disabled: string[];
+}
+
+export interface McpServerRef {
+  agent: AgentType;
+  name: string;
+}
+
+export interface McpDupGroup {
+  server: McpServerInfo;
+  members: McpServerRef[];
+}
+
+export type McpFilter = "all" | "enabled" | "disabled";
+
 export interface AddMcpServerRequest {
   name: string;
   transport: "stdio" | "http";
@@ -63,6 +185,115 @@ export interface SearchSkill {
   slug: string;
   source: string;
   installs: number;
+  license: string | null;
+  token_estimate: number | null;
+}
+
+export interface SearchResponse {
+  query: string;
+  elapsed_ms: number;
+  results: SearchSkill[];
+}
+
+export type SearchError =
+  | { kind: "rate_limited"; retry_after_secs: number }
+  | { kind: "service_unavailable"; status: number; retry_after_secs: number }
+  | { kind: "other"; message: string };
+
+export type ConflictPolicy = "replace" | "skip" | "ask";
+
+export type InstallStatus = "installed" | "replaced" | "skipped" | "conflict" | "failed";
+
+export interface InstallOptions {
+  namespace?: string;
+  on_conflict?: ConflictPolicy;
+  force?: boolean;
+  skill_md_only?: boolean;
+  entrypoint?: string;
+}
+
+export interface HookOutput {
+  command: string;
+  exit_code: number | null;
+  stdout: string;
+  stderr: string;
+}
+
+export interface SkillEnabledState {
+  name: string;
+  installed: boolean;
+  enabled: boolean;
+}
+
+export interface InstallTarget {
+  agent: AgentType;
+  path: string;
+  exists: boolean;
+  pinned: boolean;
+}
+
+export interface DeletePreview {
+  agent: AgentType;
+  path: string;
+  exists: boolean;
+  size_bytes: number;
+  file_count: number;
+  is_symlink: boolean;
+  pinned: boolean;
+  builtin: boolean;
+}
+
+export type DeleteStatus = "deleted" | "skipped" | "failed";
+
+export interface DeleteResult {
+  agent: AgentType;
+  status: DeleteStatus;
+  reason: string | null;
+}
+
+export interface InstallResult {
+  agent: AgentType;
+  name: string;
+  path: string;
+  status: InstallStatus;
+  files: string[];
+  warnings: string[];
+  hook: HookOutput | null;
+}
+
+export type ActivityKind =
+  | "install"
+  | "discard_install"
+  | "delete"
+  | "clear"
+  | "cleanup"
+  | "rename"
+  | "restore"
+  | "add_mcp_server"
+  | "remove_mcp_server"
+  | "toggle_mcp_server"
+  | "edit_mcp_config"
+  | "import_bundle"
+  | "update_settings"
+  | "link_dev_skill"
+  | "unlink_dev_skill";
+
+export type ActivityResult = { status: "ok" } | { status: "error"; message: string };
+
+export interface ActivityEvent {
+  kind: ActivityKind;
+  agent: AgentType | null;
+  target: string;
+  result: ActivityResult;
+  detail: string | null;
+  timestamp: string;
+}
+
+export interface DevLink {
+  agent: AgentType;
+  name: string;
+  source_dir: string;
+  linked_at: string;
 }
 
 export interface FileItem {
@@ -73,3 +304,109 @@ export interface FileItem {
 }
 
 export type Tab = "skills" | "mcp";
+
+export interface WhatsNewEvent {
+  previous_version: string;
+  version: string;
+}
+
+export type TrayIconTheme = "system" | "light" | "dark" | "colored";
+
+export interface PermissionChange {
+  path: string;
+  from: number;
+  to: number;
+}
+
+export interface FixReport {
+  changed: PermissionChange[];
+  errors: string[];
+  message: string | null;
+}
+
+export interface RemoteHost {
+  name: string;
+  destination: string;
+  port: number | null;
+}
+
+export interface CleanupReport {
+  removed_metadata: string[];
+  removed_empty_dirs: string[];
+  removed_temp: string[];
+  errors: string[];
+}
+
+export interface McpValidation {
+  agent: AgentType;
+  name: string;
+  transport: "stdio" | "http";
+  disabled: boolean;
+  ok: boolean;
+  resolved: string | null;
+  problem: string | null;
+}
+
+export type TokenTier = "small" | "medium" | "large";
+
+export interface SkillBadges {
+  has_mcp_requirement: boolean;
+  has_scripts: boolean;
+  is_pinned: boolean;
+  update_available: boolean | null;
+  is_symlink: boolean;
+  token_tier: TokenTier;
+}
+
+export type JobStatus = "running" | "completed" | "failed" | "cancelled";
+
+export interface JobInfo {
+  id: string;
+  agent: AgentType;
+  source: string;
+  status: JobStatus;
+  started_at: string;
+  finished_at: string | null;
+  files_downloaded: number;
+  results: InstallResult[];
+  error: string | null;
+}
+
+export interface SkillPreview {
+  content: SkillContent;
+  body: string;
+  html: string;
+  frontmatter: Record<string, unknown> | null;
+  files: FileItem[];
+  metadata: SkillMetadata | null;
+}
+
+export interface SkillContentAnalysis {
+  full_tokens: number;
+  body_tokens: number;
+  frontmatter_tokens: number;
+  has_frontmatter: boolean;
+}
+
+export interface GlobInstallResult {
+  path: string;
+  results: InstallResult[];
+  error: string | null;
+}
+
+export interface GlobInstallProgress {
+  agent: AgentType;
+  path: string;
+  done: number;
+  total: number;
+}
+
+export interface PreflightReport {
+  data_dir: string;
+  data_dir_writable: boolean;
+  data_dir_error: string | null;
+  skills_dir: string;
+  skills_dir_writable: boolean;
+  network_reachable: boolean;
+  ok: boolean;
+}